@@ -0,0 +1,246 @@
+//! A cache of interned atoms, kept alongside the connection whose atoms it holds.
+//!
+//! Atoms are interned lazily, the first time they are looked up. Names registered beforehand with
+//! [`AtomCache::register`] are interned along that first lookup, so that an application pays a
+//! single round-trip for all of its atoms.
+//!
+//! Atoms are only meaningful on the connection they were interned on, a cache must not be used
+//! with any other connection.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use xcb::x;
+
+use util::reply;
+
+#[derive(Default)]
+struct Inner {
+    atoms: HashMap<Vec<u8>, x::Atom>,
+    pending: Vec<Vec<u8>>,
+}
+
+impl Inner {
+    fn register(&mut self, name: &[u8]) {
+        if !self.atoms.contains_key(name) && !self.pending.iter().any(|p| p == name) {
+            self.pending.push(name.to_vec());
+        }
+    }
+
+    /// The atoms of `names`, if all of them are cached.
+    fn lookup(&self, names: &[&[u8]]) -> Option<Vec<x::Atom>> {
+        names
+            .iter()
+            .map(|name| self.atoms.get(*name).copied())
+            .collect()
+    }
+
+    /// The names to intern for a lookup of `names`: the registered ones, followed by the ones of
+    /// `names` that are not cached.
+    fn take_missing(&mut self, names: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut missing = std::mem::take(&mut self.pending);
+
+        for name in names {
+            if !self.atoms.contains_key(*name) && !missing.iter().any(|m| m == name) {
+                missing.push(name.to_vec());
+            }
+        }
+
+        missing
+    }
+
+    /// Keeps the atoms interned before a batch failed, and registers the names left to intern
+    /// them along the next lookup.
+    fn recover<I: IntoIterator<Item = Vec<u8>>>(
+        &mut self,
+        interned: Vec<(Vec<u8>, x::Atom)>,
+        names_left: I,
+    ) {
+        self.atoms.extend(interned);
+
+        for name in names_left {
+            self.register(&name);
+        }
+    }
+}
+
+/// Interned atoms of a connection, safe to share between threads.
+#[derive(Default)]
+pub struct AtomCache {
+    inner: Mutex<Inner>,
+}
+
+impl AtomCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers names to intern along the next lookup of an atom that is not cached yet.
+    pub fn register(&self, names: &[&[u8]]) {
+        let mut inner = self.inner.lock().unwrap();
+
+        for name in names {
+            inner.register(name);
+        }
+    }
+
+    /// The atom of `name`, if it was interned already.
+    pub fn cached(&self, name: &[u8]) -> Option<x::Atom> {
+        self.inner.lock().unwrap().atoms.get(name).copied()
+    }
+
+    /// The atom of `name`, interned along the registered names if it is not cached.
+    pub fn get(&self, connection: &xcb::Connection, name: &[u8]) -> xcb::Result<x::Atom> {
+        self.get_all(connection, &[name]).map(|atoms| atoms[0])
+    }
+
+    /// The atoms of `names`, interned in a single batch along the registered names if any of them
+    /// is not cached.
+    pub fn get_all(
+        &self,
+        connection: &xcb::Connection,
+        names: &[&[u8]],
+    ) -> xcb::Result<Vec<x::Atom>> {
+        let missing = {
+            let mut inner = self.inner.lock().unwrap();
+
+            if let Some(atoms) = inner.lookup(names) {
+                return Ok(atoms);
+            }
+
+            inner.take_missing(names)
+        };
+
+        // The lock is not held during the round-trip, another thread interning the same names
+        // gets the same atoms anyway.
+        let cookies = missing
+            .iter()
+            .map(|name| {
+                connection.send_request(&x::InternAtom {
+                    only_if_exists: false,
+                    name,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut interned = Vec::with_capacity(missing.len());
+        let mut names_left = missing.into_iter();
+
+        let mut cookies = cookies.into_iter();
+
+        while let Some(cookie) = cookies.next() {
+            let name = names_left.next().unwrap();
+
+            match connection.wait_for_reply(cookie) {
+                Ok(reply) => interned.push((name, reply.atom())),
+                Err(err) => {
+                    for cookie in cookies {
+                        reply::discard(connection, cookie);
+                    }
+
+                    self.inner
+                        .lock()
+                        .unwrap()
+                        .recover(interned, Some(name).into_iter().chain(names_left));
+
+                    return Err(err);
+                }
+            }
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.atoms.extend(interned);
+
+        Ok(names.iter().map(|name| inner.atoms[*name]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xcb::XidNew;
+
+    use super::*;
+
+    fn atom(id: u32) -> x::Atom {
+        x::Atom::new(id)
+    }
+
+    #[test]
+    fn register() {
+        let cache = AtomCache::new();
+
+        cache.register(&[b"UTF8_STRING", b"CLIPBOARD"]);
+        cache.register(&[b"CLIPBOARD", b"TARGETS"]);
+
+        let inner = cache.inner.lock().unwrap();
+
+        assert_eq!(
+            inner.pending,
+            [&b"UTF8_STRING"[..], b"CLIPBOARD", b"TARGETS"]
+        );
+        assert!(inner.atoms.is_empty());
+    }
+
+    #[test]
+    fn register_skips_cached_names() {
+        let cache = AtomCache::new();
+
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .atoms
+            .insert(b"CLIPBOARD".to_vec(), atom(400));
+        cache.register(&[b"CLIPBOARD", b"TARGETS"]);
+
+        assert_eq!(cache.inner.lock().unwrap().pending, [b"TARGETS"]);
+        assert_eq!(cache.cached(b"CLIPBOARD"), Some(atom(400)));
+        assert_eq!(cache.cached(b"TARGETS"), None);
+    }
+
+    #[test]
+    fn batch_pending_names() {
+        let mut inner = Inner::default();
+
+        inner.atoms.insert(b"CLIPBOARD".to_vec(), atom(400));
+        inner.register(b"UTF8_STRING");
+        inner.register(b"TARGETS");
+
+        assert_eq!(inner.lookup(&[b"CLIPBOARD"]), Some(vec![atom(400)]));
+        assert_eq!(inner.lookup(&[b"CLIPBOARD", b"TARGETS"]), None);
+
+        // The registered names go first, and names are interned once.
+        assert_eq!(
+            inner.take_missing(&[b"CLIPBOARD", b"TARGETS", b"MULTIPLE", b"MULTIPLE"]),
+            [&b"UTF8_STRING"[..], b"TARGETS", b"MULTIPLE"]
+        );
+        assert!(inner.pending.is_empty());
+        assert!(inner.take_missing(&[b"CLIPBOARD"]).is_empty());
+    }
+
+    #[test]
+    fn recover_from_errors() {
+        let mut inner = Inner::default();
+
+        inner.register(b"UTF8_STRING");
+        inner.register(b"TARGETS");
+
+        let missing = inner.take_missing(&[b"MULTIPLE"]);
+
+        // Registered by another thread while the batch was being interned.
+        inner.register(b"MULTIPLE");
+        inner.register(b"INCR");
+
+        let mut names = missing.into_iter();
+        let interned = vec![(names.next().unwrap(), atom(401))];
+
+        inner.recover(interned, names);
+
+        assert_eq!(inner.lookup(&[b"UTF8_STRING"]), Some(vec![atom(401)]));
+        assert_eq!(inner.pending, [&b"MULTIPLE"[..], b"INCR", b"TARGETS"]);
+        assert_eq!(
+            inner.take_missing(&[b"UTF8_STRING"]),
+            [&b"MULTIPLE"[..], b"INCR", b"TARGETS"]
+        );
+    }
+}
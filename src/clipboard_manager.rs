@@ -0,0 +1,455 @@
+//! The clipboard manager protocol of freedesktop.org, which keeps the clipboard alive after the
+//! client owning it exits.
+//!
+//! [`ClipboardManager`] owns the `CLIPBOARD_MANAGER` selection and takes the contents of the
+//! clipboard over when asked to, [`save_clipboard`] hands the clipboard of a client over before it
+//! exits.
+
+use std::time::{Duration, Instant};
+
+use xcb::x;
+
+use manager::{self, ManagerSelection};
+use selection::{self, Conversion, OwnerEvent, SelectionData, SelectionOwner, SelectionRequestor};
+use util::event;
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub clipboard_manager => b"CLIPBOARD_MANAGER" only_if_exists = false,
+        pub save_targets => b"SAVE_TARGETS" only_if_exists = false,
+        pub delete => b"DELETE" only_if_exists = false,
+    }
+}
+
+/// Asks the clipboard manager to save the contents of `clipboard`, which is owned by this client,
+/// and answers its requests until it is done or `timeout` elapses.
+///
+/// Only `targets` are saved, or all of them if it is empty. Returns whether the clipboard was
+/// saved, which is `false` if no clipboard manager is running. Other events received in the
+/// meantime are appended to `events`.
+pub fn save_clipboard(
+    connection: &xcb::Connection,
+    clipboard: &mut SelectionOwner,
+    targets: &[x::Atom],
+    timeout: Duration,
+    events: &mut Vec<xcb::Event>,
+) -> xcb::Result<bool> {
+    let atoms = Atoms::intern_all(connection)?;
+
+    if manager::owner(connection, atoms.clipboard_manager)?.is_none() {
+        return Ok(false);
+    }
+
+    let window = clipboard.window();
+    let property = atoms.clipboard_manager;
+
+    if targets.is_empty() {
+        connection.send_request(&x::DeleteProperty { window, property });
+    } else {
+        connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property,
+            r#type: x::ATOM_ATOM,
+            data: targets,
+        });
+    }
+
+    connection.send_request(&x::ConvertSelection {
+        requestor: window,
+        selection: atoms.clipboard_manager,
+        target: atoms.save_targets,
+        property,
+        time: clipboard.timestamp(),
+    });
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let event = match event::wait_until(connection, deadline)? {
+            Some(xcb::Event::X(x::Event::SelectionNotify(ev)))
+                if ev.requestor() == window
+                    && ev.selection() == atoms.clipboard_manager
+                    && ev.target() == atoms.save_targets =>
+            {
+                return Ok(ev.property() != x::ATOM_NONE);
+            }
+            Some(event) => event,
+            None => return Ok(false),
+        };
+
+        if !clipboard.concerns(&event) {
+            events.push(event);
+            continue;
+        }
+
+        match clipboard.handle_event(&event)? {
+            // The clipboard cannot be saved anymore, the caller is left to notice it was lost.
+            Some(OwnerEvent::Lost) => {
+                events.push(event);
+                return Ok(false);
+            }
+            Some(_) => {}
+            // Destroyed windows may matter to the caller as well.
+            None if matches!(event, xcb::Event::X(x::Event::DestroyNotify(_))) => {
+                events.push(event)
+            }
+            None => {}
+        }
+    }
+}
+
+/// What happened to a clipboard manager.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManagerEvent {
+    /// Another clipboard manager took the `CLIPBOARD_MANAGER` selection over.
+    Lost,
+    /// The clipboard of `requestor` was saved, and is now owned by the manager.
+    Saved { requestor: x::Window },
+    /// The clipboard of `requestor` could not be saved, or `requestor` was destroyed during the
+    /// save.
+    Failed { requestor: x::Window },
+    /// Another client took the clipboard over from the manager.
+    ClipboardLost,
+}
+
+/// A save in progress.
+struct Save {
+    request: x::SelectionRequestEvent,
+    /// Targets left to convert, `None` until the targets of the clipboard are known.
+    pending: Option<Vec<x::Atom>>,
+    saved: Vec<SelectionData>,
+}
+
+/// A clipboard manager, taking the clipboard over from clients exiting.
+pub struct ClipboardManager<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    manager: ManagerSelection<'a>,
+    requestor: SelectionRequestor<'a>,
+    clipboard: Option<SelectionOwner<'a>>,
+    save: Option<Save>,
+    /// Latest server time seen, used to acquire the clipboard.
+    time: x::Timestamp,
+}
+
+impl<'a> ClipboardManager<'a> {
+    /// Makes `window` the clipboard manager of `screen_nbr`, see [`ManagerSelection::acquire`]
+    /// for `replace` and `events`.
+    ///
+    /// The clipboard is converted into properties of `window` and owned by it once saved.
+    pub fn acquire(
+        connection: &'a xcb::Connection,
+        screen_nbr: i32,
+        window: x::Window,
+        replace: Option<Duration>,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<Self>> {
+        let atoms = Atoms::intern_all(connection)?;
+
+        let manager = match ManagerSelection::acquire(
+            connection,
+            screen_nbr,
+            atoms.clipboard_manager,
+            window,
+            replace,
+            events,
+        )? {
+            Some(manager) => manager,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            connection,
+            atoms,
+            time: manager.timestamp(),
+            requestor: SelectionRequestor::new(connection, window)?,
+            manager,
+            clipboard: None,
+            save: None,
+        }))
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    pub fn manager(&self) -> &ManagerSelection<'a> {
+        &self.manager
+    }
+
+    /// The clipboard saved last, while the manager still owns it.
+    pub fn clipboard(&self) -> Option<&SelectionOwner<'a>> {
+        self.clipboard.as_ref()
+    }
+
+    /// Handles an event, saving the clipboard when asked to and answering requests for the saved
+    /// contents.
+    pub fn handle_event(&mut self, event: &xcb::Event) -> xcb::Result<Option<ManagerEvent>> {
+        if self.manager.is_lost(event) {
+            return Ok(Some(ManagerEvent::Lost));
+        }
+
+        if let xcb::Event::X(x::Event::PropertyNotify(ev)) = event {
+            if ev.window() == self.manager.window() {
+                self.time = ev.time();
+            }
+        }
+
+        if let Some(clipboard) = &mut self.clipboard {
+            match clipboard.handle_event(event)? {
+                Some(OwnerEvent::Lost) => {
+                    self.clipboard = None;
+                    return Ok(Some(ManagerEvent::ClipboardLost));
+                }
+                Some(_) => return Ok(None),
+                None => {}
+            }
+        }
+
+        match event {
+            xcb::Event::X(x::Event::SelectionRequest(ev))
+                if ev.owner() == self.manager.window()
+                    && ev.selection() == self.atoms.clipboard_manager =>
+            {
+                self.start(ev)
+            }
+            // The clipboard of a destroyed requestor will not be converted anymore.
+            xcb::Event::X(x::Event::DestroyNotify(ev))
+                if self
+                    .save
+                    .as_ref()
+                    .is_some_and(|save| save.request.requestor() == ev.window()) =>
+            {
+                self.save = None;
+                Ok(Some(ManagerEvent::Failed {
+                    requestor: ev.window(),
+                }))
+            }
+            _ => match self.requestor.handle_event(event)? {
+                Some(conversion) => self.advance(conversion),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// The time by which [`ClipboardManager::expire`] should be called, while the clipboard is
+    /// transferred incrementally.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.requestor.next_deadline()
+    }
+
+    /// Gives up the incremental transfers of the clipboard which stalled, moving on with the save.
+    pub fn expire(&mut self) -> xcb::Result<Option<ManagerEvent>> {
+        let mut result = None;
+
+        for conversion in self.requestor.expire() {
+            if let Some(event) = self.advance(conversion)? {
+                result = Some(event);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Starts saving the clipboard for `request`, replacing a save in progress.
+    fn start(&mut self, request: &x::SelectionRequestEvent) -> xcb::Result<Option<ManagerEvent>> {
+        if request.target() != self.atoms.save_targets {
+            selection::notify(self.connection, request, x::ATOM_NONE);
+            return Ok(None);
+        }
+
+        // The destruction of the requestor is noticed through the events of its window, which are
+        // added to those already selected on it.
+        let attributes =
+            self.connection
+                .wait_for_reply(self.connection.send_request(&x::GetWindowAttributes {
+                    window: request.requestor(),
+                }));
+
+        let event_mask = match attributes {
+            Ok(attributes) => attributes.your_event_mask(),
+            // The requestor went away.
+            Err(xcb::Error::Protocol(_)) => {
+                return Ok(Some(ManagerEvent::Failed {
+                    requestor: request.requestor(),
+                }))
+            }
+            Err(err) => return Err(err),
+        };
+
+        self.connection.send_request(&x::ChangeWindowAttributes {
+            window: request.requestor(),
+            value_list: &[x::Cw::EventMask(
+                event_mask | x::EventMask::STRUCTURE_NOTIFY,
+            )],
+        });
+
+        let previous = self.save.take().map(|save| {
+            selection::notify(self.connection, &save.request, x::ATOM_NONE);
+            ManagerEvent::Failed {
+                requestor: save.request.requestor(),
+            }
+        });
+
+        let targets = if request.property() == x::ATOM_NONE {
+            Vec::new()
+        } else {
+            let reply = self
+                .connection
+                .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                    delete: false,
+                    window: request.requestor(),
+                    property: request.property(),
+                    r#type: x::ATOM_ATOM,
+                    long_offset: 0,
+                    long_length: u32::MAX,
+                }))?;
+
+            if reply.format() == 32 {
+                reply.value::<x::Atom>().to_vec()
+            } else {
+                Vec::new()
+            }
+        };
+
+        let request = x::SelectionRequestEvent::new(
+            request.time(),
+            request.owner(),
+            request.requestor(),
+            request.selection(),
+            request.target(),
+            request.property(),
+        );
+
+        // Without a list of targets, all the targets of the clipboard are saved.
+        let pending = if targets.is_empty() {
+            self.requestor.convert(
+                self.requestor.atoms().clipboard,
+                self.requestor.atoms().targets,
+                request.time(),
+            );
+            None
+        } else {
+            Some(targets)
+        };
+
+        self.save = Some(Save {
+            request,
+            pending,
+            saved: Vec::new(),
+        });
+
+        match self.convert_next()? {
+            Some(event) => Ok(Some(event)),
+            None => Ok(previous),
+        }
+    }
+
+    /// Records the outcome of a conversion of the clipboard and moves on to the next target.
+    fn advance(&mut self, conversion: Conversion) -> xcb::Result<Option<ManagerEvent>> {
+        let clipboard = self.requestor.atoms().clipboard;
+        let targets = self.requestor.atoms().targets;
+
+        let save = match &mut self.save {
+            Some(save) => save,
+            None => return Ok(None),
+        };
+
+        match conversion {
+            Conversion::Converted(data) if data.selection == clipboard => {
+                if data.target == targets && save.pending.is_none() {
+                    let skipped = [
+                        targets,
+                        self.requestor.atoms().timestamp,
+                        self.requestor.atoms().multiple,
+                        self.atoms.save_targets,
+                        self.atoms.delete,
+                    ];
+
+                    save.pending = Some(
+                        data.atoms()
+                            .into_iter()
+                            .filter(|target| !skipped.contains(target))
+                            .collect(),
+                    );
+                } else {
+                    save.saved.push(data);
+                }
+            }
+            Conversion::Refused { selection, target }
+            | Conversion::Aborted { selection, target }
+                if selection == clipboard =>
+            {
+                if target == targets && save.pending.is_none() {
+                    save.pending = Some(Vec::new());
+                }
+            }
+            _ => return Ok(None),
+        }
+
+        self.convert_next()
+    }
+
+    /// Converts the next pending target, or finishes the save if there is none.
+    fn convert_next(&mut self) -> xcb::Result<Option<ManagerEvent>> {
+        let save = match &mut self.save {
+            Some(save) => save,
+            None => return Ok(None),
+        };
+
+        let next = match &mut save.pending {
+            Some(pending) if pending.is_empty() => None,
+            Some(pending) => Some(pending.remove(0)),
+            None => return Ok(None),
+        };
+
+        if let Some(target) = next {
+            self.requestor.convert(
+                self.requestor.atoms().clipboard,
+                target,
+                save.request.time(),
+            );
+
+            return Ok(None);
+        }
+
+        let save = self.save.take().unwrap();
+        let requestor = save.request.requestor();
+
+        if save.saved.is_empty() {
+            selection::notify(self.connection, &save.request, x::ATOM_NONE);
+            return Ok(Some(ManagerEvent::Failed { requestor }));
+        }
+
+        let mut clipboard = match SelectionOwner::acquire(
+            self.connection,
+            self.requestor.atoms().clipboard,
+            self.manager.window(),
+            self.time,
+        )? {
+            Some(clipboard) => clipboard,
+            None => {
+                selection::notify(self.connection, &save.request, x::ATOM_NONE);
+                return Ok(Some(ManagerEvent::Failed { requestor }));
+            }
+        };
+
+        for data in save.saved {
+            clipboard.offer_with_format(data.target, data.r#type, data.format, data.data);
+        }
+
+        self.clipboard = Some(clipboard);
+
+        let property = if save.request.property() == x::ATOM_NONE {
+            save.request.target()
+        } else {
+            save.request.property()
+        };
+
+        selection::notify(self.connection, &save.request, property);
+
+        Ok(Some(ManagerEvent::Saved { requestor }))
+    }
+}
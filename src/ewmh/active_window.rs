@@ -3,7 +3,7 @@ use std::ptr;
 use xcb::x;
 
 use super::{
-    ffi, ClientSourceType, EwmhConnection, EwmhCookieWithReplyChecked,
+    check_error, ffi, ClientSourceType, EwmhConnection, EwmhCookieWithReplyChecked,
     EwmhCookieWithReplyUnchecked, EwmhReply, EwmhRequest, EwmhRequestWithReply,
     EwmhRequestWithoutReply, RawEwmhRequest,
 };
@@ -139,6 +139,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetActiveWindowCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             let window = <x::Window as xcb::XidNew>::new(window);
 
             Ok(Self::Reply { raw, window })
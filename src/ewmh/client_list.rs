@@ -3,8 +3,8 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetClientList<'a> {
@@ -112,6 +112,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetClientListCookie {
             let raw =
                 &ffi::xcb_ewmh_get_client_list_reply(ewmh.ewmh.get(), cookie, &mut clients, &mut e);
 
+            check_error(e)?;
+
             let windows = slice::from_raw_parts(
                 clients.windows as *mut x::Window,
                 clients.windows_len as usize,
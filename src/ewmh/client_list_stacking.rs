@@ -3,8 +3,8 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetClientListStacking<'a> {
@@ -116,6 +116,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetClientListStackingCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             let windows = slice::from_raw_parts(
                 clients.windows as *mut x::Window,
                 clients.windows_len as usize,
@@ -3,8 +3,8 @@ use std::ptr;
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetCurrentDesktop {
@@ -134,6 +134,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetCurrentDesktopCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             let window = <x::Window as xcb::XidNew>::new(window);
 
             Ok(Self::Reply { raw, window })
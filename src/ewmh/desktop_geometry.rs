@@ -3,8 +3,8 @@ use std::ptr;
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetDesktopGeometry {
@@ -144,6 +144,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetDesktopGeometryCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             Ok(Self::Reply { raw, width, height })
         }
     }
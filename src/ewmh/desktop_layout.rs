@@ -3,7 +3,7 @@ use std::{mem, ptr};
 use xcb::x;
 
 use super::{
-    ffi, DesktopLayoutOrientation, DesktopLayoutStartingCorner, EwmhConnection,
+    check_error, ffi, DesktopLayoutOrientation, DesktopLayoutStartingCorner, EwmhConnection,
     EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply, EwmhRequest,
     EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
@@ -142,6 +142,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetDesktopLayoutCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             let orientation = DesktopLayoutOrientation::from(desktop_layout.orientation);
             let columns = desktop_layout.columns;
             let rows = desktop_layout.rows;
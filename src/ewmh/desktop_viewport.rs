@@ -3,8 +3,9 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, Coordinates, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
-    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, Coordinates, EwmhConnection, EwmhCookieWithReplyChecked,
+    EwmhCookieWithReplyUnchecked, EwmhReply, EwmhRequest, EwmhRequestWithReply,
+    EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetDesktopViewport<'a> {
@@ -138,6 +139,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetDesktopViewportCookie {
             let raw =
                 &ffi::xcb_ewmh_get_desktop_viewport_reply(ewmh.ewmh.get(), cookie, &mut vp, &mut e);
 
+            check_error(e)?;
+
             let coordinates = slice::from_raw_parts(
                 vp.desktop_viewport as *mut Coordinates,
                 vp.desktop_viewport_len as usize,
@@ -1,6 +1,51 @@
+use std::{mem, ptr};
+
 use xcb::x;
 
-use super::{ffi, EwmhConnection, EwmhRequest, RawEwmhRequest};
+use super::{
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, Extents, RawEwmhRequest,
+};
+
+pub struct SetFrameExtents {
+    pub window: x::Window,
+    pub extents: Extents,
+}
+
+unsafe impl RawEwmhRequest for SetFrameExtents {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_ewmh_set_frame_extents_checked(
+                    ewmh.ewmh.get(),
+                    xcb::Xid::resource_id(&self.window),
+                    self.extents.left,
+                    self.extents.right,
+                    self.extents.top,
+                    self.extents.bottom,
+                )
+            } else {
+                ffi::xcb_ewmh_set_frame_extents(
+                    ewmh.ewmh.get(),
+                    xcb::Xid::resource_id(&self.window),
+                    self.extents.left,
+                    self.extents.right,
+                    self.extents.top,
+                    self.extents.bottom,
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+impl EwmhRequest for SetFrameExtents {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetFrameExtents {}
 
 pub struct RequestFrameExtents {
     pub screen_nbr: i32,
@@ -25,3 +70,165 @@ impl EwmhRequest for RequestFrameExtents {
 
     const IS_VOID: bool = true;
 }
+
+/// Copies the extents field by field, the layout of the ffi struct differs from [`Extents`].
+fn extents_from_ffi(extents: &ffi::xcb_ewmh_get_extents_reply_t) -> Extents {
+    Extents {
+        top: extents.top,
+        bottom: extents.bottom,
+        left: extents.left,
+        right: extents.right,
+    }
+}
+
+// TODO: Expose inner reply
+pub struct GetFrameExtentsReply {
+    raw: *const u8,
+    extents: Extents,
+}
+
+impl EwmhReply for GetFrameExtentsReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        let mut extents = mem::zeroed();
+
+        ffi::xcb_ewmh_get_frame_extents_from_reply(
+            &mut extents,
+            raw as *mut ffi::xcb_get_property_reply_t,
+        );
+
+        Self {
+            raw,
+            extents: extents_from_ffi(&extents),
+        }
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        self.raw
+    }
+}
+
+impl GetFrameExtentsReply {
+    pub fn extents(&self) -> Extents {
+        self.extents
+    }
+}
+
+//TODO: Expose inner cookie
+pub struct GetFrameExtentsCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetFrameExtentsCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetFrameExtentsCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetFrameExtentsCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetFrameExtentsCookie {
+    type Reply = GetFrameExtentsReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        unsafe {
+            let cookie = ffi::xcb_get_property_cookie_t {
+                sequence: xcb::Cookie::sequence(&self) as u32,
+            };
+            let mut extents = mem::zeroed();
+            let mut e = ptr::null_mut();
+
+            let raw = &ffi::xcb_ewmh_get_frame_extents_reply(
+                ewmh.ewmh.get(),
+                cookie,
+                &mut extents,
+                &mut e,
+            );
+
+            check_error(e)?;
+
+            Ok(Self::Reply {
+                raw,
+                extents: extents_from_ffi(&extents),
+            })
+        }
+    }
+}
+
+impl xcb::Cookie for GetFrameExtentsCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetFrameExtentsCookieUnchecked {
+    type Reply = GetFrameExtentsReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        unsafe {
+            let cookie = ffi::xcb_get_property_cookie_t {
+                sequence: xcb::Cookie::sequence(&self) as u32,
+            };
+            let mut extents = mem::zeroed();
+            let mut e = ptr::null_mut();
+
+            let raw = &ffi::xcb_ewmh_get_frame_extents_reply(
+                ewmh.ewmh.get(),
+                cookie,
+                &mut extents,
+                &mut e,
+            );
+
+            Ok(Some(Self::Reply {
+                raw,
+                extents: extents_from_ffi(&extents),
+            }))
+        }
+    }
+}
+
+pub struct GetFrameExtents {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetFrameExtents {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_ewmh_get_frame_extents(
+                    ewmh.ewmh.get(),
+                    xcb::Xid::resource_id(&self.window),
+                )
+            } else {
+                ffi::xcb_ewmh_get_frame_extents_unchecked(
+                    ewmh.ewmh.get(),
+                    xcb::Xid::resource_id(&self.window),
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+impl EwmhRequest for GetFrameExtents {
+    type Cookie = GetFrameExtentsCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetFrameExtents {
+    type Reply = GetFrameExtentsReply;
+    type Cookie = GetFrameExtentsCookie;
+    type CookieUnchecked = GetFrameExtentsCookieUnchecked;
+}
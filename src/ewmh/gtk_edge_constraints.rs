@@ -0,0 +1,211 @@
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+};
+
+bitflags! {
+    /// Tiling state of each edge of a window, set by the window manager so that GTK client-side
+    /// decorations can drop their shadows and resize handles on tiled edges.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct GtkEdgeConstraints: u32 {
+        const TopTiled = 1 << 0;
+        const TopResizable = 1 << 1;
+        const RightTiled = 1 << 2;
+        const RightResizable = 1 << 3;
+        const BottomTiled = 1 << 4;
+        const BottomResizable = 1 << 5;
+        const LeftTiled = 1 << 6;
+        const LeftResizable = 1 << 7;
+    }
+}
+
+pub struct SetGtkEdgeConstraints {
+    pub window: x::Window,
+    pub constraints: GtkEdgeConstraints,
+}
+
+unsafe impl RawEwmhRequest for SetGtkEdgeConstraints {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.GTK_EDGE_CONSTRAINTS(),
+                r#type: x::ATOM_CARDINAL,
+                data: &[self.constraints.bits()],
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for SetGtkEdgeConstraints {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetGtkEdgeConstraints {}
+
+// TODO: Expose inner reply
+pub struct GetGtkEdgeConstraintsReply(x::GetPropertyReply);
+
+impl EwmhReply for GetGtkEdgeConstraintsReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetGtkEdgeConstraintsReply {
+    /// The constraints, or `None` if the property is not set.
+    pub fn constraints(&self) -> Option<GtkEdgeConstraints> {
+        if self.0.format() != 32 {
+            return None;
+        }
+
+        from_values(self.0.value())
+    }
+}
+
+fn from_values(values: &[u32]) -> Option<GtkEdgeConstraints> {
+    values
+        .first()
+        .map(|&bits| GtkEdgeConstraints::from_bits_truncate(bits))
+}
+
+//TODO: Expose inner cookie
+pub struct GetGtkEdgeConstraintsCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetGtkEdgeConstraintsCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetGtkEdgeConstraintsCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetGtkEdgeConstraintsCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetGtkEdgeConstraintsCookie {
+    type Reply = GetGtkEdgeConstraintsReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetGtkEdgeConstraintsReply)
+    }
+}
+
+impl xcb::Cookie for GetGtkEdgeConstraintsCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetGtkEdgeConstraintsCookieUnchecked {
+    type Reply = GetGtkEdgeConstraintsReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetGtkEdgeConstraintsReply))
+    }
+}
+
+pub struct GetGtkEdgeConstraints {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetGtkEdgeConstraints {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.GTK_EDGE_CONSTRAINTS(),
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: 1,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetGtkEdgeConstraints {
+    type Cookie = GetGtkEdgeConstraintsCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetGtkEdgeConstraints {
+    type Reply = GetGtkEdgeConstraintsReply;
+    type Cookie = GetGtkEdgeConstraintsCookie;
+    type CookieUnchecked = GetGtkEdgeConstraintsCookieUnchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_fixtures() {
+        // A window tiled to the left half of the screen, resizable from its right edge only.
+        assert_eq!(
+            from_values(&[0x0000_005d]),
+            Some(
+                GtkEdgeConstraints::TopTiled
+                    | GtkEdgeConstraints::RightTiled
+                    | GtkEdgeConstraints::RightResizable
+                    | GtkEdgeConstraints::BottomTiled
+                    | GtkEdgeConstraints::LeftTiled
+            )
+        );
+
+        // A floating window, resizable from every edge.
+        assert_eq!(
+            from_values(&[0x0000_00aa]),
+            Some(
+                GtkEdgeConstraints::TopResizable
+                    | GtkEdgeConstraints::RightResizable
+                    | GtkEdgeConstraints::BottomResizable
+                    | GtkEdgeConstraints::LeftResizable
+            )
+        );
+
+        assert_eq!(
+            from_values(&[0xffff_ff00, 1]),
+            Some(GtkEdgeConstraints::empty())
+        );
+        assert_eq!(from_values(&[]), None);
+    }
+
+    #[test]
+    fn encode_fixtures() {
+        assert_eq!(GtkEdgeConstraints::all().bits(), 0xff);
+        assert_eq!(
+            (GtkEdgeConstraints::LeftTiled | GtkEdgeConstraints::TopResizable).bits(),
+            0x42
+        );
+    }
+}
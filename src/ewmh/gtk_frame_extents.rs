@@ -0,0 +1,235 @@
+use std::convert::TryFrom;
+
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, Extents, RawEwmhRequest,
+};
+
+pub struct SetGtkFrameExtents {
+    pub window: x::Window,
+    pub extents: Extents,
+}
+
+unsafe impl RawEwmhRequest for SetGtkFrameExtents {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.GTK_FRAME_EXTENTS(),
+                r#type: x::ATOM_CARDINAL,
+                data: &to_values(&self.extents),
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for SetGtkFrameExtents {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetGtkFrameExtents {}
+
+// TODO: Expose inner reply
+pub struct GetGtkFrameExtentsReply(x::GetPropertyReply);
+
+impl EwmhReply for GetGtkFrameExtentsReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetGtkFrameExtentsReply {
+    /// The extents of the client-side shadows, or `None` if the property is not set.
+    pub fn extents(&self) -> Option<Extents> {
+        if self.0.format() != 32 {
+            return None;
+        }
+
+        from_values(self.0.value())
+    }
+}
+
+/// Decodes the extents from the left, right, top and bottom values of the property.
+fn from_values(values: &[u32]) -> Option<Extents> {
+    match *values {
+        [left, right, top, bottom, ..] => Some(Extents {
+            top,
+            bottom,
+            left,
+            right,
+        }),
+        _ => None,
+    }
+}
+
+fn to_values(extents: &Extents) -> [u32; 4] {
+    [extents.left, extents.right, extents.top, extents.bottom]
+}
+
+//TODO: Expose inner cookie
+pub struct GetGtkFrameExtentsCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetGtkFrameExtentsCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetGtkFrameExtentsCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetGtkFrameExtentsCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetGtkFrameExtentsCookie {
+    type Reply = GetGtkFrameExtentsReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetGtkFrameExtentsReply)
+    }
+}
+
+impl xcb::Cookie for GetGtkFrameExtentsCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetGtkFrameExtentsCookieUnchecked {
+    type Reply = GetGtkFrameExtentsReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetGtkFrameExtentsReply))
+    }
+}
+
+pub struct GetGtkFrameExtents {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetGtkFrameExtents {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.GTK_FRAME_EXTENTS(),
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: 4,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetGtkFrameExtents {
+    type Cookie = GetGtkFrameExtentsCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetGtkFrameExtents {
+    type Reply = GetGtkFrameExtentsReply;
+    type Cookie = GetGtkFrameExtentsCookie;
+    type CookieUnchecked = GetGtkFrameExtentsCookieUnchecked;
+}
+
+impl Extents {
+    /// Geometry of a window without the area covered by these extents, such as the shadows drawn
+    /// around a GTK client-side decorated window, which should not count when tiling or snapping
+    /// it.
+    pub fn inset(&self, x: i32, y: i32, width: u32, height: u32) -> (i32, i32, u32, u32) {
+        (
+            x.saturating_add(offset(self.left)),
+            y.saturating_add(offset(self.top)),
+            width.saturating_sub(self.left).saturating_sub(self.right),
+            height.saturating_sub(self.top).saturating_sub(self.bottom),
+        )
+    }
+
+    /// Geometry to give a window for its area without these extents to match the one given, see
+    /// [`Extents::inset`].
+    pub fn outset(&self, x: i32, y: i32, width: u32, height: u32) -> (i32, i32, u32, u32) {
+        (
+            x.saturating_sub(offset(self.left)),
+            y.saturating_sub(offset(self.top)),
+            width.saturating_add(self.left).saturating_add(self.right),
+            height.saturating_add(self.top).saturating_add(self.bottom),
+        )
+    }
+}
+
+fn offset(extent: u32) -> i32 {
+    i32::try_from(extent).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADOWS: Extents = Extents {
+        top: 20,
+        bottom: 30,
+        left: 24,
+        right: 26,
+    };
+
+    #[test]
+    fn values() {
+        assert_eq!(to_values(&SHADOWS), [24, 26, 20, 30]);
+        assert_eq!(from_values(&[24, 26, 20, 30]), Some(SHADOWS));
+        assert_eq!(from_values(&[24, 26, 20, 30, 1]), Some(SHADOWS));
+        assert_eq!(from_values(&[24, 26, 20]), None);
+    }
+
+    #[test]
+    fn inset_and_outset() {
+        let window = (100, 50, 850, 650);
+
+        assert_eq!(SHADOWS.inset(100, 50, 850, 650), (124, 70, 800, 600));
+        assert_eq!(SHADOWS.outset(124, 70, 800, 600), window);
+    }
+
+    #[test]
+    fn inset_and_outset_saturate() {
+        let huge = Extents {
+            top: u32::MAX,
+            bottom: u32::MAX,
+            left: u32::MAX,
+            right: 1,
+        };
+
+        assert_eq!(huge.inset(i32::MAX, 0, 10, 10), (i32::MAX, i32::MAX, 0, 0));
+        assert_eq!(
+            huge.outset(i32::MIN, 0, 10, 10),
+            (i32::MIN, -i32::MAX, u32::MAX, u32::MAX)
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::convert::TryFrom;
+
+use xcb::{x, Xid};
+
+use super::{
+    Coordinates, EwmhConnection, Geometry, GetCurrentDesktop, GetDesktopGeometry,
+    GetDesktopViewport, RequestChangeDesktopViewport,
+};
+
+/// A large desktop split into screen-sized viewports, as used by compiz-style window managers.
+///
+/// Viewports are indexed row by row, starting from the top left corner of the desktop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LargeDesktop {
+    screen_nbr: i32,
+    width: u32,
+    height: u32,
+    viewport_width: u32,
+    viewport_height: u32,
+    current: Coordinates,
+}
+
+impl LargeDesktop {
+    pub fn new(
+        screen_nbr: i32,
+        width: u32,
+        height: u32,
+        viewport_width: u32,
+        viewport_height: u32,
+        current: Coordinates,
+    ) -> Self {
+        Self {
+            screen_nbr,
+            width: width.max(viewport_width),
+            height: height.max(viewport_height),
+            viewport_width: viewport_width.max(1),
+            viewport_height: viewport_height.max(1),
+            current,
+        }
+    }
+
+    /// Builds the model from `_NET_DESKTOP_GEOMETRY`, the `_NET_DESKTOP_VIEWPORT` of the current
+    /// desktop and the size of the screen.
+    ///
+    /// Returns `None` if `screen_nbr` does not exist.
+    pub fn fetch(ewmh: &EwmhConnection, screen_nbr: i32) -> xcb::Result<Option<Self>> {
+        let screen = match ewmh.get_setup().roots().nth(screen_nbr as usize) {
+            Some(screen) => screen,
+            None => return Ok(None),
+        };
+
+        let geometry = ewmh.send_request(&GetDesktopGeometry { screen_nbr });
+        let viewport = ewmh.send_request(&GetDesktopViewport { screen_nbr });
+        let current_desktop = ewmh.send_request(&GetCurrentDesktop { screen_nbr });
+
+        let geometry = ewmh.wait_for_reply(geometry)?;
+        let viewport = ewmh.wait_for_reply(viewport)?;
+        let current_desktop = ewmh.wait_for_reply(current_desktop)?.window().resource_id();
+
+        let current = viewport
+            .coordiantes()
+            .get(current_desktop as usize)
+            .copied()
+            .unwrap_or(Coordinates { x: 0, y: 0 });
+
+        Ok(Some(Self::new(
+            screen_nbr,
+            geometry.width(),
+            geometry.height(),
+            screen.width_in_pixels() as u32,
+            screen.height_in_pixels() as u32,
+            current,
+        )))
+    }
+
+    pub fn screen_nbr(&self) -> i32 {
+        self.screen_nbr
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.width.div_ceil(self.viewport_width)
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.height.div_ceil(self.viewport_height)
+    }
+
+    /// Number of viewports on the desktop, saturated to `usize::MAX`.
+    pub fn len(&self) -> usize {
+        let len = u64::from(self.columns()) * u64::from(self.rows());
+
+        usize::try_from(len).unwrap_or(usize::MAX)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Geometry of the viewport at `index`, in desktop coordinates.
+    ///
+    /// Viewports on the right and bottom edges are clipped to the desktop geometry.
+    pub fn viewport(&self, index: usize) -> Option<Geometry> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let index = index as u64;
+        let columns = u64::from(self.columns());
+
+        // The viewports start inside the desktop, so their positions fit in a u32.
+        let x = u32::try_from(index % columns * u64::from(self.viewport_width)).ok()?;
+        let y = u32::try_from(index / columns * u64::from(self.viewport_height)).ok()?;
+
+        Some(Geometry {
+            x,
+            y,
+            width: self.viewport_width.min(self.width.saturating_sub(x)),
+            height: self.viewport_height.min(self.height.saturating_sub(y)),
+        })
+    }
+
+    pub fn viewports(&self) -> impl Iterator<Item = Geometry> + '_ {
+        (0..self.len()).filter_map(move |index| self.viewport(index))
+    }
+
+    /// Index of the viewport containing the point `x`, `y` in desktop coordinates.
+    pub fn viewport_at(&self, x: i64, y: i64) -> Option<usize> {
+        if x < 0 || y < 0 || x >= i64::from(self.width) || y >= i64::from(self.height) {
+            return None;
+        }
+
+        let column = x as u64 / u64::from(self.viewport_width);
+        let row = y as u64 / u64::from(self.viewport_height);
+
+        usize::try_from(row * u64::from(self.columns()) + column).ok()
+    }
+
+    /// Index of the viewport currently shown on the screen.
+    pub fn current_viewport(&self) -> Option<usize> {
+        self.viewport_at(i64::from(self.current.x), i64::from(self.current.y))
+    }
+
+    /// Index of the viewport a window belongs to, given its geometry relative to the root window.
+    ///
+    /// Window managers keep windows of other viewports at coordinates relative to the viewport
+    /// being shown, so the center of the window is offset by the current viewport before looking
+    /// it up.
+    pub fn viewport_of(&self, x: i32, y: i32, width: u32, height: u32) -> Option<usize> {
+        let x = i64::from(self.current.x) + i64::from(x) + i64::from(width / 2);
+        let y = i64::from(self.current.y) + i64::from(y) + i64::from(height / 2);
+
+        self.viewport_at(x, y)
+    }
+
+    /// Index of the viewport `window` belongs to, see [`LargeDesktop::viewport_of`].
+    pub fn window_viewport(
+        &self,
+        connection: &xcb::Connection,
+        window: x::Window,
+    ) -> xcb::Result<Option<usize>> {
+        let geometry = connection.wait_for_reply(connection.send_request(&x::GetGeometry {
+            drawable: x::Drawable::Window(window),
+        }))?;
+
+        let position =
+            connection.wait_for_reply(connection.send_request(&x::TranslateCoordinates {
+                src_window: window,
+                dst_window: geometry.root(),
+                src_x: 0,
+                src_y: 0,
+            }))?;
+
+        Ok(self.viewport_of(
+            position.dst_x() as i32,
+            position.dst_y() as i32,
+            geometry.width() as u32,
+            geometry.height() as u32,
+        ))
+    }
+
+    /// Request switching to the viewport at `index`.
+    pub fn request_change_viewport(&self, index: usize) -> Option<RequestChangeDesktopViewport> {
+        self.viewport(index)
+            .map(|viewport| RequestChangeDesktopViewport {
+                screen_nbr: self.screen_nbr,
+                x: viewport.x,
+                y: viewport.y,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desktop(current: Coordinates) -> LargeDesktop {
+        // Three columns, the last one clipped, and two rows.
+        LargeDesktop::new(0, 2500, 2048, 1024, 1024, current)
+    }
+
+    #[test]
+    fn viewports() {
+        let desktop = desktop(Coordinates { x: 0, y: 0 });
+
+        assert_eq!(
+            (desktop.columns(), desktop.rows(), desktop.len()),
+            (3, 2, 6)
+        );
+        assert_eq!(
+            desktop.viewport(2),
+            Some(Geometry {
+                x: 2048,
+                y: 0,
+                width: 452,
+                height: 1024,
+            })
+        );
+        assert_eq!(
+            desktop.viewport(4),
+            Some(Geometry {
+                x: 1024,
+                y: 1024,
+                width: 1024,
+                height: 1024,
+            })
+        );
+        assert_eq!(desktop.viewport(6), None);
+        assert_eq!(desktop.viewports().count(), 6);
+    }
+
+    #[test]
+    fn desktop_smaller_than_the_screen() {
+        let desktop = LargeDesktop::new(0, 800, 600, 1024, 768, Coordinates { x: 0, y: 0 });
+
+        assert_eq!(desktop.len(), 1);
+        assert_eq!(
+            desktop.viewport(0),
+            Some(Geometry {
+                x: 0,
+                y: 0,
+                width: 1024,
+                height: 768,
+            })
+        );
+    }
+
+    #[test]
+    fn viewport_lookup() {
+        let desktop = desktop(Coordinates { x: 1024, y: 1024 });
+
+        assert_eq!(desktop.viewport_at(0, 0), Some(0));
+        assert_eq!(desktop.viewport_at(2499, 1023), Some(2));
+        assert_eq!(desktop.viewport_at(1024, 2047), Some(4));
+        assert_eq!(desktop.viewport_at(2500, 0), None);
+        assert_eq!(desktop.viewport_at(-1, 0), None);
+        assert_eq!(desktop.current_viewport(), Some(4));
+
+        // Relative to the current viewport, a window left of the screen is in the viewport on the
+        // left of it.
+        assert_eq!(desktop.viewport_of(-1000, 10, 100, 100), Some(3));
+        assert_eq!(desktop.viewport_of(2000, 0, 100, 100), None);
+    }
+
+    #[test]
+    fn change_viewport_request() {
+        let request = desktop(Coordinates { x: 0, y: 0 })
+            .request_change_viewport(5)
+            .unwrap();
+
+        assert_eq!((request.x, request.y), (2048, 1024));
+        assert!(desktop(Coordinates { x: 0, y: 0 })
+            .request_change_viewport(6)
+            .is_none());
+    }
+
+    #[test]
+    fn huge_desktop_does_not_overflow() {
+        let desktop = LargeDesktop::new(
+            0,
+            u32::MAX,
+            u32::MAX,
+            1,
+            1,
+            Coordinates {
+                x: u32::MAX,
+                y: u32::MAX,
+            },
+        );
+
+        let len = u64::from(u32::MAX) * u64::from(u32::MAX);
+
+        assert_eq!(desktop.len(), usize::try_from(len).unwrap_or(usize::MAX));
+        assert_eq!(desktop.current_viewport(), None);
+        assert_eq!(
+            desktop.viewport_at(i64::from(u32::MAX) - 1, i64::from(u32::MAX) - 1),
+            usize::try_from(len - 1).ok()
+        );
+
+        if let Ok(last) = usize::try_from(len - 1) {
+            assert_eq!(
+                desktop.viewport(last),
+                Some(Geometry {
+                    x: u32::MAX - 1,
+                    y: u32::MAX - 1,
+                    width: 1,
+                    height: 1,
+                })
+            );
+        }
+
+        let desktop = LargeDesktop::new(
+            0,
+            u32::MAX,
+            10,
+            u32::MAX - 1,
+            10,
+            Coordinates { x: 0, y: 0 },
+        );
+
+        assert_eq!(
+            desktop.viewport(1),
+            Some(Geometry {
+                x: u32::MAX - 1,
+                y: 0,
+                width: 1,
+                height: 10,
+            })
+        );
+    }
+}
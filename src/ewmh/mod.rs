@@ -1,7 +1,16 @@
-use std::{cell::UnsafeCell, mem, ops::Deref, ptr};
+use std::{cell::UnsafeCell, ops::Deref};
+#[cfg(not(feature = "ewmh-native"))]
+use std::{mem, ptr};
 
+use xcb::x;
+
+#[cfg(not(feature = "ewmh-native"))]
 pub use xcb_util_sys::ewmh as ffi;
 
+#[cfg(feature = "ewmh-native")]
+#[path = "native.rs"]
+pub mod ffi;
+
 mod active_window;
 mod client_list;
 mod client_list_stacking;
@@ -12,20 +21,29 @@ mod desktop_layout;
 mod desktop_names;
 mod desktop_viewport;
 mod frame_extents;
+mod gtk_edge_constraints;
+mod gtk_frame_extents;
+mod large_desktop;
+mod motif_wm_hints;
 mod moveresize_window;
 mod number_of_desktops;
 mod restack_window;
 mod showing_desktop;
+mod startup_notification;
 mod supported;
 mod supporting_wm_check;
 mod virtual_roots;
+mod window_actions;
+mod wm_bypass_compositor;
 mod wm_desktop;
 mod wm_icon_name;
 mod wm_moveresize;
 mod wm_name;
+mod wm_opaque_region;
 mod wm_state;
 mod wm_visible_icon_name;
 mod wm_visible_name;
+mod wm_window_opacity;
 mod wm_window_type;
 mod workarea;
 
@@ -39,20 +57,29 @@ pub use self::desktop_layout::*;
 pub use self::desktop_names::*;
 pub use self::desktop_viewport::*;
 pub use self::frame_extents::*;
+pub use self::gtk_edge_constraints::*;
+pub use self::gtk_frame_extents::*;
+pub use self::large_desktop::*;
+pub use self::motif_wm_hints::*;
 pub use self::moveresize_window::*;
 pub use self::number_of_desktops::*;
 pub use self::restack_window::*;
 pub use self::showing_desktop::*;
+pub use self::startup_notification::*;
 pub use self::supported::*;
 pub use self::supporting_wm_check::*;
 pub use self::virtual_roots::*;
+pub use self::window_actions::*;
+pub use self::wm_bypass_compositor::*;
 pub use self::wm_desktop::*;
 pub use self::wm_icon_name::*;
 pub use self::wm_moveresize::*;
 pub use self::wm_name::*;
+pub use self::wm_opaque_region::*;
 pub use self::wm_state::*;
 pub use self::wm_visible_icon_name::*;
 pub use self::wm_visible_name::*;
+pub use self::wm_window_opacity::*;
 pub use self::wm_window_type::*;
 pub use self::workarea::*;
 
@@ -77,6 +104,18 @@ impl<T: xcb::Reply> EwmhReply for T {
     }
 }
 
+/// Turns the error a `_reply` function stored in its out parameter into an `Err`, taking ownership
+/// of it.
+///
+/// Property requests only fail with core errors, no extension data is needed to resolve them.
+unsafe fn check_error<E>(e: *mut E) -> xcb::Result<()> {
+    if e.is_null() {
+        Ok(())
+    } else {
+        Err(xcb::resolve_error(e as *mut xcb::ffi::xcb_generic_error_t, &[]).into())
+    }
+}
+
 /// # Safety
 /// Cookies implementing this trait acknowledge that their error is checked when the reply is fetched from the server.
 /// This is the default cookie type for requests with reply.
@@ -160,6 +199,7 @@ impl<T: xcb::RequestWithReply> EwmhRequestWithReply for T {
 
 pub struct EwmhConnection<'a> {
     ewmh: UnsafeCell<ffi::xcb_ewmh_connection_t>,
+    atoms: Atoms,
     connection: &'a xcb::Connection,
 }
 
@@ -172,6 +212,7 @@ impl<'a> Deref for EwmhConnection<'a> {
 }
 
 impl<'a> EwmhConnection<'a> {
+    #[cfg(not(feature = "ewmh-native"))]
     pub fn new(connection: &'a xcb::Connection) -> Option<Self> {
         unsafe {
             let ewmh = UnsafeCell::new(mem::zeroed());
@@ -181,13 +222,37 @@ impl<'a> EwmhConnection<'a> {
             let mut e = ptr::null_mut();
 
             if ffi::xcb_ewmh_init_atoms_replies(ewmh.get(), ewmh_cookies, &mut e) == 1 {
-                Some(Self { ewmh, connection })
+                let atoms = match Atoms::intern_all(connection) {
+                    Ok(atoms) => atoms,
+                    Err(_) => {
+                        ffi::xcb_ewmh_connection_wipe(ewmh.get());
+                        return None;
+                    }
+                };
+
+                Some(Self {
+                    ewmh,
+                    atoms,
+                    connection,
+                })
             } else {
                 None
             }
         }
     }
 
+    #[cfg(feature = "ewmh-native")]
+    pub fn new(connection: &'a xcb::Connection) -> Option<Self> {
+        let ewmh = UnsafeCell::new(ffi::xcb_ewmh_connection_t::new(connection).ok()?);
+        let atoms = Atoms::intern_all(connection).ok()?;
+
+        Some(Self {
+            ewmh,
+            atoms,
+            connection,
+        })
+    }
+
     pub fn send_request<R>(&self, req: &R) -> R::Cookie
     where
         R: EwmhRequest,
@@ -237,6 +302,139 @@ impl<'a> EwmhConnection<'a> {
     }
 }
 
+macro_rules! atoms {
+    ($($name:ident => $field:ident,)*) => {
+        impl<'a> EwmhConnection<'a> {
+            $(
+                #[inline(always)]
+                pub fn $name(&self) -> x::Atom {
+                    unsafe { <x::Atom as xcb::XidNew>::new((*self.ewmh.get()).$field) }
+                }
+            )*
+        }
+    };
+}
+
+atoms! {
+    UTF8_STRING => UTF8_STRING,
+    SUPPORTED => _NET_SUPPORTED,
+    CLIENT_LIST => _NET_CLIENT_LIST,
+    CLIENT_LIST_STACKING => _NET_CLIENT_LIST_STACKING,
+    NUMBER_OF_DESKTOPS => _NET_NUMBER_OF_DESKTOPS,
+    DESKTOP_GEOMETRY => _NET_DESKTOP_GEOMETRY,
+    DESKTOP_VIEWPORT => _NET_DESKTOP_VIEWPORT,
+    CURRENT_DESKTOP => _NET_CURRENT_DESKTOP,
+    DESKTOP_NAMES => _NET_DESKTOP_NAMES,
+    ACTIVE_WINDOW => _NET_ACTIVE_WINDOW,
+    WORKAREA => _NET_WORKAREA,
+    SUPPORTING_WM_CHECK => _NET_SUPPORTING_WM_CHECK,
+    VIRTUAL_ROOTS => _NET_VIRTUAL_ROOTS,
+    DESKTOP_LAYOUT => _NET_DESKTOP_LAYOUT,
+    SHOWING_DESKTOP => _NET_SHOWING_DESKTOP,
+    CLOSE_WINDOW => _NET_CLOSE_WINDOW,
+    MOVERESIZE_WINDOW => _NET_MOVERESIZE_WINDOW,
+    WM_MOVERESIZE => _NET_WM_MOVERESIZE,
+    RESTACK_WINDOW => _NET_RESTACK_WINDOW,
+    REQUEST_FRAME_EXTENTS => _NET_REQUEST_FRAME_EXTENTS,
+    WM_NAME => _NET_WM_NAME,
+    WM_VISIBLE_NAME => _NET_WM_VISIBLE_NAME,
+    WM_ICON_NAME => _NET_WM_ICON_NAME,
+    WM_VISIBLE_ICON_NAME => _NET_WM_VISIBLE_ICON_NAME,
+    WM_DESKTOP => _NET_WM_DESKTOP,
+    WM_WINDOW_TYPE => _NET_WM_WINDOW_TYPE,
+    WM_STATE => _NET_WM_STATE,
+    WM_ALLOWED_ACTIONS => _NET_WM_ALLOWED_ACTIONS,
+    WM_STRUT => _NET_WM_STRUT,
+    WM_STRUT_PARTIAL => _NET_WM_STRUT_PARTIAL,
+    WM_ICON_GEOMETRY => _NET_WM_ICON_GEOMETRY,
+    WM_ICON => _NET_WM_ICON,
+    WM_PID => _NET_WM_PID,
+    WM_HANDLED_ICONS => _NET_WM_HANDLED_ICONS,
+    WM_USER_TIME => _NET_WM_USER_TIME,
+    WM_USER_TIME_WINDOW => _NET_WM_USER_TIME_WINDOW,
+    FRAME_EXTENTS => _NET_FRAME_EXTENTS,
+    WM_PING => _NET_WM_PING,
+    WM_SYNC_REQUEST => _NET_WM_SYNC_REQUEST,
+    WM_SYNC_REQUEST_COUNTER => _NET_WM_SYNC_REQUEST_COUNTER,
+    WM_FULLSCREEN_MONITORS => _NET_WM_FULLSCREEN_MONITORS,
+    WM_FULL_PLACEMENT => _NET_WM_FULL_PLACEMENT,
+    WM_PROTOCOLS => WM_PROTOCOLS,
+    MANAGER => MANAGER,
+    WM_WINDOW_TYPE_DESKTOP => _NET_WM_WINDOW_TYPE_DESKTOP,
+    WM_WINDOW_TYPE_DOCK => _NET_WM_WINDOW_TYPE_DOCK,
+    WM_WINDOW_TYPE_TOOLBAR => _NET_WM_WINDOW_TYPE_TOOLBAR,
+    WM_WINDOW_TYPE_MENU => _NET_WM_WINDOW_TYPE_MENU,
+    WM_WINDOW_TYPE_UTILITY => _NET_WM_WINDOW_TYPE_UTILITY,
+    WM_WINDOW_TYPE_SPLASH => _NET_WM_WINDOW_TYPE_SPLASH,
+    WM_WINDOW_TYPE_DIALOG => _NET_WM_WINDOW_TYPE_DIALOG,
+    WM_WINDOW_TYPE_DROPDOWN_MENU => _NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+    WM_WINDOW_TYPE_POPUP_MENU => _NET_WM_WINDOW_TYPE_POPUP_MENU,
+    WM_WINDOW_TYPE_TOOLTIP => _NET_WM_WINDOW_TYPE_TOOLTIP,
+    WM_WINDOW_TYPE_NOTIFICATION => _NET_WM_WINDOW_TYPE_NOTIFICATION,
+    WM_WINDOW_TYPE_COMBO => _NET_WM_WINDOW_TYPE_COMBO,
+    WM_WINDOW_TYPE_DND => _NET_WM_WINDOW_TYPE_DND,
+    WM_WINDOW_TYPE_NORMAL => _NET_WM_WINDOW_TYPE_NORMAL,
+    WM_STATE_MODAL => _NET_WM_STATE_MODAL,
+    WM_STATE_STICKY => _NET_WM_STATE_STICKY,
+    WM_STATE_MAXIMIZED_VERT => _NET_WM_STATE_MAXIMIZED_VERT,
+    WM_STATE_MAXIMIZED_HORZ => _NET_WM_STATE_MAXIMIZED_HORZ,
+    WM_STATE_SHADED => _NET_WM_STATE_SHADED,
+    WM_STATE_SKIP_TASKBAR => _NET_WM_STATE_SKIP_TASKBAR,
+    WM_STATE_SKIP_PAGER => _NET_WM_STATE_SKIP_PAGER,
+    WM_STATE_HIDDEN => _NET_WM_STATE_HIDDEN,
+    WM_STATE_FULLSCREEN => _NET_WM_STATE_FULLSCREEN,
+    WM_STATE_ABOVE => _NET_WM_STATE_ABOVE,
+    WM_STATE_BELOW => _NET_WM_STATE_BELOW,
+    WM_STATE_DEMANDS_ATTENTION => _NET_WM_STATE_DEMANDS_ATTENTION,
+    WM_ACTION_MOVE => _NET_WM_ACTION_MOVE,
+    WM_ACTION_RESIZE => _NET_WM_ACTION_RESIZE,
+    WM_ACTION_MINIMIZE => _NET_WM_ACTION_MINIMIZE,
+    WM_ACTION_SHADE => _NET_WM_ACTION_SHADE,
+    WM_ACTION_STICK => _NET_WM_ACTION_STICK,
+    WM_ACTION_MAXIMIZE_HORZ => _NET_WM_ACTION_MAXIMIZE_HORZ,
+    WM_ACTION_MAXIMIZE_VERT => _NET_WM_ACTION_MAXIMIZE_VERT,
+    WM_ACTION_FULLSCREEN => _NET_WM_ACTION_FULLSCREEN,
+    WM_ACTION_CHANGE_DESKTOP => _NET_WM_ACTION_CHANGE_DESKTOP,
+    WM_ACTION_CLOSE => _NET_WM_ACTION_CLOSE,
+    WM_ACTION_ABOVE => _NET_WM_ACTION_ABOVE,
+    WM_ACTION_BELOW => _NET_WM_ACTION_BELOW,
+}
+
+/// Atoms not known to `libxcb-ewmh`, interned along the connection.
+macro_rules! extra_atoms {
+    ($($name:ident => $atom:tt,)*) => {
+        xcb::atoms_struct! {
+            struct Atoms {
+                $($name => $atom only_if_exists = false,)*
+            }
+        }
+
+        impl<'a> EwmhConnection<'a> {
+            $(
+                #[inline(always)]
+                pub fn $name(&self) -> x::Atom {
+                    self.atoms.$name
+                }
+            )*
+        }
+    };
+}
+
+extra_atoms! {
+    STARTUP_ID => b"_NET_STARTUP_ID",
+    STARTUP_INFO_BEGIN => b"_NET_STARTUP_INFO_BEGIN",
+    STARTUP_INFO => b"_NET_STARTUP_INFO",
+    MOTIF_WM_HINTS => b"_MOTIF_WM_HINTS",
+    GTK_FRAME_EXTENTS => b"_GTK_FRAME_EXTENTS",
+    GTK_EDGE_CONSTRAINTS => b"_GTK_EDGE_CONSTRAINTS",
+    WM_OPAQUE_REGION => b"_NET_WM_OPAQUE_REGION",
+    WM_BYPASS_COMPOSITOR => b"_NET_WM_BYPASS_COMPOSITOR",
+    WM_WINDOW_OPACITY => b"_NET_WM_WINDOW_OPACITY",
+    WM_STATE_FOCUSED => b"_NET_WM_STATE_FOCUSED",
+    WM_FRAME_DRAWN => b"_NET_WM_FRAME_DRAWN",
+    WM_FRAME_TIMINGS => b"_NET_WM_FRAME_TIMINGS",
+}
+
 impl<'a> Drop for EwmhConnection<'a> {
     fn drop(&mut self) {
         unsafe { ffi::xcb_ewmh_connection_wipe(self.ewmh.get()) }
@@ -259,6 +457,15 @@ pub struct Geometry {
     pub height: u32,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(C)]
+pub struct Extents {
+    pub top: u32,
+    pub bottom: u32,
+    pub left: u32,
+    pub right: u32,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum ClientSourceType {
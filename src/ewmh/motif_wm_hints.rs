@@ -0,0 +1,397 @@
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+};
+
+const MWM_HINTS_FUNCTIONS: u32 = 1 << 0;
+const MWM_HINTS_DECORATIONS: u32 = 1 << 1;
+const MWM_HINTS_INPUT_MODE: u32 = 1 << 2;
+const MWM_HINTS_STATUS: u32 = 1 << 3;
+
+bitflags! {
+    /// Functions the window manager should offer, the other ones if `All` is set.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct MotifFunctions: u32 {
+        const All = 1 << 0;
+        const Resize = 1 << 1;
+        const Move = 1 << 2;
+        const Minimize = 1 << 3;
+        const Maximize = 1 << 4;
+        const Close = 1 << 5;
+    }
+}
+
+bitflags! {
+    /// Decorations the window manager should draw, the other ones if `All` is set.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct MotifDecorations: u32 {
+        const All = 1 << 0;
+        const Border = 1 << 1;
+        const ResizeH = 1 << 2;
+        const Title = 1 << 3;
+        const Menu = 1 << 4;
+        const Minimize = 1 << 5;
+        const Maximize = 1 << 6;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct MotifStatus: u32 {
+        const TearoffWindow = 1 << 0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MotifInputMode {
+    Modeless = 0,
+    PrimaryApplicationModal = 1,
+    SystemModal = 2,
+    FullApplicationModal = 3,
+}
+
+impl From<u32> for MotifInputMode {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::PrimaryApplicationModal,
+            2 => Self::SystemModal,
+            3 => Self::FullApplicationModal,
+            _ => Self::Modeless,
+        }
+    }
+}
+
+/// The `_MOTIF_WM_HINTS` structure, where each field is `None` when its flag is not set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MotifWmHints {
+    pub functions: Option<MotifFunctions>,
+    pub decorations: Option<MotifDecorations>,
+    pub input_mode: Option<MotifInputMode>,
+    pub status: Option<MotifStatus>,
+}
+
+impl MotifWmHints {
+    /// Hints asking for a window without any decoration, as used by borderless clients.
+    pub fn no_decorations() -> Self {
+        Self {
+            decorations: Some(MotifDecorations::empty()),
+            ..Self::default()
+        }
+    }
+
+    pub fn from_values(values: &[u32]) -> Option<Self> {
+        if values.len() < 5 {
+            return None;
+        }
+
+        let flags = values[0];
+
+        Some(Self {
+            functions: (flags & MWM_HINTS_FUNCTIONS != 0)
+                .then(|| MotifFunctions::from_bits_truncate(values[1])),
+            decorations: (flags & MWM_HINTS_DECORATIONS != 0)
+                .then(|| MotifDecorations::from_bits_truncate(values[2])),
+            input_mode: (flags & MWM_HINTS_INPUT_MODE != 0)
+                .then(|| MotifInputMode::from(values[3])),
+            status: (flags & MWM_HINTS_STATUS != 0)
+                .then(|| MotifStatus::from_bits_truncate(values[4])),
+        })
+    }
+
+    pub fn to_values(&self) -> [u32; 5] {
+        let mut flags = 0;
+
+        if self.functions.is_some() {
+            flags |= MWM_HINTS_FUNCTIONS;
+        }
+
+        if self.decorations.is_some() {
+            flags |= MWM_HINTS_DECORATIONS;
+        }
+
+        if self.input_mode.is_some() {
+            flags |= MWM_HINTS_INPUT_MODE;
+        }
+
+        if self.status.is_some() {
+            flags |= MWM_HINTS_STATUS;
+        }
+
+        [
+            flags,
+            self.functions.map_or(0, |functions| functions.bits()),
+            self.decorations.map_or(0, |decorations| decorations.bits()),
+            self.input_mode.map_or(0, |input_mode| input_mode as u32),
+            self.status.map_or(0, |status| status.bits()),
+        ]
+    }
+
+    /// Decorations to draw once `All` is resolved, every one of them if the hint is not set.
+    pub fn resolved_decorations(&self) -> MotifDecorations {
+        match self.decorations {
+            Some(decorations) if decorations.contains(MotifDecorations::All) => {
+                MotifDecorations::all() - decorations
+            }
+            Some(decorations) => decorations,
+            None => MotifDecorations::all() - MotifDecorations::All,
+        }
+    }
+
+    /// Functions to offer once `All` is resolved, every one of them if the hint is not set.
+    pub fn resolved_functions(&self) -> MotifFunctions {
+        match self.functions {
+            Some(functions) if functions.contains(MotifFunctions::All) => {
+                MotifFunctions::all() - functions
+            }
+            Some(functions) => functions,
+            None => MotifFunctions::all() - MotifFunctions::All,
+        }
+    }
+
+    /// Whether the client asks for no decorations at all.
+    pub fn is_undecorated(&self) -> bool {
+        self.resolved_decorations().is_empty()
+    }
+}
+
+pub struct SetMotifWmHints {
+    pub window: x::Window,
+    pub hints: MotifWmHints,
+}
+
+unsafe impl RawEwmhRequest for SetMotifWmHints {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.MOTIF_WM_HINTS(),
+                r#type: ewmh.MOTIF_WM_HINTS(),
+                data: &self.hints.to_values(),
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for SetMotifWmHints {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetMotifWmHints {}
+
+// TODO: Expose inner reply
+pub struct GetMotifWmHintsReply(x::GetPropertyReply);
+
+impl EwmhReply for GetMotifWmHintsReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetMotifWmHintsReply {
+    /// The hints, or `None` if the property is not set or malformed.
+    pub fn hints(&self) -> Option<MotifWmHints> {
+        if self.0.format() != 32 {
+            return None;
+        }
+
+        MotifWmHints::from_values(self.0.value::<u32>())
+    }
+}
+
+//TODO: Expose inner cookie
+pub struct GetMotifWmHintsCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetMotifWmHintsCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetMotifWmHintsCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetMotifWmHintsCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetMotifWmHintsCookie {
+    type Reply = GetMotifWmHintsReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetMotifWmHintsReply)
+    }
+}
+
+impl xcb::Cookie for GetMotifWmHintsCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetMotifWmHintsCookieUnchecked {
+    type Reply = GetMotifWmHintsReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetMotifWmHintsReply))
+    }
+}
+
+pub struct GetMotifWmHints {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetMotifWmHints {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.MOTIF_WM_HINTS(),
+                r#type: ewmh.MOTIF_WM_HINTS(),
+                long_offset: 0,
+                long_length: 5,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetMotifWmHints {
+    type Cookie = GetMotifWmHintsCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetMotifWmHints {
+    type Reply = GetMotifWmHintsReply;
+    type Cookie = GetMotifWmHintsCookie;
+    type CookieUnchecked = GetMotifWmHintsCookieUnchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_fixtures() {
+        // What GTK sets for a client-side decorated window.
+        assert_eq!(
+            MotifWmHints::from_values(&[MWM_HINTS_DECORATIONS, 0, 0, 0, 0]),
+            Some(MotifWmHints::no_decorations())
+        );
+
+        // Values of unset fields and unknown bits are ignored.
+        assert_eq!(
+            MotifWmHints::from_values(&[
+                MWM_HINTS_FUNCTIONS | MWM_HINTS_INPUT_MODE | 1 << 8,
+                0x7f,
+                0xff,
+                2,
+                1
+            ]),
+            Some(MotifWmHints {
+                functions: Some(MotifFunctions::all()),
+                decorations: None,
+                input_mode: Some(MotifInputMode::SystemModal),
+                status: None,
+            })
+        );
+
+        assert_eq!(
+            MotifWmHints::from_values(&[MWM_HINTS_INPUT_MODE, 0, 0, 7, 0])
+                .and_then(|hints| hints.input_mode),
+            Some(MotifInputMode::Modeless)
+        );
+        assert_eq!(
+            MotifWmHints::from_values(&[MWM_HINTS_DECORATIONS, 0, 0, 0]),
+            None
+        );
+    }
+
+    #[test]
+    fn encode_fixtures() {
+        assert_eq!(MotifWmHints::default().to_values(), [0; 5]);
+        assert_eq!(
+            MotifWmHints::no_decorations().to_values(),
+            [MWM_HINTS_DECORATIONS, 0, 0, 0, 0]
+        );
+
+        let hints = MotifWmHints {
+            functions: Some(MotifFunctions::Move | MotifFunctions::Close),
+            decorations: Some(MotifDecorations::All | MotifDecorations::Title),
+            input_mode: Some(MotifInputMode::FullApplicationModal),
+            status: Some(MotifStatus::TearoffWindow),
+        };
+
+        assert_eq!(hints.to_values(), [0xf, 0x24, 0x9, 3, 1]);
+        assert_eq!(MotifWmHints::from_values(&hints.to_values()), Some(hints));
+    }
+
+    #[test]
+    fn resolve_all() {
+        let hints = MotifWmHints {
+            functions: Some(MotifFunctions::All | MotifFunctions::Resize),
+            decorations: Some(MotifDecorations::All | MotifDecorations::Menu),
+            ..MotifWmHints::default()
+        };
+
+        assert_eq!(
+            hints.resolved_functions(),
+            MotifFunctions::Move
+                | MotifFunctions::Minimize
+                | MotifFunctions::Maximize
+                | MotifFunctions::Close
+        );
+        assert!(!hints
+            .resolved_decorations()
+            .contains(MotifDecorations::Menu));
+        assert!(hints
+            .resolved_decorations()
+            .contains(MotifDecorations::Title));
+        assert!(!hints.is_undecorated());
+
+        let hints = MotifWmHints {
+            decorations: Some(MotifDecorations::Border),
+            ..MotifWmHints::default()
+        };
+
+        assert_eq!(hints.resolved_decorations(), MotifDecorations::Border);
+        assert!(!MotifWmHints::default().is_undecorated());
+        assert!(MotifWmHints::no_decorations().is_undecorated());
+        assert!(MotifWmHints {
+            decorations: Some(MotifDecorations::all()),
+            ..MotifWmHints::default()
+        }
+        .is_undecorated());
+        assert_eq!(
+            MotifWmHints::default().resolved_functions(),
+            MotifFunctions::all() - MotifFunctions::All
+        );
+    }
+}
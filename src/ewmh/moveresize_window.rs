@@ -1,7 +1,10 @@
 use xcb::x;
 
+use util::gravity;
+
 use super::{
-    ffi, ClientSourceType, EwmhConnection, EwmhRequest, MoveresizeWindowOptFlags, RawEwmhRequest,
+    ffi, ClientSourceType, EwmhConnection, EwmhRequest, Extents, GetFrameExtents,
+    MoveresizeWindowOptFlags, RawEwmhRequest,
 };
 
 pub struct RequestMoveresizeWindow {
@@ -41,3 +44,120 @@ impl EwmhRequest for RequestMoveresizeWindow {
 
     const IS_VOID: bool = true;
 }
+
+/// Places the outer frame of a window, decorations included, at `x`, `y` with a size of
+/// `width` × `height`.
+///
+/// `_NET_MOVERESIZE_WINDOW` takes the geometry of the client window, interpreted according to
+/// its gravity, so the request sent is derived from the frame extents and the window gravity.
+pub struct MoveresizeFrame {
+    pub screen_nbr: i32,
+    pub window: x::Window,
+    pub source_indication: ClientSourceType,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MoveresizeFrame {
+    /// Builds the request from the `_NET_FRAME_EXTENTS` and `win_gravity` of the window.
+    pub fn request(&self, ewmh: &EwmhConnection) -> xcb::Result<RequestMoveresizeWindow> {
+        let extents = ewmh.send_request(&GetFrameExtents {
+            window: self.window,
+        });
+        let attributes = ewmh.send_request(&x::GetWindowAttributes {
+            window: self.window,
+        });
+
+        let extents = ewmh.wait_for_reply(extents)?.extents();
+        let gravity = ewmh.wait_for_reply(attributes)?.win_gravity();
+
+        Ok(self.request_with(extents, gravity))
+    }
+
+    /// Builds the request for a window with the given frame extents and gravity.
+    pub fn request_with(&self, extents: Extents, gravity: x::Gravity) -> RequestMoveresizeWindow {
+        let gravity = match gravity {
+            x::Gravity::BitForget => x::Gravity::NorthWest,
+            gravity => gravity,
+        };
+
+        // The client sits inside the frame as it would once unframed, without a border of its own.
+        let (dx, dy) = gravity::offset(
+            gravity,
+            0,
+            (
+                i64::from(extents.left),
+                i64::from(extents.right),
+                i64::from(extents.top),
+                i64::from(extents.bottom),
+            ),
+        );
+
+        let horizontal = extents.left.saturating_add(extents.right);
+        let vertical = extents.top.saturating_add(extents.bottom);
+        let position = |value: i32, offset: i64| saturate(i64::from(value) - offset) as u32;
+
+        RequestMoveresizeWindow {
+            screen_nbr: self.screen_nbr,
+            moveresize_window: self.window,
+            gravity,
+            source_indication: self.source_indication,
+            flags: MoveresizeWindowOptFlags::all(),
+            x: position(self.x, dx),
+            y: position(self.y, dy),
+            width: self.width.saturating_sub(horizontal).max(1),
+            height: self.height.saturating_sub(vertical).max(1),
+        }
+    }
+}
+
+/// Brings a coordinate back into the range of `i32`.
+fn saturate(value: i64) -> i32 {
+    value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use xcb::Xid;
+
+    use super::*;
+
+    #[test]
+    fn request_with_places_client_inside_frame() {
+        let frame = MoveresizeFrame {
+            screen_nbr: 0,
+            window: x::Window::none(),
+            source_indication: ClientSourceType::Normal,
+            x: 100,
+            y: 50,
+            width: 646,
+            height: 496,
+        };
+        let extents = Extents {
+            top: 10,
+            bottom: 6,
+            left: 2,
+            right: 4,
+        };
+
+        let requests = [
+            (x::Gravity::BitForget, x::Gravity::NorthWest, (100, 50)),
+            (x::Gravity::NorthWest, x::Gravity::NorthWest, (100, 50)),
+            (x::Gravity::North, x::Gravity::North, (103, 50)),
+            (x::Gravity::NorthEast, x::Gravity::NorthEast, (106, 50)),
+            (x::Gravity::Center, x::Gravity::Center, (103, 58)),
+            (x::Gravity::SouthEast, x::Gravity::SouthEast, (106, 66)),
+            (x::Gravity::Static, x::Gravity::Static, (102, 60)),
+        ];
+
+        for (gravity, sent, (x, y)) in requests {
+            let request = frame.request_with(extents, gravity);
+
+            assert_eq!(request.gravity, sent);
+            assert_eq!((request.x, request.y), (x, y), "{:?}", gravity);
+            assert_eq!((request.width, request.height), (640, 480));
+        }
+    }
+}
@@ -0,0 +1,1455 @@
+//! Rust implementation of the `libxcb-ewmh` functions used by this module, built on top of
+//! `xcb::x` requests.
+//!
+//! It mirrors the C API closely so that the request types do not need to know which backend
+//! they are talking to.
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::convert::TryFrom;
+use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::mem::ManuallyDrop;
+use std::{ptr, slice};
+
+use libc;
+use xcb::{x, Xid, XidNew};
+
+pub type xcb_atom_t = u32;
+pub type xcb_window_t = u32;
+pub type xcb_timestamp_t = u32;
+
+pub type xcb_ewmh_client_source_type_t = c_uint;
+pub const XCB_EWMH_CLIENT_SOURCE_TYPE_NONE: xcb_ewmh_client_source_type_t = 0;
+pub const XCB_EWMH_CLIENT_SOURCE_TYPE_NORMAL: xcb_ewmh_client_source_type_t = 1;
+pub const XCB_EWMH_CLIENT_SOURCE_TYPE_OTHER: xcb_ewmh_client_source_type_t = 2;
+
+pub type xcb_ewmh_desktop_layout_orientation_t = c_uint;
+pub const XCB_EWMH_WM_ORIENTATION_HORZ: xcb_ewmh_desktop_layout_orientation_t = 0;
+pub const XCB_EWMH_WM_ORIENTATION_VERT: xcb_ewmh_desktop_layout_orientation_t = 1;
+
+pub type xcb_ewmh_desktop_layout_starting_corner_t = c_uint;
+pub const XCB_EWMH_WM_TOPLEFT: xcb_ewmh_desktop_layout_starting_corner_t = 0;
+pub const XCB_EWMH_WM_TOPRIGHT: xcb_ewmh_desktop_layout_starting_corner_t = 1;
+pub const XCB_EWMH_WM_BOTTOMRIGHT: xcb_ewmh_desktop_layout_starting_corner_t = 2;
+pub const XCB_EWMH_WM_BOTTOMLEFT: xcb_ewmh_desktop_layout_starting_corner_t = 3;
+
+pub type xcb_ewmh_moveresize_window_opt_flags_t = c_uint;
+pub const XCB_EWMH_MOVERESIZE_WINDOW_X: xcb_ewmh_moveresize_window_opt_flags_t = 1 << 8;
+pub const XCB_EWMH_MOVERESIZE_WINDOW_Y: xcb_ewmh_moveresize_window_opt_flags_t = 1 << 9;
+pub const XCB_EWMH_MOVERESIZE_WINDOW_WIDTH: xcb_ewmh_moveresize_window_opt_flags_t = 1 << 10;
+pub const XCB_EWMH_MOVERESIZE_WINDOW_HEIGHT: xcb_ewmh_moveresize_window_opt_flags_t = 1 << 11;
+
+pub type xcb_ewmh_moveresize_direction_t = c_uint;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_TOPLEFT: xcb_ewmh_moveresize_direction_t = 0;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_TOP: xcb_ewmh_moveresize_direction_t = 1;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_TOPRIGHT: xcb_ewmh_moveresize_direction_t = 2;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_RIGHT: xcb_ewmh_moveresize_direction_t = 3;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_BOTTOMRIGHT: xcb_ewmh_moveresize_direction_t = 4;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_BOTTOM: xcb_ewmh_moveresize_direction_t = 5;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_BOTTOMLEFT: xcb_ewmh_moveresize_direction_t = 6;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_LEFT: xcb_ewmh_moveresize_direction_t = 7;
+pub const XCB_EWMH_WM_MOVERESIZE_MOVE: xcb_ewmh_moveresize_direction_t = 8;
+pub const XCB_EWMH_WM_MOVERESIZE_SIZE_KEYBOARD: xcb_ewmh_moveresize_direction_t = 9;
+pub const XCB_EWMH_WM_MOVERESIZE_MOVE_KEYBOARD: xcb_ewmh_moveresize_direction_t = 10;
+pub const XCB_EWMH_WM_MOVERESIZE_CANCEL: xcb_ewmh_moveresize_direction_t = 11;
+
+pub type xcb_ewmh_wm_state_action_t = c_uint;
+pub const XCB_EWMH_WM_STATE_REMOVE: xcb_ewmh_wm_state_action_t = 0;
+pub const XCB_EWMH_WM_STATE_ADD: xcb_ewmh_wm_state_action_t = 1;
+pub const XCB_EWMH_WM_STATE_TOGGLE: xcb_ewmh_wm_state_action_t = 2;
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_get_property_cookie_t {
+    pub sequence: c_uint,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_void_cookie_t {
+    pub sequence: c_uint,
+}
+
+/// Wire representation of a `GetProperty` reply.
+pub enum xcb_get_property_reply_t {}
+
+/// Errors of checked requests are reported through the out parameter, as by `libxcb`, errors of
+/// unchecked ones go to the event queue.
+pub type xcb_generic_error_t = xcb::ffi::xcb_generic_error_t;
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_ewmh_coordinates_t {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_ewmh_geometry_t {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[repr(C)]
+pub struct xcb_ewmh_get_atoms_reply_t {
+    pub atoms_len: u32,
+    pub atoms: *mut xcb_atom_t,
+}
+
+#[repr(C)]
+pub struct xcb_ewmh_get_windows_reply_t {
+    pub windows_len: u32,
+    pub windows: *mut xcb_window_t,
+}
+
+#[repr(C)]
+pub struct xcb_ewmh_get_utf8_strings_reply_t {
+    pub strings_len: u32,
+    pub strings: *mut c_char,
+}
+
+#[repr(C)]
+pub struct xcb_ewmh_get_desktop_viewport_reply_t {
+    pub desktop_viewport_len: u32,
+    pub desktop_viewport: *mut xcb_ewmh_coordinates_t,
+}
+
+#[repr(C)]
+pub struct xcb_ewmh_get_workarea_reply_t {
+    pub workarea_len: u32,
+    pub workarea: *mut xcb_ewmh_geometry_t,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_ewmh_get_desktop_layout_reply_t {
+    pub orientation: u32,
+    pub columns: u32,
+    pub rows: u32,
+    pub starting_corner: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_ewmh_get_extents_reply_t {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+macro_rules! connection {
+    ($($atom:ident,)*) => {
+        pub struct xcb_ewmh_connection_t {
+            connection: *const xcb::Connection,
+            screens: Vec<x::Window>,
+            /// Full sequence of the last request sent through the connection, from which the
+            /// 32-bit sequences of the cookies are widened back.
+            last_sequence: u64,
+            pub nb_screens: c_int,
+            pub _NET_WM_CM_Sn: *mut xcb_atom_t,
+            $(pub $atom: xcb_atom_t,)*
+        }
+
+        impl xcb_ewmh_connection_t {
+            /// Interns every atom known to the EWMH connection in a single round-trip.
+            pub fn new(connection: &xcb::Connection) -> xcb::Result<Self> {
+                let screens = connection
+                    .get_setup()
+                    .roots()
+                    .map(|screen| screen.root())
+                    .collect::<Vec<_>>();
+
+                $(
+                    let $atom = connection.send_request(&x::InternAtom {
+                        only_if_exists: false,
+                        name: stringify!($atom).as_bytes(),
+                    });
+                )*
+
+                let cm = (0..screens.len())
+                    .map(|screen| {
+                        connection.send_request(&x::InternAtom {
+                            only_if_exists: false,
+                            name: format!("_NET_WM_CM_S{}", screen).as_bytes(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                $(
+                    let $atom = connection.wait_for_reply($atom)?.atom().resource_id();
+                )*
+
+                let cm = cm
+                    .into_iter()
+                    .map(|cookie| Ok(connection.wait_for_reply(cookie)?.atom().resource_id()))
+                    .collect::<xcb::Result<Vec<_>>>()?;
+
+                Ok(Self {
+                    connection,
+                    nb_screens: screens.len() as c_int,
+                    screens,
+                    last_sequence: 0,
+                    _NET_WM_CM_Sn: into_raw(cm).1,
+                    $($atom,)*
+                })
+            }
+        }
+    };
+}
+
+connection! {
+    _NET_SUPPORTED,
+    _NET_CLIENT_LIST,
+    _NET_CLIENT_LIST_STACKING,
+    _NET_NUMBER_OF_DESKTOPS,
+    _NET_DESKTOP_GEOMETRY,
+    _NET_DESKTOP_VIEWPORT,
+    _NET_CURRENT_DESKTOP,
+    _NET_DESKTOP_NAMES,
+    _NET_ACTIVE_WINDOW,
+    _NET_WORKAREA,
+    _NET_SUPPORTING_WM_CHECK,
+    _NET_VIRTUAL_ROOTS,
+    _NET_DESKTOP_LAYOUT,
+    _NET_SHOWING_DESKTOP,
+    _NET_CLOSE_WINDOW,
+    _NET_MOVERESIZE_WINDOW,
+    _NET_WM_MOVERESIZE,
+    _NET_RESTACK_WINDOW,
+    _NET_REQUEST_FRAME_EXTENTS,
+    _NET_WM_NAME,
+    _NET_WM_VISIBLE_NAME,
+    _NET_WM_ICON_NAME,
+    _NET_WM_VISIBLE_ICON_NAME,
+    _NET_WM_DESKTOP,
+    _NET_WM_WINDOW_TYPE,
+    _NET_WM_STATE,
+    _NET_WM_ALLOWED_ACTIONS,
+    _NET_WM_STRUT,
+    _NET_WM_STRUT_PARTIAL,
+    _NET_WM_ICON_GEOMETRY,
+    _NET_WM_ICON,
+    _NET_WM_PID,
+    _NET_WM_HANDLED_ICONS,
+    _NET_WM_USER_TIME,
+    _NET_WM_USER_TIME_WINDOW,
+    _NET_FRAME_EXTENTS,
+    _NET_WM_PING,
+    _NET_WM_SYNC_REQUEST,
+    _NET_WM_SYNC_REQUEST_COUNTER,
+    _NET_WM_FULLSCREEN_MONITORS,
+    _NET_WM_FULL_PLACEMENT,
+    UTF8_STRING,
+    WM_PROTOCOLS,
+    MANAGER,
+    _NET_WM_WINDOW_TYPE_DESKTOP,
+    _NET_WM_WINDOW_TYPE_DOCK,
+    _NET_WM_WINDOW_TYPE_TOOLBAR,
+    _NET_WM_WINDOW_TYPE_MENU,
+    _NET_WM_WINDOW_TYPE_UTILITY,
+    _NET_WM_WINDOW_TYPE_SPLASH,
+    _NET_WM_WINDOW_TYPE_DIALOG,
+    _NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+    _NET_WM_WINDOW_TYPE_POPUP_MENU,
+    _NET_WM_WINDOW_TYPE_TOOLTIP,
+    _NET_WM_WINDOW_TYPE_NOTIFICATION,
+    _NET_WM_WINDOW_TYPE_COMBO,
+    _NET_WM_WINDOW_TYPE_DND,
+    _NET_WM_WINDOW_TYPE_NORMAL,
+    _NET_WM_STATE_MODAL,
+    _NET_WM_STATE_STICKY,
+    _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MAXIMIZED_HORZ,
+    _NET_WM_STATE_SHADED,
+    _NET_WM_STATE_SKIP_TASKBAR,
+    _NET_WM_STATE_SKIP_PAGER,
+    _NET_WM_STATE_HIDDEN,
+    _NET_WM_STATE_FULLSCREEN,
+    _NET_WM_STATE_ABOVE,
+    _NET_WM_STATE_BELOW,
+    _NET_WM_STATE_DEMANDS_ATTENTION,
+    _NET_WM_ACTION_MOVE,
+    _NET_WM_ACTION_RESIZE,
+    _NET_WM_ACTION_MINIMIZE,
+    _NET_WM_ACTION_SHADE,
+    _NET_WM_ACTION_STICK,
+    _NET_WM_ACTION_MAXIMIZE_HORZ,
+    _NET_WM_ACTION_MAXIMIZE_VERT,
+    _NET_WM_ACTION_FULLSCREEN,
+    _NET_WM_ACTION_CHANGE_DESKTOP,
+    _NET_WM_ACTION_CLOSE,
+    _NET_WM_ACTION_ABOVE,
+    _NET_WM_ACTION_BELOW,
+}
+
+/// Moves `data` to the heap and returns its length and pointer, to be released with
+/// [`from_raw`].
+fn into_raw<T>(data: Vec<T>) -> (u32, *mut T) {
+    let data = data.into_boxed_slice();
+
+    (data.len() as u32, Box::into_raw(data) as *mut T)
+}
+
+unsafe fn from_raw<T>(len: u32, data: *mut T) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            data,
+            len as usize,
+        )));
+    }
+}
+
+unsafe fn connection<'a>(ewmh: *mut xcb_ewmh_connection_t) -> &'a xcb::Connection {
+    &*(*ewmh).connection
+}
+
+/// Sends `request` and returns the lower 32 bits of its sequence, as held by the cookies.
+unsafe fn send<R: xcb::RawRequest>(
+    ewmh: *mut xcb_ewmh_connection_t,
+    request: &R,
+    checked: bool,
+) -> c_uint {
+    let sequence = request.raw_request(connection(ewmh), checked);
+    (*ewmh).last_sequence = sequence;
+
+    sequence as c_uint
+}
+
+/// The full sequence of a request given the lower 32 bits of it, which is the last one sent with
+/// these bits, as done by `libxcb`.
+fn widen(last_sequence: u64, sequence: c_uint) -> u64 {
+    let widened = (last_sequence & !u64::from(u32::MAX)) | u64::from(sequence);
+
+    if widened > last_sequence {
+        widened.checked_sub(1 << 32).unwrap_or(widened)
+    } else {
+        widened
+    }
+}
+
+/// The root window of `screen_nbr`, or `None` if the screen does not exist so that requests on it
+/// fail with `BadWindow`.
+unsafe fn root(ewmh: *mut xcb_ewmh_connection_t, screen_nbr: c_int) -> xcb_window_t {
+    let screens = &(*ewmh).screens;
+
+    usize::try_from(screen_nbr)
+        .ok()
+        .and_then(|screen_nbr| screens.get(screen_nbr))
+        .map_or(x::Window::none(), |root| *root)
+        .resource_id()
+}
+
+unsafe fn change_property<P: x::PropEl>(
+    ewmh: *mut xcb_ewmh_connection_t,
+    checked: bool,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+    r#type: xcb_atom_t,
+    data: &[P],
+) -> xcb_void_cookie_t {
+    let request = x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: x::Window::new(window),
+        property: x::Atom::new(property),
+        r#type: x::Atom::new(r#type),
+        data,
+    };
+
+    xcb_void_cookie_t {
+        sequence: send(ewmh, &request, checked),
+    }
+}
+
+unsafe fn get_property(
+    ewmh: *mut xcb_ewmh_connection_t,
+    checked: bool,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+    r#type: xcb_atom_t,
+    long_length: u32,
+) -> xcb_get_property_cookie_t {
+    let request = x::GetProperty {
+        delete: false,
+        window: x::Window::new(window),
+        property: x::Atom::new(property),
+        r#type: x::Atom::new(r#type),
+        long_offset: 0,
+        long_length,
+    };
+
+    xcb_get_property_cookie_t {
+        sequence: send(ewmh, &request, checked),
+    }
+}
+
+unsafe fn send_client_message(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    window: xcb_window_t,
+    r#type: xcb_atom_t,
+    data: &[u32],
+) -> xcb_void_cookie_t {
+    let mut padded = [0; 5];
+    padded[..data.len()].copy_from_slice(data);
+
+    let event = x::ClientMessageEvent::new(
+        x::Window::new(window),
+        x::Atom::new(r#type),
+        x::ClientMessageData::Data32(padded),
+    );
+
+    let request = x::SendEvent {
+        propagate: false,
+        destination: x::SendEventDest::Window(x::Window::new(root(ewmh, screen_nbr))),
+        event_mask: x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+        event: &event,
+    };
+
+    xcb_void_cookie_t {
+        sequence: send(ewmh, &request, false),
+    }
+}
+
+extern "C" {
+    fn xcb_wait_for_reply64(
+        c: *mut xcb::ffi::xcb_connection_t,
+        request: u64,
+        e: *mut *mut xcb_generic_error_t,
+    ) -> *mut c_void;
+}
+
+/// Fetches the reply for `cookie` and hands it to `from_reply`, returning `0` if the request
+/// failed.
+///
+/// The error of a checked request is stored in `e` when it is not null, and must then be freed
+/// by the caller. `libxcb` sends the errors of unchecked requests to the event queue.
+unsafe fn property_reply<T>(
+    ewmh: *mut xcb_ewmh_connection_t,
+    cookie: xcb_get_property_cookie_t,
+    out: *mut T,
+    e: *mut *mut xcb_generic_error_t,
+    from_reply: unsafe fn(*mut xcb_ewmh_connection_t, *mut T, &x::GetPropertyReply) -> u8,
+) -> u8 {
+    let sequence = widen((*ewmh).last_sequence, cookie.sequence);
+    let mut error = ptr::null_mut();
+
+    let reply = xcb_wait_for_reply64(connection(ewmh).get_raw_conn(), sequence, &mut error);
+
+    if !e.is_null() {
+        *e = error;
+    } else if !error.is_null() {
+        libc::free(error as *mut c_void);
+    }
+
+    if reply.is_null() {
+        return 0;
+    }
+
+    let reply = <x::GetPropertyReply as xcb::Reply>::from_raw(reply as *const u8);
+
+    from_reply(ewmh, out, &reply)
+}
+
+/// Borrows the reply behind a raw pointer without taking ownership of it.
+unsafe fn borrow_reply(r: *mut xcb_get_property_reply_t) -> ManuallyDrop<x::GetPropertyReply> {
+    ManuallyDrop::new(<x::GetPropertyReply as xcb::Reply>::from_raw(
+        r as *const u8,
+    ))
+}
+
+fn value<P: x::PropEl>(reply: &x::GetPropertyReply, r#type: xcb_atom_t) -> Option<&[P]> {
+    if reply.r#type().resource_id() == r#type && reply.format() == P::FORMAT {
+        Some(reply.value::<P>())
+    } else {
+        None
+    }
+}
+
+unsafe fn cardinal_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut u32,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some([value, ..]) => {
+            *out = *value;
+            1
+        }
+        _ => 0,
+    }
+}
+
+unsafe fn window_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_window_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_WINDOW.resource_id()) {
+        Some([value, ..]) => {
+            *out = *value;
+            1
+        }
+        _ => 0,
+    }
+}
+
+unsafe fn atoms_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_atoms_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_ATOM.resource_id()) {
+        Some(atoms) => {
+            let (atoms_len, atoms) = into_raw(atoms.to_vec());
+            *out = xcb_ewmh_get_atoms_reply_t { atoms_len, atoms };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe fn windows_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_windows_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_WINDOW.resource_id()) {
+        Some(windows) => {
+            let (windows_len, windows) = into_raw(windows.to_vec());
+            *out = xcb_ewmh_get_windows_reply_t {
+                windows_len,
+                windows,
+            };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe fn utf8_strings_from_reply(
+    ewmh: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_utf8_strings_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u8>(reply, (*ewmh).UTF8_STRING) {
+        Some(strings) => {
+            let (strings_len, strings) = into_raw(strings.to_vec());
+            *out = xcb_ewmh_get_utf8_strings_reply_t {
+                strings_len,
+                strings: strings as *mut c_char,
+            };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe fn desktop_geometry_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut (*mut u32, *mut u32),
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some([width, height, ..]) => {
+            *(*out).0 = *width;
+            *(*out).1 = *height;
+            1
+        }
+        _ => 0,
+    }
+}
+
+unsafe fn desktop_viewport_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_desktop_viewport_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some(values) => {
+            let coordinates = values
+                .chunks_exact(2)
+                .map(|c| xcb_ewmh_coordinates_t { x: c[0], y: c[1] })
+                .collect();
+            let (desktop_viewport_len, desktop_viewport) = into_raw(coordinates);
+            *out = xcb_ewmh_get_desktop_viewport_reply_t {
+                desktop_viewport_len,
+                desktop_viewport,
+            };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe fn workarea_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_workarea_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some(values) => {
+            let geometries = values
+                .chunks_exact(4)
+                .map(|g| xcb_ewmh_geometry_t {
+                    x: g[0],
+                    y: g[1],
+                    width: g[2],
+                    height: g[3],
+                })
+                .collect();
+            let (workarea_len, workarea) = into_raw(geometries);
+            *out = xcb_ewmh_get_workarea_reply_t {
+                workarea_len,
+                workarea,
+            };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe fn desktop_layout_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_desktop_layout_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some(&[orientation, columns, rows, ref rest @ ..]) => {
+            *out = xcb_ewmh_get_desktop_layout_reply_t {
+                orientation,
+                columns,
+                rows,
+                starting_corner: rest.first().copied().unwrap_or(XCB_EWMH_WM_TOPLEFT),
+            };
+            1
+        }
+        _ => 0,
+    }
+}
+
+unsafe fn extents_from_reply(
+    _: *mut xcb_ewmh_connection_t,
+    out: *mut xcb_ewmh_get_extents_reply_t,
+    reply: &x::GetPropertyReply,
+) -> u8 {
+    match value::<u32>(reply, x::ATOM_CARDINAL.resource_id()) {
+        Some(&[left, right, top, bottom, ..]) => {
+            *out = xcb_ewmh_get_extents_reply_t {
+                left,
+                right,
+                top,
+                bottom,
+            };
+            1
+        }
+        _ => 0,
+    }
+}
+
+pub unsafe fn xcb_ewmh_connection_wipe(ewmh: *mut xcb_ewmh_connection_t) {
+    from_raw((*ewmh).nb_screens as u32, (*ewmh)._NET_WM_CM_Sn);
+    (*ewmh)._NET_WM_CM_Sn = ptr::null_mut();
+}
+
+pub unsafe fn xcb_ewmh_get_atoms_reply_wipe(data: *mut xcb_ewmh_get_atoms_reply_t) {
+    from_raw((*data).atoms_len, (*data).atoms);
+}
+
+pub unsafe fn xcb_ewmh_get_windows_reply_wipe(data: *mut xcb_ewmh_get_windows_reply_t) {
+    from_raw((*data).windows_len, (*data).windows);
+}
+
+pub unsafe fn xcb_ewmh_get_utf8_strings_reply_wipe(data: *mut xcb_ewmh_get_utf8_strings_reply_t) {
+    from_raw((*data).strings_len, (*data).strings);
+}
+
+pub unsafe fn xcb_ewmh_get_desktop_viewport_reply_wipe(
+    data: *mut xcb_ewmh_get_desktop_viewport_reply_t,
+) {
+    from_raw((*data).desktop_viewport_len, (*data).desktop_viewport);
+}
+
+pub unsafe fn xcb_ewmh_get_workarea_reply_wipe(data: *mut xcb_ewmh_get_workarea_reply_t) {
+    from_raw((*data).workarea_len, (*data).workarea);
+}
+
+/// Generates the `get`, `get_unchecked`, `get_reply` and `get_from_reply` functions of a
+/// property. The `screen` form reads it on the root window of a screen, the `window` form on any
+/// window.
+macro_rules! get_property {
+    (
+        $form:ident $get:ident, $get_unchecked:ident, $reply:ident, $from_reply:ident,
+        $atom:ident: $type:expr, $len:expr => $out:ty, $parse:ident
+    ) => {
+        get_property!(@get $form $get, $get_unchecked, $atom: $type, $len);
+        get_property!(@reply $reply => $out, $parse);
+
+        pub unsafe fn $from_reply(out: *mut $out, r: *mut xcb_get_property_reply_t) -> u8 {
+            $parse(ptr::null_mut(), out, &borrow_reply(r))
+        }
+    };
+
+    (@get screen $get:ident, $get_unchecked:ident, $atom:ident: $type:expr, $len:expr) => {
+        pub unsafe fn $get(
+            ewmh: *mut xcb_ewmh_connection_t,
+            screen_nbr: c_int,
+        ) -> xcb_get_property_cookie_t {
+            let r#type = $type(ewmh);
+            get_property(ewmh, true, root(ewmh, screen_nbr), (*ewmh).$atom, r#type, $len)
+        }
+
+        pub unsafe fn $get_unchecked(
+            ewmh: *mut xcb_ewmh_connection_t,
+            screen_nbr: c_int,
+        ) -> xcb_get_property_cookie_t {
+            let r#type = $type(ewmh);
+            get_property(ewmh, false, root(ewmh, screen_nbr), (*ewmh).$atom, r#type, $len)
+        }
+    };
+
+    (@get window $get:ident, $get_unchecked:ident, $atom:ident: $type:expr, $len:expr) => {
+        pub unsafe fn $get(
+            ewmh: *mut xcb_ewmh_connection_t,
+            window: xcb_window_t,
+        ) -> xcb_get_property_cookie_t {
+            let r#type = $type(ewmh);
+            get_property(ewmh, true, window, (*ewmh).$atom, r#type, $len)
+        }
+
+        pub unsafe fn $get_unchecked(
+            ewmh: *mut xcb_ewmh_connection_t,
+            window: xcb_window_t,
+        ) -> xcb_get_property_cookie_t {
+            let r#type = $type(ewmh);
+            get_property(ewmh, false, window, (*ewmh).$atom, r#type, $len)
+        }
+    };
+
+    (@reply $reply:ident => $out:ty, $parse:ident) => {
+        pub unsafe fn $reply(
+            ewmh: *mut xcb_ewmh_connection_t,
+            cookie: xcb_get_property_cookie_t,
+            out: *mut $out,
+            e: *mut *mut xcb_generic_error_t,
+        ) -> u8 {
+            property_reply(ewmh, cookie, out, e, $parse)
+        }
+    };
+}
+
+/// Same as `get_property!` for UTF8_STRING properties, whose `from_reply` function needs the
+/// connection to know the `UTF8_STRING` atom.
+macro_rules! get_utf8_property {
+    (
+        $form:ident $get:ident, $get_unchecked:ident, $reply:ident, $from_reply:ident,
+        $atom:ident
+    ) => {
+        get_property!(@get $form $get, $get_unchecked, $atom: utf8_string, u32::MAX);
+        get_property!(@reply $reply => xcb_ewmh_get_utf8_strings_reply_t, utf8_strings_from_reply);
+
+        pub unsafe fn $from_reply(
+            ewmh: *mut xcb_ewmh_connection_t,
+            out: *mut xcb_ewmh_get_utf8_strings_reply_t,
+            r: *mut xcb_get_property_reply_t,
+        ) -> u8 {
+            utf8_strings_from_reply(ewmh, out, &borrow_reply(r))
+        }
+    };
+}
+
+unsafe fn cardinal(_: *mut xcb_ewmh_connection_t) -> xcb_atom_t {
+    x::ATOM_CARDINAL.resource_id()
+}
+
+unsafe fn window(_: *mut xcb_ewmh_connection_t) -> xcb_atom_t {
+    x::ATOM_WINDOW.resource_id()
+}
+
+unsafe fn atom(_: *mut xcb_ewmh_connection_t) -> xcb_atom_t {
+    x::ATOM_ATOM.resource_id()
+}
+
+unsafe fn utf8_string(ewmh: *mut xcb_ewmh_connection_t) -> xcb_atom_t {
+    (*ewmh).UTF8_STRING
+}
+
+/// Generates the checked and unchecked variants of a property setter.
+macro_rules! set_property {
+    (
+        $set:ident, $set_checked:ident($($arg:ident: $arg_ty:ty),*)
+        => $window:expr, $atom:ident: $type:expr, $data:expr
+    ) => {
+        pub unsafe fn $set(
+            ewmh: *mut xcb_ewmh_connection_t,
+            $($arg: $arg_ty),*
+        ) -> xcb_void_cookie_t {
+            let (window, r#type) = ($window(ewmh), $type(ewmh));
+            change_property(ewmh, false, window, (*ewmh).$atom, r#type, $data)
+        }
+
+        pub unsafe fn $set_checked(
+            ewmh: *mut xcb_ewmh_connection_t,
+            $($arg: $arg_ty),*
+        ) -> xcb_void_cookie_t {
+            let (window, r#type) = ($window(ewmh), $type(ewmh));
+            change_property(ewmh, true, window, (*ewmh).$atom, r#type, $data)
+        }
+    };
+}
+
+get_property!(
+    screen xcb_ewmh_get_supported, xcb_ewmh_get_supported_unchecked,
+    xcb_ewmh_get_supported_reply, xcb_ewmh_get_supported_from_reply,
+    _NET_SUPPORTED: atom, u32::MAX => xcb_ewmh_get_atoms_reply_t, atoms_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_supported, xcb_ewmh_set_supported_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_atom_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_SUPPORTED: atom,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+get_property!(
+    screen xcb_ewmh_get_client_list, xcb_ewmh_get_client_list_unchecked,
+    xcb_ewmh_get_client_list_reply, xcb_ewmh_get_client_list_from_reply,
+    _NET_CLIENT_LIST: window, u32::MAX => xcb_ewmh_get_windows_reply_t, windows_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_client_list, xcb_ewmh_set_client_list_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_window_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_CLIENT_LIST: window,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+get_property!(
+    screen xcb_ewmh_get_client_list_stacking, xcb_ewmh_get_client_list_stacking_unchecked,
+    xcb_ewmh_get_client_list_stacking_reply, xcb_ewmh_get_client_list_stacking_from_reply,
+    _NET_CLIENT_LIST_STACKING: window, u32::MAX => xcb_ewmh_get_windows_reply_t,
+    windows_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_client_list_stacking, xcb_ewmh_set_client_list_stacking_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_window_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_CLIENT_LIST_STACKING: window,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+get_property!(
+    screen xcb_ewmh_get_number_of_desktops, xcb_ewmh_get_number_of_desktops_unchecked,
+    xcb_ewmh_get_number_of_desktops_reply, xcb_ewmh_get_number_of_desktops_from_reply,
+    _NET_NUMBER_OF_DESKTOPS: cardinal, 1 => u32, cardinal_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_number_of_desktops, xcb_ewmh_set_number_of_desktops_checked(
+        screen_nbr: c_int, number_of_desktops: u32
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_NUMBER_OF_DESKTOPS: cardinal,
+    &[number_of_desktops]
+);
+
+pub unsafe fn xcb_ewmh_request_change_number_of_desktops(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    new_number_of_desktops: u32,
+) -> xcb_void_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_NUMBER_OF_DESKTOPS;
+    send_client_message(ewmh, screen_nbr, root, atom, &[new_number_of_desktops])
+}
+
+pub unsafe fn xcb_ewmh_get_desktop_geometry(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+) -> xcb_get_property_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_DESKTOP_GEOMETRY;
+    get_property(ewmh, true, root, atom, cardinal(ewmh), 2)
+}
+
+pub unsafe fn xcb_ewmh_get_desktop_geometry_unchecked(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+) -> xcb_get_property_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_DESKTOP_GEOMETRY;
+    get_property(ewmh, false, root, atom, cardinal(ewmh), 2)
+}
+
+pub unsafe fn xcb_ewmh_get_desktop_geometry_reply(
+    ewmh: *mut xcb_ewmh_connection_t,
+    cookie: xcb_get_property_cookie_t,
+    width: *mut u32,
+    height: *mut u32,
+    e: *mut *mut xcb_generic_error_t,
+) -> u8 {
+    property_reply(
+        ewmh,
+        cookie,
+        &mut (width, height),
+        e,
+        desktop_geometry_from_reply,
+    )
+}
+
+pub unsafe fn xcb_ewmh_get_desktop_geometry_from_reply(
+    width: *mut u32,
+    height: *mut u32,
+    r: *mut xcb_get_property_reply_t,
+) -> u8 {
+    desktop_geometry_from_reply(ptr::null_mut(), &mut (width, height), &borrow_reply(r))
+}
+
+set_property!(
+    xcb_ewmh_set_desktop_geometry, xcb_ewmh_set_desktop_geometry_checked(
+        screen_nbr: c_int, new_width: u32, new_height: u32
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_DESKTOP_GEOMETRY: cardinal,
+    &[new_width, new_height]
+);
+
+pub unsafe fn xcb_ewmh_request_change_desktop_geometry(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    new_width: u32,
+    new_height: u32,
+) -> xcb_void_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_DESKTOP_GEOMETRY;
+    send_client_message(ewmh, screen_nbr, root, atom, &[new_width, new_height])
+}
+
+get_property!(
+    screen xcb_ewmh_get_desktop_viewport, xcb_ewmh_get_desktop_viewport_unchecked,
+    xcb_ewmh_get_desktop_viewport_reply, xcb_ewmh_get_desktop_viewport_from_reply,
+    _NET_DESKTOP_VIEWPORT: cardinal, u32::MAX => xcb_ewmh_get_desktop_viewport_reply_t,
+    desktop_viewport_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_desktop_viewport, xcb_ewmh_set_desktop_viewport_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_ewmh_coordinates_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_DESKTOP_VIEWPORT: cardinal,
+    slice::from_raw_parts(list as *const u32, list_len as usize * 2)
+);
+
+pub unsafe fn xcb_ewmh_request_change_desktop_viewport(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    x: u32,
+    y: u32,
+) -> xcb_void_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_DESKTOP_VIEWPORT;
+    send_client_message(ewmh, screen_nbr, root, atom, &[x, y])
+}
+
+get_property!(
+    screen xcb_ewmh_get_current_desktop, xcb_ewmh_get_current_desktop_unchecked,
+    xcb_ewmh_get_current_desktop_reply, xcb_ewmh_get_current_desktop_from_reply,
+    _NET_CURRENT_DESKTOP: cardinal, 1 => u32, cardinal_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_current_desktop, xcb_ewmh_set_current_desktop_checked(
+        screen_nbr: c_int, new_current_desktop: u32
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_CURRENT_DESKTOP: cardinal,
+    &[new_current_desktop]
+);
+
+pub unsafe fn xcb_ewmh_request_change_current_desktop(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    new_desktop: u32,
+    timestamp: xcb_timestamp_t,
+) -> xcb_void_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_CURRENT_DESKTOP;
+    send_client_message(ewmh, screen_nbr, root, atom, &[new_desktop, timestamp])
+}
+
+get_utf8_property!(
+    screen xcb_ewmh_get_desktop_names, xcb_ewmh_get_desktop_names_unchecked,
+    xcb_ewmh_get_desktop_names_reply, xcb_ewmh_get_desktop_names_from_reply,
+    _NET_DESKTOP_NAMES
+);
+
+set_property!(
+    xcb_ewmh_set_desktop_names, xcb_ewmh_set_desktop_names_checked(
+        screen_nbr: c_int, strings_len: u32, strings: *const c_char
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_DESKTOP_NAMES: utf8_string,
+    slice::from_raw_parts(strings as *const u8, strings_len as usize)
+);
+
+get_property!(
+    screen xcb_ewmh_get_active_window, xcb_ewmh_get_active_window_unchecked,
+    xcb_ewmh_get_active_window_reply, xcb_ewmh_get_active_window_from_reply,
+    _NET_ACTIVE_WINDOW: window, 1 => xcb_window_t, window_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_active_window, xcb_ewmh_set_active_window_checked(
+        screen_nbr: c_int, new_active_window: xcb_window_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_ACTIVE_WINDOW: window,
+    &[new_active_window]
+);
+
+pub unsafe fn xcb_ewmh_request_change_active_window(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    window_to_activate: xcb_window_t,
+    source_indication: xcb_ewmh_client_source_type_t,
+    timestamp: xcb_timestamp_t,
+    current_active_window: xcb_window_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_ACTIVE_WINDOW;
+    let data = [source_indication, timestamp, current_active_window];
+    send_client_message(ewmh, screen_nbr, window_to_activate, atom, &data)
+}
+
+get_property!(
+    screen xcb_ewmh_get_workarea, xcb_ewmh_get_workarea_unchecked,
+    xcb_ewmh_get_workarea_reply, xcb_ewmh_get_workarea_from_reply,
+    _NET_WORKAREA: cardinal, u32::MAX => xcb_ewmh_get_workarea_reply_t, workarea_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_workarea, xcb_ewmh_set_workarea_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_ewmh_geometry_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_WORKAREA: cardinal,
+    slice::from_raw_parts(list as *const u32, list_len as usize * 4)
+);
+
+get_property!(
+    window xcb_ewmh_get_supporting_wm_check, xcb_ewmh_get_supporting_wm_check_unchecked,
+    xcb_ewmh_get_supporting_wm_check_reply, xcb_ewmh_get_supporting_wm_check_from_reply,
+    _NET_SUPPORTING_WM_CHECK: window, 1 => xcb_window_t, window_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_supporting_wm_check, xcb_ewmh_set_supporting_wm_check_checked(
+        parent_window: xcb_window_t, child_window: xcb_window_t
+    ) => |_| parent_window, _NET_SUPPORTING_WM_CHECK: window,
+    &[child_window]
+);
+
+get_property!(
+    screen xcb_ewmh_get_virtual_roots, xcb_ewmh_get_virtual_roots_unchecked,
+    xcb_ewmh_get_virtual_roots_reply, xcb_ewmh_get_virtual_roots_from_reply,
+    _NET_VIRTUAL_ROOTS: window, u32::MAX => xcb_ewmh_get_windows_reply_t, windows_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_virtual_roots, xcb_ewmh_set_virtual_roots_checked(
+        screen_nbr: c_int, list_len: u32, list: *mut xcb_window_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_VIRTUAL_ROOTS: window,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+get_property!(
+    screen xcb_ewmh_get_desktop_layout, xcb_ewmh_get_desktop_layout_unchecked,
+    xcb_ewmh_get_desktop_layout_reply, xcb_ewmh_get_desktop_layout_from_reply,
+    _NET_DESKTOP_LAYOUT: cardinal, 4 => xcb_ewmh_get_desktop_layout_reply_t,
+    desktop_layout_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_desktop_layout, xcb_ewmh_set_desktop_layout_checked(
+        screen_nbr: c_int,
+        orientation: xcb_ewmh_desktop_layout_orientation_t,
+        columns: u32,
+        rows: u32,
+        starting_corner: xcb_ewmh_desktop_layout_starting_corner_t
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_DESKTOP_LAYOUT: cardinal,
+    &[orientation, columns, rows, starting_corner]
+);
+
+get_property!(
+    screen xcb_ewmh_get_showing_desktop, xcb_ewmh_get_showing_desktop_unchecked,
+    xcb_ewmh_get_showing_desktop_reply, xcb_ewmh_get_showing_desktop_from_reply,
+    _NET_SHOWING_DESKTOP: cardinal, 1 => u32, cardinal_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_showing_desktop, xcb_ewmh_set_showing_desktop_checked(
+        screen_nbr: c_int, desktop: u32
+    ) => |ewmh| root(ewmh, screen_nbr), _NET_SHOWING_DESKTOP: cardinal,
+    &[desktop]
+);
+
+pub unsafe fn xcb_ewmh_request_change_showing_desktop(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    enter: u32,
+) -> xcb_void_cookie_t {
+    let root = root(ewmh, screen_nbr);
+    let atom = (*ewmh)._NET_SHOWING_DESKTOP;
+    send_client_message(ewmh, screen_nbr, root, atom, &[enter])
+}
+
+pub unsafe fn xcb_ewmh_request_close_window(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    window_to_close: xcb_window_t,
+    timestamp: xcb_timestamp_t,
+    source_indication: xcb_ewmh_client_source_type_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_CLOSE_WINDOW;
+    let data = [timestamp, source_indication];
+    send_client_message(ewmh, screen_nbr, window_to_close, atom, &data)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn xcb_ewmh_request_moveresize_window(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    moveresize_window: xcb_window_t,
+    gravity: u32,
+    source_indication: xcb_ewmh_client_source_type_t,
+    flags: xcb_ewmh_moveresize_window_opt_flags_t,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_MOVERESIZE_WINDOW;
+    let data = [
+        gravity | flags | source_indication << 12,
+        x,
+        y,
+        width,
+        height,
+    ];
+    send_client_message(ewmh, screen_nbr, moveresize_window, atom, &data)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn xcb_ewmh_request_wm_moveresize(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    moveresize_window: xcb_window_t,
+    x_root: u32,
+    y_root: u32,
+    direction: xcb_ewmh_moveresize_direction_t,
+    button: u32,
+    source_indication: xcb_ewmh_client_source_type_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_WM_MOVERESIZE;
+    let data = [x_root, y_root, direction, button, source_indication];
+    send_client_message(ewmh, screen_nbr, moveresize_window, atom, &data)
+}
+
+pub unsafe fn xcb_ewmh_request_restack_window(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    window_to_restack: xcb_window_t,
+    sibling_window: xcb_window_t,
+    detail: u32,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_RESTACK_WINDOW;
+    let data = [XCB_EWMH_CLIENT_SOURCE_TYPE_OTHER, sibling_window, detail];
+    send_client_message(ewmh, screen_nbr, window_to_restack, atom, &data)
+}
+
+pub unsafe fn xcb_ewmh_request_frame_extents(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    client_window: xcb_window_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_REQUEST_FRAME_EXTENTS;
+    send_client_message(ewmh, screen_nbr, client_window, atom, &[])
+}
+
+get_utf8_property!(
+    window xcb_ewmh_get_wm_name, xcb_ewmh_get_wm_name_unchecked,
+    xcb_ewmh_get_wm_name_reply, xcb_ewmh_get_wm_name_from_reply,
+    _NET_WM_NAME
+);
+
+set_property!(
+    xcb_ewmh_set_wm_name, xcb_ewmh_set_wm_name_checked(
+        window: xcb_window_t, strings_len: u32, strings: *const c_char
+    ) => |_| window, _NET_WM_NAME: utf8_string,
+    slice::from_raw_parts(strings as *const u8, strings_len as usize)
+);
+
+get_utf8_property!(
+    window xcb_ewmh_get_wm_visible_name, xcb_ewmh_get_wm_visible_name_unchecked,
+    xcb_ewmh_get_wm_visible_name_reply, xcb_ewmh_get_wm_visible_name_from_reply,
+    _NET_WM_VISIBLE_NAME
+);
+
+set_property!(
+    xcb_ewmh_set_wm_visible_name, xcb_ewmh_set_wm_visible_name_checked(
+        window: xcb_window_t, strings_len: u32, strings: *const c_char
+    ) => |_| window, _NET_WM_VISIBLE_NAME: utf8_string,
+    slice::from_raw_parts(strings as *const u8, strings_len as usize)
+);
+
+get_utf8_property!(
+    window xcb_ewmh_get_wm_icon_name, xcb_ewmh_get_wm_icon_name_unchecked,
+    xcb_ewmh_get_wm_icon_name_reply, xcb_ewmh_get_wm_icon_name_from_reply,
+    _NET_WM_ICON_NAME
+);
+
+set_property!(
+    xcb_ewmh_set_wm_icon_name, xcb_ewmh_set_wm_icon_name_checked(
+        window: xcb_window_t, strings_len: u32, strings: *const c_char
+    ) => |_| window, _NET_WM_ICON_NAME: utf8_string,
+    slice::from_raw_parts(strings as *const u8, strings_len as usize)
+);
+
+get_utf8_property!(
+    window xcb_ewmh_get_wm_visible_icon_name, xcb_ewmh_get_wm_visible_icon_name_unchecked,
+    xcb_ewmh_get_wm_visible_icon_name_reply, xcb_ewmh_get_wm_visible_icon_name_from_reply,
+    _NET_WM_VISIBLE_ICON_NAME
+);
+
+set_property!(
+    xcb_ewmh_set_wm_visible_icon_name, xcb_ewmh_set_wm_visible_icon_name_checked(
+        window: xcb_window_t, strings_len: u32, strings: *const c_char
+    ) => |_| window, _NET_WM_VISIBLE_ICON_NAME: utf8_string,
+    slice::from_raw_parts(strings as *const u8, strings_len as usize)
+);
+
+get_property!(
+    window xcb_ewmh_get_wm_desktop, xcb_ewmh_get_wm_desktop_unchecked,
+    xcb_ewmh_get_wm_desktop_reply, xcb_ewmh_get_wm_desktop_from_reply,
+    _NET_WM_DESKTOP: cardinal, 1 => u32, cardinal_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_wm_desktop, xcb_ewmh_set_wm_desktop_checked(
+        window: xcb_window_t, desktop: u32
+    ) => |_| window, _NET_WM_DESKTOP: cardinal,
+    &[desktop]
+);
+
+pub unsafe fn xcb_ewmh_request_change_wm_desktop(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    client_window: xcb_window_t,
+    new_desktop: u32,
+    source_indication: xcb_ewmh_client_source_type_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_WM_DESKTOP;
+    let data = [new_desktop, source_indication];
+    send_client_message(ewmh, screen_nbr, client_window, atom, &data)
+}
+
+get_property!(
+    window xcb_ewmh_get_wm_window_type, xcb_ewmh_get_wm_window_type_unchecked,
+    xcb_ewmh_get_wm_window_type_reply, xcb_ewmh_get_wm_window_type_from_reply,
+    _NET_WM_WINDOW_TYPE: atom, u32::MAX => xcb_ewmh_get_atoms_reply_t, atoms_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_wm_window_type, xcb_ewmh_set_wm_window_type_checked(
+        window: xcb_window_t, list_len: u32, list: *mut xcb_atom_t
+    ) => |_| window, _NET_WM_WINDOW_TYPE: atom,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+get_property!(
+    window xcb_ewmh_get_wm_state, xcb_ewmh_get_wm_state_unchecked,
+    xcb_ewmh_get_wm_state_reply, xcb_ewmh_get_wm_state_from_reply,
+    _NET_WM_STATE: atom, u32::MAX => xcb_ewmh_get_atoms_reply_t, atoms_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_wm_state, xcb_ewmh_set_wm_state_checked(
+        window: xcb_window_t, list_len: u32, list: *mut xcb_atom_t
+    ) => |_| window, _NET_WM_STATE: atom,
+    slice::from_raw_parts(list, list_len as usize)
+);
+
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn xcb_ewmh_request_change_wm_state(
+    ewmh: *mut xcb_ewmh_connection_t,
+    screen_nbr: c_int,
+    client_window: xcb_window_t,
+    action: xcb_ewmh_wm_state_action_t,
+    first_property: xcb_atom_t,
+    second_property: xcb_atom_t,
+    source_indication: xcb_ewmh_client_source_type_t,
+) -> xcb_void_cookie_t {
+    let atom = (*ewmh)._NET_WM_STATE;
+    let data = [action, first_property, second_property, source_indication];
+    send_client_message(ewmh, screen_nbr, client_window, atom, &data)
+}
+
+get_property!(
+    window xcb_ewmh_get_frame_extents, xcb_ewmh_get_frame_extents_unchecked,
+    xcb_ewmh_get_frame_extents_reply, xcb_ewmh_get_frame_extents_from_reply,
+    _NET_FRAME_EXTENTS: cardinal, 4 => xcb_ewmh_get_extents_reply_t, extents_from_reply
+);
+
+set_property!(
+    xcb_ewmh_set_frame_extents, xcb_ewmh_set_frame_extents_checked(
+        window: xcb_window_t, left: u32, right: u32, top: u32, bottom: u32
+    ) => |_| window, _NET_FRAME_EXTENTS: cardinal,
+    &[left, right, top, bottom]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a `GetProperty` reply as received from the server, with format 32 `values`.
+    fn property(r#type: x::Atom, values: &[u32]) -> x::GetPropertyReply {
+        let mut wire = vec![0; 32];
+        wire[0] = 1;
+        wire[1] = 32;
+        wire[4..8].copy_from_slice(&(values.len() as u32).to_ne_bytes());
+        wire[8..12].copy_from_slice(&r#type.resource_id().to_ne_bytes());
+        wire[16..20].copy_from_slice(&(values.len() as u32).to_ne_bytes());
+
+        for value in values {
+            wire.extend_from_slice(&value.to_ne_bytes());
+        }
+
+        unsafe {
+            let raw = libc::malloc(wire.len()) as *mut u8;
+            ptr::copy_nonoverlapping(wire.as_ptr(), raw, wire.len());
+
+            <x::GetPropertyReply as xcb::Reply>::from_raw(raw)
+        }
+    }
+
+    #[test]
+    fn cardinal() {
+        let mut value = 0;
+
+        unsafe {
+            let reply = property(x::ATOM_CARDINAL, &[3, 4]);
+            assert_eq!(cardinal_from_reply(ptr::null_mut(), &mut value, &reply), 1);
+            assert_eq!(value, 3);
+
+            let reply = property(x::ATOM_CARDINAL, &[]);
+            assert_eq!(cardinal_from_reply(ptr::null_mut(), &mut value, &reply), 0);
+        }
+    }
+
+    #[test]
+    fn mismatched_type() {
+        let mut window = 0;
+        let mut extents = xcb_ewmh_get_extents_reply_t {
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+        };
+
+        unsafe {
+            let reply = property(x::ATOM_CARDINAL, &[42]);
+            assert_eq!(window_from_reply(ptr::null_mut(), &mut window, &reply), 0);
+
+            let reply = property(x::ATOM_INTEGER, &[1, 2, 3, 4]);
+            assert_eq!(extents_from_reply(ptr::null_mut(), &mut extents, &reply), 0);
+        }
+    }
+
+    #[test]
+    fn extents() {
+        let mut extents = xcb_ewmh_get_extents_reply_t {
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+        };
+
+        unsafe {
+            let reply = property(x::ATOM_CARDINAL, &[1, 2, 3, 4]);
+            assert_eq!(extents_from_reply(ptr::null_mut(), &mut extents, &reply), 1);
+        }
+
+        assert_eq!(
+            (extents.left, extents.right, extents.top, extents.bottom),
+            (1, 2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn desktop_layout() {
+        let mut layout = xcb_ewmh_get_desktop_layout_reply_t {
+            orientation: 0,
+            columns: 0,
+            rows: 0,
+            starting_corner: XCB_EWMH_WM_BOTTOMLEFT,
+        };
+
+        unsafe {
+            let reply = property(x::ATOM_CARDINAL, &[XCB_EWMH_WM_ORIENTATION_VERT, 2, 3]);
+            assert_eq!(
+                desktop_layout_from_reply(ptr::null_mut(), &mut layout, &reply),
+                1
+            );
+        }
+
+        assert_eq!(layout.orientation, XCB_EWMH_WM_ORIENTATION_VERT);
+        assert_eq!((layout.columns, layout.rows), (2, 3));
+        assert_eq!(layout.starting_corner, XCB_EWMH_WM_TOPLEFT);
+    }
+
+    #[test]
+    fn workarea() {
+        let mut workarea = xcb_ewmh_get_workarea_reply_t {
+            workarea_len: 0,
+            workarea: ptr::null_mut(),
+        };
+
+        unsafe {
+            // The trailing incomplete geometry is ignored.
+            let reply = property(
+                x::ATOM_CARDINAL,
+                &[0, 24, 1920, 1056, 1920, 0, 1280, 1024, 7],
+            );
+            assert_eq!(
+                workarea_from_reply(ptr::null_mut(), &mut workarea, &reply),
+                1
+            );
+
+            let geometries = slice::from_raw_parts(workarea.workarea, workarea.workarea_len as _)
+                .iter()
+                .map(|g| (g.x, g.y, g.width, g.height))
+                .collect::<Vec<_>>();
+
+            assert_eq!(geometries, [(0, 24, 1920, 1056), (1920, 0, 1280, 1024)]);
+
+            xcb_ewmh_get_workarea_reply_wipe(&mut workarea);
+        }
+    }
+
+    #[test]
+    fn client_list() {
+        let mut windows = xcb_ewmh_get_windows_reply_t {
+            windows_len: 0,
+            windows: ptr::null_mut(),
+        };
+
+        unsafe {
+            let reply = property(x::ATOM_WINDOW, &[0x40_0001, 0x60_0003]);
+            assert_eq!(windows_from_reply(ptr::null_mut(), &mut windows, &reply), 1);
+            assert_eq!(
+                slice::from_raw_parts(windows.windows, windows.windows_len as _),
+                [0x40_0001, 0x60_0003]
+            );
+
+            xcb_ewmh_get_windows_reply_wipe(&mut windows);
+        }
+    }
+
+    #[test]
+    fn widen_sequences() {
+        assert_eq!(widen(5, 5), 5);
+        assert_eq!(widen(10, 3), 3);
+        assert_eq!(widen(0x1_0000_0002, 1), 0x1_0000_0001);
+        assert_eq!(widen(0x1_0000_0002, u32::MAX), 0xffff_ffff);
+        assert_eq!(widen(0x7_0000_0000, 0xffff_fff0), 0x6_ffff_fff0);
+    }
+}
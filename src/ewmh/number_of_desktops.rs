@@ -3,8 +3,8 @@ use std::ptr;
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetNumberOfDesktops {
@@ -130,6 +130,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetNumberOfDesktopsCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             Ok(Self::Reply { raw, number })
         }
     }
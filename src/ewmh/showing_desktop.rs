@@ -3,8 +3,8 @@ use std::ptr;
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetShowingDesktop {
@@ -126,6 +126,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetShowingDesktopCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             Ok(Self::Reply { raw, desktop })
         }
     }
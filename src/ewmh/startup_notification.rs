@@ -0,0 +1,607 @@
+use std::{
+    collections::VecDeque,
+    convert::TryFrom,
+    fmt, process, str,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+};
+
+pub struct SetStartupId<'a> {
+    pub window: x::Window,
+    pub startup_id: &'a str,
+}
+
+unsafe impl<'a> RawEwmhRequest for SetStartupId<'a> {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.STARTUP_ID(),
+                r#type: ewmh.UTF8_STRING(),
+                data: self.startup_id.as_bytes(),
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl<'a> EwmhRequest for SetStartupId<'a> {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl<'a> EwmhRequestWithoutReply for SetStartupId<'a> {}
+
+// TODO: Expose inner reply
+pub struct GetStartupIdReply(x::GetPropertyReply);
+
+impl EwmhReply for GetStartupIdReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetStartupIdReply {
+    /// The startup ID, or `None` if the property is not set or is not valid UTF-8.
+    pub fn startup_id(&self) -> Option<&str> {
+        if self.0.format() != 8 {
+            return None;
+        }
+
+        let value = self.0.value::<u8>();
+        let value = value.split(|&b| b == 0).next().unwrap_or(value);
+
+        str::from_utf8(value).ok().filter(|id| !id.is_empty())
+    }
+}
+
+//TODO: Expose inner cookie
+pub struct GetStartupIdCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetStartupIdCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetStartupIdCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetStartupIdCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetStartupIdCookie {
+    type Reply = GetStartupIdReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetStartupIdReply)
+    }
+}
+
+impl xcb::Cookie for GetStartupIdCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetStartupIdCookieUnchecked {
+    type Reply = GetStartupIdReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetStartupIdReply))
+    }
+}
+
+pub struct GetStartupId {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetStartupId {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.STARTUP_ID(),
+                r#type: ewmh.UTF8_STRING(),
+                long_offset: 0,
+                long_length: u32::MAX,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetStartupId {
+    type Cookie = GetStartupIdCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetStartupId {
+    type Reply = GetStartupIdReply;
+    type Cookie = GetStartupIdCookie;
+    type CookieUnchecked = GetStartupIdCookieUnchecked;
+}
+
+/// Generates a startup ID for launching `program`, embedding the timestamp of the event that
+/// triggered the launch as recommended by the startup notification specification.
+pub fn generate_startup_id(program: &str, timestamp: x::Timestamp) -> String {
+    static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let program = program
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect::<String>();
+
+    format!(
+        "{}-{}-{}_TIME{}",
+        program,
+        process::id(),
+        SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        timestamp
+    )
+}
+
+/// Timestamp embedded in a startup ID with the `_TIME<timestamp>` suffix.
+pub fn startup_id_timestamp(startup_id: &str) -> Option<x::Timestamp> {
+    let (_, timestamp) = startup_id.rsplit_once("_TIME")?;
+
+    timestamp.parse().ok()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StartupMessageKind {
+    New,
+    Change,
+    Remove,
+}
+
+impl StartupMessageKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Change => "change",
+            Self::Remove => "remove",
+        }
+    }
+}
+
+/// A startup notification message, made of a kind and `KEY=value` pairs such as `ID`, `NAME`,
+/// `SCREEN`, `BIN`, `ICON`, `DESKTOP`, `TIMESTAMP`, `DESCRIPTION`, `WMCLASS` or `SILENT`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartupMessage {
+    kind: StartupMessageKind,
+    pairs: Vec<(String, String)>,
+}
+
+impl StartupMessage {
+    pub fn new(kind: StartupMessageKind, id: &str) -> Self {
+        Self {
+            kind,
+            pairs: vec![("ID".to_owned(), id.to_owned())],
+        }
+    }
+
+    /// Sets `key` to `value`, replacing any previous value.
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.pairs.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self.pairs.push((key.to_owned(), value.to_owned())),
+        }
+    }
+
+    pub fn kind(&self) -> StartupMessageKind {
+        self.kind
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.get("ID")
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Parses a message such as `new: ID="foo_TIME42" NAME="Foo Bar" SCREEN=0`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let (kind, rest) = message.split_once(':')?;
+
+        let kind = match kind {
+            "new" => StartupMessageKind::New,
+            "change" => StartupMessageKind::Change,
+            "remove" => StartupMessageKind::Remove,
+            _ => return None,
+        };
+
+        let mut pairs = Vec::new();
+        let mut chars = rest.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut key = String::new();
+
+            while let Some(c) = chars.next_if(|&c| c != '=') {
+                key.push(c);
+            }
+
+            chars.next()?;
+
+            let mut value = String::new();
+            let mut quoted = false;
+
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => quoted = !quoted,
+                    '\\' => value.push(chars.next()?),
+                    c if c.is_whitespace() && !quoted => break,
+                    c => value.push(c),
+                }
+            }
+
+            if quoted || key.is_empty() {
+                return None;
+            }
+
+            pairs.push((key, value));
+        }
+
+        Some(Self { kind, pairs })
+    }
+}
+
+impl fmt::Display for StartupMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.kind.as_str())?;
+
+        for (key, value) in &self.pairs {
+            write!(f, " {}=\"", key)?;
+
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    write!(f, "\\")?;
+                }
+
+                write!(f, "{}", c)?;
+            }
+
+            write!(f, "\"")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> EwmhConnection<'a> {
+    /// Broadcasts `message` on the root window of `screen_nbr`, split in as many client messages as
+    /// needed.
+    ///
+    /// `window` identifies the sender to receivers reassembling the message, it should be a
+    /// window created for that purpose and kept alive until the message is sent.
+    ///
+    /// Returns the cookie of the last client message, or `None` if the screen does not exist.
+    pub fn send_startup_message(
+        &self,
+        screen_nbr: i32,
+        window: x::Window,
+        message: &StartupMessage,
+    ) -> Option<xcb::VoidCookie> {
+        let root = self
+            .get_setup()
+            .roots()
+            .nth(usize::try_from(screen_nbr).ok()?)?
+            .root();
+
+        let mut message = message.to_string().into_bytes();
+        message.push(0);
+
+        let mut cookie = None;
+
+        for (i, chunk) in message.chunks(20).enumerate() {
+            let mut data = [0; 20];
+            data[..chunk.len()].copy_from_slice(chunk);
+
+            let event = x::ClientMessageEvent::new(
+                window,
+                if i == 0 {
+                    self.STARTUP_INFO_BEGIN()
+                } else {
+                    self.STARTUP_INFO()
+                },
+                x::ClientMessageData::Data8(data),
+            );
+
+            cookie = Some(self.send_request(&x::SendEvent {
+                propagate: false,
+                destination: x::SendEventDest::Window(root),
+                event_mask: x::EventMask::PROPERTY_CHANGE,
+                event: &event,
+            }));
+        }
+
+        cookie
+    }
+}
+
+/// Longest startup notification message accepted, longer ones are dropped.
+pub const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+
+/// Most messages reassembled at once, the oldest one is dropped to make room for a new one.
+pub const MAX_PENDING_MESSAGES: usize = 64;
+
+/// Reassembles startup notification messages from the client messages received on the root
+/// window.
+#[derive(Debug, Default)]
+pub struct StartupMessageReceiver {
+    /// Partial messages by sender, oldest first.
+    pending: VecDeque<(x::Window, Vec<u8>)>,
+}
+
+impl StartupMessageReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a client message, returning the message it completes if any.
+    ///
+    /// Messages that are not valid UTF-8, cannot be parsed or are longer than
+    /// [`MAX_MESSAGE_LENGTH`] are dropped.
+    pub fn handle(
+        &mut self,
+        ewmh: &EwmhConnection,
+        event: &x::ClientMessageEvent,
+    ) -> Option<StartupMessage> {
+        let data = match event.data() {
+            x::ClientMessageData::Data8(data) => data,
+            _ => return None,
+        };
+
+        if event.r#type() == ewmh.STARTUP_INFO_BEGIN() {
+            self.feed(event.window(), true, &data)
+        } else if event.r#type() == ewmh.STARTUP_INFO() {
+            self.feed(event.window(), false, &data)
+        } else {
+            None
+        }
+    }
+
+    /// Appends the data of a client message from `window`, starting a new message if `begin`.
+    fn feed(&mut self, window: x::Window, begin: bool, data: &[u8]) -> Option<StartupMessage> {
+        if begin {
+            self.forget(window);
+
+            if self.pending.len() >= MAX_PENDING_MESSAGES {
+                self.pending.pop_front();
+            }
+
+            self.pending.push_back((window, Vec::new()));
+        }
+
+        let index = self.pending.iter().position(|(w, _)| *w == window)?;
+        let end = data.iter().position(|&b| b == 0);
+
+        let buffer = &mut self.pending[index].1;
+        buffer.extend_from_slice(&data[..end.unwrap_or(data.len())]);
+
+        if buffer.len() > MAX_MESSAGE_LENGTH {
+            self.pending.remove(index);
+            return None;
+        }
+
+        end?;
+
+        let (_, buffer) = self.pending.remove(index)?;
+
+        StartupMessage::parse(str::from_utf8(&buffer).ok()?)
+    }
+
+    /// Drops the partial message sent by `window`, typically once it is destroyed.
+    pub fn forget(&mut self, window: x::Window) {
+        self.pending.retain(|(w, _)| *w != window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_message() {
+        let message = StartupMessage::parse(r#"new: ID="foo_TIME42" NAME="Foo Bar" SCREEN=0"#)
+            .expect("valid message");
+
+        assert_eq!(message.kind(), StartupMessageKind::New);
+        assert_eq!(message.id(), Some("foo_TIME42"));
+        assert_eq!(message.get("NAME"), Some("Foo Bar"));
+        assert_eq!(message.get("SCREEN"), Some("0"));
+        assert_eq!(message.pairs().count(), 3);
+    }
+
+    #[test]
+    fn parse_escapes() {
+        let message = StartupMessage::parse(r#"change: ID=a\ b NAME="say \"hi\" \\ bye""#)
+            .expect("valid message");
+
+        assert_eq!(message.kind(), StartupMessageKind::Change);
+        assert_eq!(message.id(), Some("a b"));
+        assert_eq!(message.get("NAME"), Some(r#"say "hi" \ bye"#));
+    }
+
+    #[test]
+    fn display_quotes_and_escapes() {
+        let message =
+            StartupMessage::new(StartupMessageKind::Remove, "id").with("NAME", r#"say "hi" \ bye"#);
+
+        assert_eq!(
+            message.to_string(),
+            r#"remove: ID="id" NAME="say \"hi\" \\ bye""#
+        );
+        assert_eq!(StartupMessage::parse(&message.to_string()), Some(message));
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        assert_eq!(StartupMessage::parse(r#"new: ID="unclosed"#), None);
+        assert_eq!(StartupMessage::parse(r#"new: ID=trailing\"#), None);
+        assert_eq!(StartupMessage::parse("new: ID"), None);
+        assert_eq!(StartupMessage::parse(r#"new: ="value""#), None);
+        assert_eq!(StartupMessage::parse(r#"start: ID="id""#), None);
+        assert_eq!(StartupMessage::parse(r#"ID="id""#), None);
+    }
+
+    fn chunks(message: &str) -> Vec<[u8; 20]> {
+        let mut message = message.as_bytes().to_vec();
+        message.push(0);
+
+        message
+            .chunks(20)
+            .map(|chunk| {
+                let mut data = [0; 20];
+                data[..chunk.len()].copy_from_slice(chunk);
+                data
+            })
+            .collect()
+    }
+
+    fn window(id: u32) -> x::Window {
+        xcb::XidNew::new(id)
+    }
+
+    #[test]
+    fn reassemble_interleaved_messages() {
+        let mut receiver = StartupMessageReceiver::new();
+        let first = chunks(r#"new: ID="first_TIME1" NAME="First application""#);
+        let second = chunks(r#"remove: ID="second_TIME2""#);
+
+        assert_eq!(receiver.feed(window(1), true, &first[0]), None);
+        assert_eq!(receiver.feed(window(2), true, &second[0]), None);
+
+        let message = receiver.feed(window(2), false, &second[1]).unwrap();
+
+        assert_eq!(message.kind(), StartupMessageKind::Remove);
+        assert_eq!(message.id(), Some("second_TIME2"));
+
+        for chunk in &first[1..first.len() - 1] {
+            assert_eq!(receiver.feed(window(1), false, chunk), None);
+        }
+
+        let message = receiver
+            .feed(window(1), false, first.last().unwrap())
+            .unwrap();
+
+        assert_eq!(message.get("NAME"), Some("First application"));
+        assert!(receiver.pending.is_empty());
+    }
+
+    #[test]
+    fn continuation_without_begin_is_ignored() {
+        let mut receiver = StartupMessageReceiver::new();
+
+        assert_eq!(
+            receiver.feed(window(1), false, &chunks("new: ID=a")[0]),
+            None
+        );
+        assert!(receiver.pending.is_empty());
+    }
+
+    #[test]
+    fn overlong_message_is_dropped() {
+        let mut receiver = StartupMessageReceiver::new();
+        let data = [b'a'; 20];
+
+        assert_eq!(receiver.feed(window(1), true, &data), None);
+
+        for _ in 0..MAX_MESSAGE_LENGTH / data.len() {
+            assert_eq!(receiver.feed(window(1), false, &data), None);
+        }
+
+        assert!(receiver.pending.is_empty());
+
+        // The rest of the message is ignored until the sender begins a new one.
+        assert_eq!(receiver.feed(window(1), false, &chunks("")[0]), None);
+        assert!(receiver.pending.is_empty());
+    }
+
+    #[test]
+    fn unterminated_messages_are_evicted() {
+        let mut receiver = StartupMessageReceiver::new();
+        let begin = chunks(r#"new: ID="never_finished""#);
+
+        for id in 0..MAX_PENDING_MESSAGES as u32 + 8 {
+            assert_eq!(receiver.feed(window(id), true, &begin[0]), None);
+        }
+
+        assert_eq!(receiver.pending.len(), MAX_PENDING_MESSAGES);
+        assert_eq!(receiver.pending[0].0, window(8));
+        assert_eq!(receiver.feed(window(0), false, &begin[1]), None);
+
+        let last = window(MAX_PENDING_MESSAGES as u32 + 7);
+
+        assert!(receiver.feed(last, false, &begin[1]).is_some());
+
+        receiver.forget(window(8));
+
+        assert_eq!(receiver.pending.len(), MAX_PENDING_MESSAGES - 2);
+    }
+
+    #[test]
+    fn begin_restarts_the_message() {
+        let mut receiver = StartupMessageReceiver::new();
+        let abandoned = chunks(r#"new: ID="abandoned_message""#);
+
+        assert_eq!(receiver.feed(window(1), true, &abandoned[0]), None);
+
+        let message = receiver
+            .feed(window(1), true, &chunks("remove: ID=done")[0])
+            .unwrap();
+
+        assert_eq!(message.id(), Some("done"));
+        assert!(receiver.pending.is_empty());
+    }
+}
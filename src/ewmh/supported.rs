@@ -3,8 +3,8 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetSupported<'a> {
@@ -112,6 +112,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetSupportedCookie {
             let raw =
                 &ffi::xcb_ewmh_get_supported_reply(ewmh.ewmh.get(), cookie, &mut supported, &mut e);
 
+            check_error(e)?;
+
             let atoms = slice::from_raw_parts(
                 supported.atoms as *mut x::Atom,
                 supported.atoms_len as usize,
@@ -0,0 +1,232 @@
+use std::convert::TryFrom;
+
+use xcb::{x, Xid};
+
+use super::{
+    ClientSourceType, EwmhConnection, GetSupported, RequestChangeActiveWindow,
+    RequestChangeWmDesktop, RequestChangeWmState, RequestCloseWindow, WmStateAction,
+};
+
+/// `IconicState` as defined by ICCCM, sent with `WM_CHANGE_STATE` to minimize a window.
+const ICONIC_STATE: u32 = 3;
+
+/// High-level actions on client windows, sent as EWMH client messages to the root window.
+///
+/// Every action checks that the window manager lists the hints it relies on in `_NET_SUPPORTED`
+/// and returns `None` without sending anything otherwise.
+pub struct WindowActions<'a, 'b> {
+    ewmh: &'b EwmhConnection<'a>,
+    screen_nbr: i32,
+    supported: Vec<x::Atom>,
+    wm_change_state: x::Atom,
+    timestamp: x::Timestamp,
+    source_indication: ClientSourceType,
+}
+
+impl<'a> EwmhConnection<'a> {
+    /// Fetches `_NET_SUPPORTED` for `screen_nbr` and returns the actions available on it.
+    pub fn window_actions(&self, screen_nbr: i32) -> xcb::Result<WindowActions<'a, '_>> {
+        let supported = self.send_request(&GetSupported { screen_nbr });
+        let wm_change_state = self.send_request(&x::InternAtom {
+            only_if_exists: false,
+            name: b"WM_CHANGE_STATE",
+        });
+
+        let supported = self.wait_for_reply(supported)?.atoms().to_vec();
+        let wm_change_state = self.wait_for_reply(wm_change_state)?.atom();
+
+        Ok(WindowActions {
+            ewmh: self,
+            screen_nbr,
+            supported,
+            wm_change_state,
+            timestamp: x::CURRENT_TIME,
+            source_indication: ClientSourceType::Other,
+        })
+    }
+}
+
+impl<'a, 'b> WindowActions<'a, 'b> {
+    /// Timestamp of the user event that triggered the actions, `CurrentTime` by default.
+    pub fn timestamp(mut self, timestamp: x::Timestamp) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Source indication sent along the actions, [`ClientSourceType::Other`] (pagers and
+    /// taskbars) by default.
+    pub fn source_indication(mut self, source_indication: ClientSourceType) -> Self {
+        self.source_indication = source_indication;
+        self
+    }
+
+    pub fn is_supported(&self, atom: x::Atom) -> bool {
+        self.supported.contains(&atom)
+    }
+
+    fn change_state(
+        &self,
+        window: x::Window,
+        action: WmStateAction,
+        first_property: x::Atom,
+        second_property: x::Atom,
+    ) -> Option<xcb::VoidCookie> {
+        if !self.is_supported(self.ewmh.WM_STATE()) || !self.is_supported(first_property) {
+            return None;
+        }
+
+        if !second_property.is_none() && !self.is_supported(second_property) {
+            return None;
+        }
+
+        Some(self.ewmh.send_request(&RequestChangeWmState {
+            screen_nbr: self.screen_nbr,
+            window,
+            action,
+            first_property,
+            second_property,
+            source_indication: self.source_indication,
+        }))
+    }
+
+    pub fn maximize(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            WmStateAction::Add,
+            self.ewmh.WM_STATE_MAXIMIZED_VERT(),
+            self.ewmh.WM_STATE_MAXIMIZED_HORZ(),
+        )
+    }
+
+    pub fn unmaximize(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            WmStateAction::Remove,
+            self.ewmh.WM_STATE_MAXIMIZED_VERT(),
+            self.ewmh.WM_STATE_MAXIMIZED_HORZ(),
+        )
+    }
+
+    pub fn toggle_maximize(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            WmStateAction::Toggle,
+            self.ewmh.WM_STATE_MAXIMIZED_VERT(),
+            self.ewmh.WM_STATE_MAXIMIZED_HORZ(),
+        )
+    }
+
+    pub fn set_fullscreen(&self, window: x::Window, fullscreen: bool) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            if fullscreen {
+                WmStateAction::Add
+            } else {
+                WmStateAction::Remove
+            },
+            self.ewmh.WM_STATE_FULLSCREEN(),
+            x::ATOM_NONE,
+        )
+    }
+
+    pub fn toggle_fullscreen(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            WmStateAction::Toggle,
+            self.ewmh.WM_STATE_FULLSCREEN(),
+            x::ATOM_NONE,
+        )
+    }
+
+    pub fn set_above(&self, window: x::Window, above: bool) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            if above {
+                WmStateAction::Add
+            } else {
+                WmStateAction::Remove
+            },
+            self.ewmh.WM_STATE_ABOVE(),
+            x::ATOM_NONE,
+        )
+    }
+
+    pub fn set_below(&self, window: x::Window, below: bool) -> Option<xcb::VoidCookie> {
+        self.change_state(
+            window,
+            if below {
+                WmStateAction::Add
+            } else {
+                WmStateAction::Remove
+            },
+            self.ewmh.WM_STATE_BELOW(),
+            x::ATOM_NONE,
+        )
+    }
+
+    /// Iconifies `window` with the ICCCM `WM_CHANGE_STATE` message, which EWMH relies on instead
+    /// of a hint of its own, so this is sent regardless of `_NET_SUPPORTED`.
+    ///
+    /// Returns `None` if the screen does not exist, as the message must go to its root window.
+    pub fn minimize(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        let root = self
+            .ewmh
+            .get_setup()
+            .roots()
+            .nth(usize::try_from(self.screen_nbr).ok()?)?
+            .root();
+
+        let event = x::ClientMessageEvent::new(
+            window,
+            self.wm_change_state,
+            x::ClientMessageData::Data32([ICONIC_STATE, 0, 0, 0, 0]),
+        );
+
+        Some(self.ewmh.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(root),
+            event_mask: x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+            event: &event,
+        }))
+    }
+
+    pub fn move_to_desktop(&self, window: x::Window, desktop: u32) -> Option<xcb::VoidCookie> {
+        if !self.is_supported(self.ewmh.WM_DESKTOP()) {
+            return None;
+        }
+
+        Some(self.ewmh.send_request(&RequestChangeWmDesktop {
+            screen_nbr: self.screen_nbr,
+            client_window: window,
+            new_desktop: desktop,
+            source_indication: self.source_indication,
+        }))
+    }
+
+    pub fn activate(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        if !self.is_supported(self.ewmh.ACTIVE_WINDOW()) {
+            return None;
+        }
+
+        Some(self.ewmh.send_request(&RequestChangeActiveWindow {
+            screen_nbr: self.screen_nbr,
+            window_to_activate: window,
+            timestamp: self.timestamp,
+            source_indication: self.source_indication,
+            current_active_window: x::Window::none(),
+        }))
+    }
+
+    pub fn close(&self, window: x::Window) -> Option<xcb::VoidCookie> {
+        if !self.is_supported(self.ewmh.CLOSE_WINDOW()) {
+            return None;
+        }
+
+        Some(self.ewmh.send_request(&RequestCloseWindow {
+            screen_nbr: self.screen_nbr,
+            window_to_close: window,
+            timestamp: self.timestamp,
+            source_indication: self.source_indication,
+        }))
+    }
+}
@@ -0,0 +1,192 @@
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+};
+
+/// Preference of a window about being composited, typically set by fullscreen games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BypassCompositor {
+    NoPreference = 0,
+    /// Asks the compositor to unredirect the window.
+    Disable = 1,
+    /// Asks the compositor to keep compositing the window.
+    Enable = 2,
+}
+
+impl From<u32> for BypassCompositor {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Disable,
+            2 => Self::Enable,
+            _ => Self::NoPreference,
+        }
+    }
+}
+
+pub struct SetWmBypassCompositor {
+    pub window: x::Window,
+    pub bypass: BypassCompositor,
+}
+
+unsafe impl RawEwmhRequest for SetWmBypassCompositor {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.WM_BYPASS_COMPOSITOR(),
+                r#type: x::ATOM_CARDINAL,
+                data: &[self.bypass as u32],
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for SetWmBypassCompositor {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetWmBypassCompositor {}
+
+// TODO: Expose inner reply
+pub struct GetWmBypassCompositorReply(x::GetPropertyReply);
+
+impl EwmhReply for GetWmBypassCompositorReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetWmBypassCompositorReply {
+    pub fn bypass(&self) -> BypassCompositor {
+        if self.0.format() != 32 {
+            return BypassCompositor::NoPreference;
+        }
+
+        from_values(self.0.value())
+    }
+}
+
+fn from_values(values: &[u32]) -> BypassCompositor {
+    values
+        .first()
+        .map_or(BypassCompositor::NoPreference, |&value| {
+            BypassCompositor::from(value)
+        })
+}
+
+//TODO: Expose inner cookie
+pub struct GetWmBypassCompositorCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetWmBypassCompositorCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetWmBypassCompositorCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetWmBypassCompositorCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetWmBypassCompositorCookie {
+    type Reply = GetWmBypassCompositorReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetWmBypassCompositorReply)
+    }
+}
+
+impl xcb::Cookie for GetWmBypassCompositorCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetWmBypassCompositorCookieUnchecked {
+    type Reply = GetWmBypassCompositorReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetWmBypassCompositorReply))
+    }
+}
+
+pub struct GetWmBypassCompositor {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetWmBypassCompositor {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.WM_BYPASS_COMPOSITOR(),
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: 1,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetWmBypassCompositor {
+    type Cookie = GetWmBypassCompositorCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetWmBypassCompositor {
+    type Reply = GetWmBypassCompositorReply;
+    type Cookie = GetWmBypassCompositorCookie;
+    type CookieUnchecked = GetWmBypassCompositorCookieUnchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_fixtures() {
+        assert_eq!(from_values(&[0]), BypassCompositor::NoPreference);
+        assert_eq!(from_values(&[1]), BypassCompositor::Disable);
+        assert_eq!(from_values(&[2, 0]), BypassCompositor::Enable);
+        assert_eq!(from_values(&[3]), BypassCompositor::NoPreference);
+        assert_eq!(from_values(&[]), BypassCompositor::NoPreference);
+    }
+
+    #[test]
+    fn encode_fixtures() {
+        assert_eq!(BypassCompositor::NoPreference as u32, 0);
+        assert_eq!(BypassCompositor::Disable as u32, 1);
+        assert_eq!(BypassCompositor::Enable as u32, 2);
+    }
+}
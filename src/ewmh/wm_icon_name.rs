@@ -3,8 +3,8 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetWmIconName<'a> {
@@ -120,6 +120,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetWmIconNameCookie {
             let raw =
                 &ffi::xcb_ewmh_get_wm_icon_name_reply(ewmh.ewmh.get(), cookie, &mut names, &mut e);
 
+            check_error(e)?;
+
             let strings = std::str::from_utf8_unchecked(slice::from_raw_parts(
                 names.strings as *mut u8,
                 names.strings_len as usize - 1,
@@ -37,3 +37,235 @@ impl EwmhRequest for RequestWmMoveresize {
 
     const IS_VOID: bool = true;
 }
+
+/// The button index of a button press, `Any` for buttons without one.
+fn button_index(button: x::Button) -> x::ButtonIndex {
+    match button {
+        1 => x::ButtonIndex::N1,
+        2 => x::ButtonIndex::N2,
+        3 => x::ButtonIndex::N3,
+        4 => x::ButtonIndex::N4,
+        5 => x::ButtonIndex::N5,
+        _ => x::ButtonIndex::Any,
+    }
+}
+
+/// Drives a window manager assisted move or resize for windows drawing their own decorations.
+///
+/// On button press the direction is picked from the pointer position relative to the window
+/// edges, the implicit pointer grab is released and `_NET_WM_MOVERESIZE` is sent. Window managers
+/// are expected to end the operation themselves, but some never see the button release, so a
+/// cancel message is sent when the client receives it.
+pub struct InteractiveMoveresize {
+    screen_nbr: i32,
+    window: x::Window,
+    border: u32,
+    button: Option<x::ButtonIndex>,
+}
+
+impl InteractiveMoveresize {
+    pub fn new(screen_nbr: i32, window: x::Window) -> Self {
+        Self {
+            screen_nbr,
+            window,
+            border: 8,
+            button: None,
+        }
+    }
+
+    /// Width of the area along the window edges that resizes the window, 8 pixels by default.
+    pub fn border(mut self, border: u32) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.button.is_some()
+    }
+
+    /// Direction for a pointer at `x`, `y` relative to a window of size `width` × `height`.
+    pub fn direction_at(&self, x: i32, y: i32, width: u32, height: u32) -> MoveresizeDirection {
+        let border = self.border as i64;
+        let (x, y) = (x as i64, y as i64);
+
+        let left = x < border;
+        let right = x >= width as i64 - border;
+        let top = y < border;
+        let bottom = y >= height as i64 - border;
+
+        match (left, right, top, bottom) {
+            (true, _, true, _) => MoveresizeDirection::SizeTopLeft,
+            (_, true, true, _) => MoveresizeDirection::SizeTopRight,
+            (true, _, _, true) => MoveresizeDirection::SizeBottomLeft,
+            (_, true, _, true) => MoveresizeDirection::SizeBottomRight,
+            (true, _, _, _) => MoveresizeDirection::SizeLeft,
+            (_, true, _, _) => MoveresizeDirection::SizeRight,
+            (_, _, true, _) => MoveresizeDirection::SizeTop,
+            (_, _, _, true) => MoveresizeDirection::SizeBottom,
+            _ => MoveresizeDirection::Move,
+        }
+    }
+
+    /// Starts moving or resizing the window of size `width` × `height` from a button press on
+    /// it, returning the direction picked.
+    pub fn start(
+        &mut self,
+        ewmh: &EwmhConnection,
+        event: &x::ButtonPressEvent,
+        width: u32,
+        height: u32,
+    ) -> MoveresizeDirection {
+        let direction = self.direction_at(
+            event.event_x() as i32,
+            event.event_y() as i32,
+            width,
+            height,
+        );
+
+        self.start_with(ewmh, event, direction);
+
+        direction
+    }
+
+    /// Starts a move or resize in the given direction from a button press on the window.
+    pub fn start_with(
+        &mut self,
+        ewmh: &EwmhConnection,
+        event: &x::ButtonPressEvent,
+        direction: MoveresizeDirection,
+    ) -> xcb::VoidCookie {
+        let button = button_index(event.detail());
+
+        ewmh.send_request(&x::UngrabPointer { time: event.time() });
+
+        self.button = Some(button);
+
+        ewmh.send_request(&RequestWmMoveresize {
+            screen_nbr: self.screen_nbr,
+            moveresize_window: self.window,
+            x_root: event.root_x() as i32 as u32,
+            y_root: event.root_y() as i32 as u32,
+            direction,
+            button,
+            source_indication: ClientSourceType::Normal,
+        })
+    }
+
+    /// Ends the operation if `event` releases the button that started it.
+    pub fn handle_button_release(
+        &mut self,
+        ewmh: &EwmhConnection,
+        event: &x::ButtonReleaseEvent,
+    ) -> Option<xcb::VoidCookie> {
+        if self.is_released_by(event.detail()) {
+            self.cancel(ewmh, event.root_x() as i32, event.root_y() as i32)
+        } else {
+            None
+        }
+    }
+
+    /// Whether releasing `button` ends the operation in progress.
+    fn is_released_by(&self, button: x::Button) -> bool {
+        match self.button {
+            Some(x::ButtonIndex::Any) => true,
+            Some(started) => started as u8 == button,
+            None => false,
+        }
+    }
+
+    /// Sends a cancel message if an operation is in progress.
+    pub fn cancel(
+        &mut self,
+        ewmh: &EwmhConnection,
+        x_root: i32,
+        y_root: i32,
+    ) -> Option<xcb::VoidCookie> {
+        let button = self.button.take()?;
+
+        Some(ewmh.send_request(&RequestWmMoveresize {
+            screen_nbr: self.screen_nbr,
+            moveresize_window: self.window,
+            x_root: x_root as u32,
+            y_root: y_root as u32,
+            direction: MoveresizeDirection::Cancel,
+            button,
+            source_indication: ClientSourceType::Normal,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xcb::XidNew;
+
+    fn moveresize() -> InteractiveMoveresize {
+        InteractiveMoveresize::new(0, x::Window::new(0x0040_0001))
+    }
+
+    #[test]
+    fn directions() {
+        let moveresize = moveresize();
+        let direction = |x, y| moveresize.direction_at(x, y, 100, 50);
+
+        assert_eq!(direction(0, 0), MoveresizeDirection::SizeTopLeft);
+        assert_eq!(direction(99, 0), MoveresizeDirection::SizeTopRight);
+        assert_eq!(direction(0, 49), MoveresizeDirection::SizeBottomLeft);
+        assert_eq!(direction(92, 42), MoveresizeDirection::SizeBottomRight);
+        assert_eq!(direction(7, 25), MoveresizeDirection::SizeLeft);
+        assert_eq!(direction(92, 25), MoveresizeDirection::SizeRight);
+        assert_eq!(direction(50, 7), MoveresizeDirection::SizeTop);
+        assert_eq!(direction(50, 42), MoveresizeDirection::SizeBottom);
+        assert_eq!(direction(8, 8), MoveresizeDirection::Move);
+        assert_eq!(direction(91, 41), MoveresizeDirection::Move);
+
+        // Pointers outside the window resize along the closest edges.
+        assert_eq!(direction(-5, 100), MoveresizeDirection::SizeBottomLeft);
+    }
+
+    #[test]
+    fn directions_with_large_borders() {
+        let moveresize = moveresize().border(u32::MAX);
+
+        assert_eq!(
+            moveresize.direction_at(i32::MAX, i32::MAX, u32::MAX, u32::MAX),
+            MoveresizeDirection::SizeTopLeft
+        );
+
+        let moveresize = moveresize.border(0);
+
+        assert_eq!(
+            moveresize.direction_at(0, 0, u32::MAX, u32::MAX),
+            MoveresizeDirection::Move
+        );
+        assert_eq!(
+            moveresize.direction_at(0, 0, 0, 0),
+            MoveresizeDirection::SizeBottomRight
+        );
+    }
+
+    #[test]
+    fn button_indices() {
+        assert_eq!(button_index(1), x::ButtonIndex::N1);
+        assert_eq!(button_index(5), x::ButtonIndex::N5);
+        assert_eq!(button_index(0), x::ButtonIndex::Any);
+        assert_eq!(button_index(8), x::ButtonIndex::Any);
+    }
+
+    #[test]
+    fn release() {
+        let mut moveresize = moveresize();
+
+        assert!(!moveresize.is_released_by(1));
+
+        moveresize.button = Some(x::ButtonIndex::N1);
+
+        assert!(moveresize.is_active());
+        assert!(moveresize.is_released_by(1));
+        assert!(!moveresize.is_released_by(3));
+
+        moveresize.button = Some(x::ButtonIndex::Any);
+
+        assert!(moveresize.is_released_by(8));
+    }
+}
@@ -0,0 +1,198 @@
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, Geometry, RawEwmhRequest,
+};
+
+pub struct SetWmOpaqueRegion<'a> {
+    pub window: x::Window,
+    pub rectangles: &'a [Geometry],
+}
+
+unsafe impl<'a> RawEwmhRequest for SetWmOpaqueRegion<'a> {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.WM_OPAQUE_REGION(),
+                r#type: x::ATOM_CARDINAL,
+                data: &to_values(self.rectangles),
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl<'a> EwmhRequest for SetWmOpaqueRegion<'a> {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl<'a> EwmhRequestWithoutReply for SetWmOpaqueRegion<'a> {}
+
+// TODO: Expose inner reply
+pub struct GetWmOpaqueRegionReply(x::GetPropertyReply);
+
+impl EwmhReply for GetWmOpaqueRegionReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetWmOpaqueRegionReply {
+    /// Rectangles of the window, relative to its origin, where it is fully opaque.
+    pub fn rectangles(&self) -> Vec<Geometry> {
+        if self.0.format() != 32 {
+            return Vec::new();
+        }
+
+        from_values(self.0.value())
+    }
+}
+
+/// Flattens the rectangles to the x, y, width and height values of the property.
+fn to_values(rectangles: &[Geometry]) -> Vec<u32> {
+    rectangles
+        .iter()
+        .flat_map(|r| [r.x, r.y, r.width, r.height])
+        .collect()
+}
+
+/// Decodes the rectangles of the property, ignoring an incomplete trailing rectangle.
+fn from_values(values: &[u32]) -> Vec<Geometry> {
+    values
+        .chunks_exact(4)
+        .map(|r| Geometry {
+            x: r[0],
+            y: r[1],
+            width: r[2],
+            height: r[3],
+        })
+        .collect()
+}
+
+//TODO: Expose inner cookie
+pub struct GetWmOpaqueRegionCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetWmOpaqueRegionCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetWmOpaqueRegionCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetWmOpaqueRegionCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetWmOpaqueRegionCookie {
+    type Reply = GetWmOpaqueRegionReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetWmOpaqueRegionReply)
+    }
+}
+
+impl xcb::Cookie for GetWmOpaqueRegionCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetWmOpaqueRegionCookieUnchecked {
+    type Reply = GetWmOpaqueRegionReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetWmOpaqueRegionReply))
+    }
+}
+
+pub struct GetWmOpaqueRegion {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetWmOpaqueRegion {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.WM_OPAQUE_REGION(),
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: u32::MAX,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetWmOpaqueRegion {
+    type Cookie = GetWmOpaqueRegionCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetWmOpaqueRegion {
+    type Reply = GetWmOpaqueRegionReply;
+    type Cookie = GetWmOpaqueRegionCookie;
+    type CookieUnchecked = GetWmOpaqueRegionCookieUnchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGION: [Geometry; 2] = [
+        // The body of a window with rounded top corners, below its title bar.
+        Geometry {
+            x: 0,
+            y: 8,
+            width: 640,
+            height: 472,
+        },
+        Geometry {
+            x: 8,
+            y: 0,
+            width: 624,
+            height: 8,
+        },
+    ];
+
+    #[test]
+    fn encode_fixtures() {
+        assert_eq!(to_values(&REGION), [0, 8, 640, 472, 8, 0, 624, 8]);
+        assert_eq!(to_values(&[]), []);
+    }
+
+    #[test]
+    fn decode_fixtures() {
+        assert_eq!(from_values(&[0, 8, 640, 472, 8, 0, 624, 8]), REGION);
+        assert_eq!(from_values(&[0, 8, 640, 472, 8, 0, 624]), REGION[..1]);
+        assert_eq!(from_values(&[]), []);
+    }
+}
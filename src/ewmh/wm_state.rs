@@ -3,7 +3,7 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, ClientSourceType, EwmhConnection, EwmhCookieWithReplyChecked,
+    check_error, ffi, ClientSourceType, EwmhConnection, EwmhCookieWithReplyChecked,
     EwmhCookieWithReplyUnchecked, EwmhReply, EwmhRequest, EwmhRequestWithReply,
     EwmhRequestWithoutReply, RawEwmhRequest, WmStateAction,
 };
@@ -109,6 +109,55 @@ impl GetWmStateReply {
     pub fn atoms(&self) -> &[x::Atom] {
         &self.atoms
     }
+
+    pub fn contains(&self, state: x::Atom) -> bool {
+        self.atoms.contains(&state)
+    }
+
+    /// Whether the window manager marked the window with `_NET_WM_STATE_FOCUSED`, which clients
+    /// drawing their own decorations use to draw them as active.
+    pub fn is_focused(&self, ewmh: &EwmhConnection) -> bool {
+        self.contains(ewmh.WM_STATE_FOCUSED())
+    }
+}
+
+/// Applies `action` on `state` to the states of a window, as a window manager does when handling
+/// a `_NET_WM_STATE` request.
+///
+/// Added states go last and the order of the other states is kept.
+pub fn change_wm_state(atoms: &mut Vec<x::Atom>, action: WmStateAction, state: x::Atom) {
+    let present = atoms.contains(&state);
+
+    match action {
+        WmStateAction::Add | WmStateAction::Toggle if !present => atoms.push(state),
+        WmStateAction::Remove | WmStateAction::Toggle if present => {
+            atoms.retain(|&atom| atom != state)
+        }
+        _ => {}
+    }
+}
+
+impl<'a> EwmhConnection<'a> {
+    /// Adds or removes `_NET_WM_STATE_FOCUSED` from `atoms`, the states of `window`, and sets
+    /// them on the window.
+    ///
+    /// Window managers call it on the windows losing and gaining the focus.
+    pub fn set_wm_state_focused(
+        &self,
+        window: x::Window,
+        atoms: &mut Vec<x::Atom>,
+        focused: bool,
+    ) -> xcb::VoidCookie {
+        let action = if focused {
+            WmStateAction::Add
+        } else {
+            WmStateAction::Remove
+        };
+
+        change_wm_state(atoms, action, self.WM_STATE_FOCUSED());
+
+        self.send_request(&SetWmState { window, atoms })
+    }
 }
 
 //TODO: Expose inner cookie
@@ -143,6 +192,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetWmStateCookie {
             let raw =
                 &ffi::xcb_ewmh_get_wm_state_reply(ewmh.ewmh.get(), cookie, &mut wm_state, &mut e);
 
+            check_error(e)?;
+
             let atoms =
                 slice::from_raw_parts(wm_state.atoms as *mut x::Atom, wm_state.atoms_len as usize)
                     .to_vec();
@@ -221,3 +272,51 @@ impl EwmhRequestWithReply for GetWmState {
     type Cookie = GetWmStateCookie;
     type CookieUnchecked = GetWmStateCookieUnchecked;
 }
+
+#[cfg(test)]
+mod tests {
+    use xcb::XidNew;
+
+    use super::*;
+
+    fn atom(id: u32) -> x::Atom {
+        x::Atom::new(id)
+    }
+
+    #[test]
+    fn change_states() {
+        let (above, focused) = (atom(300), atom(301));
+        let mut atoms = vec![above];
+
+        change_wm_state(&mut atoms, WmStateAction::Add, focused);
+        assert_eq!(atoms, [above, focused]);
+
+        change_wm_state(&mut atoms, WmStateAction::Add, focused);
+        assert_eq!(atoms, [above, focused]);
+
+        change_wm_state(&mut atoms, WmStateAction::Remove, above);
+        assert_eq!(atoms, [focused]);
+
+        change_wm_state(&mut atoms, WmStateAction::Remove, above);
+        assert_eq!(atoms, [focused]);
+
+        change_wm_state(&mut atoms, WmStateAction::Toggle, focused);
+        assert_eq!(atoms, []);
+
+        change_wm_state(&mut atoms, WmStateAction::Toggle, focused);
+        assert_eq!(atoms, [focused]);
+    }
+
+    #[test]
+    fn contains_states() {
+        let (above, focused) = (atom(300), atom(301));
+
+        let reply = GetWmStateReply {
+            raw: ptr::null(),
+            atoms: vec![above, focused],
+        };
+
+        assert!(reply.contains(focused));
+        assert!(!reply.contains(atom(302)));
+    }
+}
@@ -0,0 +1,160 @@
+use xcb::x;
+
+use super::{
+    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
+    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+};
+
+pub struct SetWmWindowOpacity {
+    pub window: x::Window,
+    pub opacity: u32,
+}
+
+unsafe impl RawEwmhRequest for SetWmWindowOpacity {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: self.window,
+                property: ewmh.WM_WINDOW_OPACITY(),
+                r#type: x::ATOM_CARDINAL,
+                data: &[self.opacity],
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for SetWmWindowOpacity {
+    type Cookie = xcb::VoidCookie;
+
+    const IS_VOID: bool = true;
+}
+
+impl EwmhRequestWithoutReply for SetWmWindowOpacity {}
+
+// TODO: Expose inner reply
+pub struct GetWmWindowOpacityReply(x::GetPropertyReply);
+
+impl EwmhReply for GetWmWindowOpacityReply {
+    unsafe fn from_raw(raw: *const u8, _: *mut ffi::xcb_ewmh_connection_t) -> Self {
+        Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+    }
+}
+
+impl GetWmWindowOpacityReply {
+    /// The opacity, from `0` for transparent to `u32::MAX` for opaque, or `None` if the property
+    /// is not set.
+    pub fn opacity(&self) -> Option<u32> {
+        if self.0.format() != 32 {
+            return None;
+        }
+
+        from_values(self.0.value())
+    }
+}
+
+fn from_values(values: &[u32]) -> Option<u32> {
+    values.first().copied()
+}
+
+//TODO: Expose inner cookie
+pub struct GetWmWindowOpacityCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetWmWindowOpacityCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetWmWindowOpacityCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetWmWindowOpacityCookie {}
+
+unsafe impl EwmhCookieWithReplyChecked for GetWmWindowOpacityCookie {
+    type Reply = GetWmWindowOpacityReply;
+
+    fn wait_for_reply(self, ewmh: &EwmhConnection) -> xcb::Result<Self::Reply> {
+        ewmh.connection
+            .wait_for_reply(self.0)
+            .map(GetWmWindowOpacityReply)
+    }
+}
+
+impl xcb::Cookie for GetWmWindowOpacityCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl EwmhCookieWithReplyUnchecked for GetWmWindowOpacityCookieUnchecked {
+    type Reply = GetWmWindowOpacityReply;
+
+    fn wait_for_reply_unchecked(
+        self,
+        ewmh: &EwmhConnection,
+    ) -> xcb::ConnResult<Option<Self::Reply>> {
+        ewmh.connection
+            .wait_for_reply_unchecked(self.0)
+            .map(|reply| reply.map(GetWmWindowOpacityReply))
+    }
+}
+
+pub struct GetWmWindowOpacity {
+    pub window: x::Window,
+}
+
+unsafe impl RawEwmhRequest for GetWmWindowOpacity {
+    fn raw_ewmh_request(&self, ewmh: &EwmhConnection, checked: bool) -> u64 {
+        xcb::RawRequest::raw_request(
+            &x::GetProperty {
+                delete: false,
+                window: self.window,
+                property: ewmh.WM_WINDOW_OPACITY(),
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: 1,
+            },
+            ewmh.connection,
+            checked,
+        )
+    }
+}
+
+impl EwmhRequest for GetWmWindowOpacity {
+    type Cookie = GetWmWindowOpacityCookie;
+
+    const IS_VOID: bool = false;
+}
+
+impl EwmhRequestWithReply for GetWmWindowOpacity {
+    type Reply = GetWmWindowOpacityReply;
+    type Cookie = GetWmWindowOpacityCookie;
+    type CookieUnchecked = GetWmWindowOpacityCookieUnchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_fixtures() {
+        assert_eq!(from_values(&[0x7fff_ffff]), Some(0x7fff_ffff));
+        assert_eq!(from_values(&[u32::MAX, 0]), Some(u32::MAX));
+        assert_eq!(from_values(&[]), None);
+    }
+}
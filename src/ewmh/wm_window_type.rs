@@ -3,8 +3,8 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, RawEwmhRequest,
 };
 
 pub struct SetWmWindowType<'a> {
@@ -116,6 +116,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetWmWindowTypeCookie {
                 &mut e,
             );
 
+            check_error(e)?;
+
             let atoms = slice::from_raw_parts(
                 wm_window_type.atoms as *mut x::Atom,
                 wm_window_type.atoms_len as usize,
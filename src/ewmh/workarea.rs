@@ -3,8 +3,9 @@ use std::{mem, ptr, slice};
 use xcb::x;
 
 use super::{
-    ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked, EwmhReply,
-    EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, Geometry, RawEwmhRequest,
+    check_error, ffi, EwmhConnection, EwmhCookieWithReplyChecked, EwmhCookieWithReplyUnchecked,
+    EwmhReply, EwmhRequest, EwmhRequestWithReply, EwmhRequestWithoutReply, Geometry,
+    RawEwmhRequest,
 };
 
 pub struct SetWorkarea<'a> {
@@ -105,6 +106,8 @@ unsafe impl EwmhCookieWithReplyChecked for GetWorkareaCookie {
 
             let raw = &ffi::xcb_ewmh_get_workarea_reply(ewmh.ewmh.get(), cookie, &mut wa, &mut e);
 
+            check_error(e)?;
+
             let geometries =
                 slice::from_raw_parts(wa.workarea as *mut Geometry, wa.workarea_len as usize)
                     .to_vec();
@@ -0,0 +1,393 @@
+//! Decoding of the X Compound Text Encoding, the ISO 2022 based `COMPOUND_TEXT` of ICCCM.
+//!
+//! The 94 and 96 character sets with an algorithmic mapping to Unicode are decoded, along UTF-8
+//! segments and extended segments in UTF-8 or ISO 8859-1. Other sets, the 94^2 sets used for CJK
+//! text in particular, are reported as unsupported.
+
+use std::{borrow::Cow, convert::TryFrom, str};
+
+use super::{DecodeError, EncodeError};
+
+const ESC: u8 = 0x1b;
+const CSI: u8 = 0x9b;
+const STX: u8 = 0x02;
+
+/// The set invoked in the left half, bytes from `0x21` to `0x7e`.
+#[derive(Clone, Copy)]
+enum Left {
+    /// ISO 8859-1 left half, which is ASCII and the initial state.
+    Ascii,
+    /// JIS X 0201 Roman, ASCII with a yen sign and an overline.
+    JisRoman,
+}
+
+impl Left {
+    fn decode(self, byte: u8) -> char {
+        match (self, byte) {
+            (Left::JisRoman, 0x5c) => '\u{a5}',
+            (Left::JisRoman, 0x7e) => '\u{203e}',
+            _ => byte as char,
+        }
+    }
+}
+
+/// The set invoked in the right half, bytes from `0xa0` to `0xff`.
+#[derive(Clone, Copy)]
+enum Right {
+    /// ISO 8859-1 right half, the initial state.
+    Latin1,
+    /// ISO 8859-5 right half.
+    Cyrillic,
+    /// JIS X 0201 Katakana.
+    Katakana,
+}
+
+impl Right {
+    fn decode(self, byte: u8) -> Option<char> {
+        match (self, byte) {
+            (Right::Latin1, _) => Some(byte as char),
+            (Right::Cyrillic, 0xa0) | (Right::Cyrillic, 0xad) => Some(byte as char),
+            (Right::Cyrillic, 0xf0) => Some('\u{2116}'),
+            (Right::Cyrillic, 0xfd) => Some('\u{a7}'),
+            (Right::Cyrillic, _) => char::from_u32(0x400 + u32::from(byte - 0xa0)),
+            (Right::Katakana, 0xa1..=0xdf) => char::from_u32(0xff61 + u32::from(byte - 0xa1)),
+            (Right::Katakana, _) => None,
+        }
+    }
+}
+
+/// Decodes ISO 8859-1, borrowing `data` if it is only made of ASCII.
+pub fn latin1(data: &[u8]) -> Cow<'_, str> {
+    match str::from_utf8(data) {
+        Ok(text) if data.is_ascii() => Cow::Borrowed(text),
+        _ => Cow::Owned(data.iter().map(|&byte| byte as char).collect()),
+    }
+}
+
+/// Encodes ISO 8859-1, borrowing `text` if it is only made of ASCII.
+pub fn to_latin1(text: &str) -> Result<Cow<'_, [u8]>, EncodeError> {
+    if text.contains('\0') {
+        return Err(EncodeError::Nul);
+    }
+
+    if text.is_ascii() {
+        return Ok(Cow::Borrowed(text.as_bytes()));
+    }
+
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).map_err(|_| EncodeError::UnrepresentableChar(c)))
+        .collect::<Result<_, _>>()
+        .map(Cow::Owned)
+}
+
+/// Encodes compound text, borrowing `text` if it is only made of ASCII.
+///
+/// ISO 8859-1 is written as is, everything else in UTF-8 segments.
+pub fn encode(text: &str) -> Result<Cow<'_, [u8]>, EncodeError> {
+    if text.contains('\0') {
+        return Err(EncodeError::Nul);
+    }
+
+    if text.is_ascii() {
+        return Ok(Cow::Borrowed(text.as_bytes()));
+    }
+
+    let mut data = Vec::with_capacity(text.len());
+    let mut in_segment = false;
+
+    for c in text.chars() {
+        let latin1 = match u32::from(c) {
+            0x80..=0x9f => None,
+            code => u8::try_from(code).ok(),
+        };
+
+        match latin1 {
+            Some(byte) => {
+                if in_segment {
+                    data.extend_from_slice(b"\x1b%@");
+                    in_segment = false;
+                }
+
+                data.push(byte);
+            }
+            None => {
+                if !in_segment {
+                    data.extend_from_slice(b"\x1b%G");
+                    in_segment = true;
+                }
+
+                data.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+    }
+
+    if in_segment {
+        data.extend_from_slice(b"\x1b%@");
+    }
+
+    Ok(Cow::Owned(data))
+}
+
+/// Decodes compound text, borrowing `data` if it is only made of ASCII.
+pub fn decode(data: &[u8]) -> Result<Cow<'_, str>, DecodeError> {
+    if data.is_ascii() && !data.contains(&ESC) {
+        return Ok(latin1(data));
+    }
+
+    let mut text = String::with_capacity(data.len());
+    let mut left = Left::Ascii;
+    let mut right = Right::Latin1;
+    let mut offset = 0;
+
+    while let Some(&byte) = data.get(offset) {
+        match byte {
+            ESC => {
+                offset = escape(data, offset, &mut left, &mut right, &mut text)?;
+                continue;
+            }
+
+            CSI => {
+                offset = control_sequence(data, offset)?;
+                continue;
+            }
+
+            0x80..=0x9f => return Err(DecodeError::InvalidCompoundText(offset)),
+
+            0x21..=0x7e => text.push(left.decode(byte)),
+
+            0xa0..=0xff => text.push(
+                right
+                    .decode(byte)
+                    .ok_or(DecodeError::InvalidCompoundText(offset))?,
+            ),
+
+            _ => text.push(byte as char),
+        }
+
+        offset += 1;
+    }
+
+    Ok(Cow::Owned(text))
+}
+
+/// Handles the escape sequence at `start`, returning the offset following it and the segment it
+/// introduces, if any.
+fn escape(
+    data: &[u8],
+    start: usize,
+    left: &mut Left,
+    right: &mut Right,
+    text: &mut String,
+) -> Result<usize, DecodeError> {
+    let mut end = start + 1;
+
+    while let Some(0x20..=0x2f) = data.get(end) {
+        end += 1;
+    }
+
+    let last = match data.get(end) {
+        Some(&last @ 0x30..=0x7e) => last,
+        _ => return Err(DecodeError::InvalidCompoundText(start)),
+    };
+
+    match (&data[start + 1..end], last) {
+        (b"(", b'B') => *left = Left::Ascii,
+        (b"(", b'J') => *left = Left::JisRoman,
+        (b")", b'I') => *right = Right::Katakana,
+        (b"-", b'A') => *right = Right::Latin1,
+        (b"-", b'L') => *right = Right::Cyrillic,
+
+        (b"%", b'G') => {
+            let segment = &data[end + 1..];
+            let length = segment
+                .windows(3)
+                .position(|window| window == b"\x1b%@")
+                .unwrap_or(segment.len());
+
+            text.push_str(str::from_utf8(&segment[..length]).map_err(DecodeError::InvalidUtf8)?);
+
+            return Ok((end + 1 + length + 3).min(data.len()));
+        }
+
+        (b"%", b'@') => (),
+
+        (b"%/", b'0'..=b'4') => return extended_segment(data, start, end + 1, text),
+
+        _ => return Err(DecodeError::UnsupportedCharset(data[start..=end].to_vec())),
+    }
+
+    Ok(end + 1)
+}
+
+/// Decodes the extended segment whose length starts at `offset`, returning the offset following
+/// it.
+fn extended_segment(
+    data: &[u8],
+    start: usize,
+    offset: usize,
+    text: &mut String,
+) -> Result<usize, DecodeError> {
+    let invalid = DecodeError::InvalidCompoundText(start);
+
+    let length = match data.get(offset..offset + 2) {
+        Some(&[high @ 0x80..=0xff, low @ 0x80..=0xff]) => {
+            usize::from(high - 0x80) * 128 + usize::from(low - 0x80)
+        }
+        _ => return Err(invalid),
+    };
+
+    let end = offset + 2 + length;
+    let segment = data.get(offset + 2..end).ok_or(invalid.clone())?;
+    let separator = segment
+        .iter()
+        .position(|&byte| byte == STX)
+        .ok_or(invalid)?;
+    let (name, bytes) = (&segment[..separator], &segment[separator + 1..]);
+
+    match &name.to_ascii_lowercase()[..] {
+        b"iso8859-1" => text.push_str(&latin1(bytes)),
+        b"utf-8" => text.push_str(str::from_utf8(bytes).map_err(DecodeError::InvalidUtf8)?),
+        _ => return Err(DecodeError::UnsupportedCharset(name.to_vec())),
+    }
+
+    Ok(end)
+}
+
+/// Skips the control sequence at `start`, which only carries the direction of the text.
+fn control_sequence(data: &[u8], start: usize) -> Result<usize, DecodeError> {
+    let mut end = start + 1;
+
+    while let Some(0x20..=0x3f) = data.get(end) {
+        end += 1;
+    }
+
+    match data.get(end) {
+        Some(0x40..=0x7e) => Ok(end + 1),
+        _ => Err(DecodeError::InvalidCompoundText(start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(data: &[u8]) -> String {
+        decode(data).unwrap().into_owned()
+    }
+
+    #[test]
+    fn ascii() {
+        assert!(matches!(decode(b"xterm"), Ok(Cow::Borrowed("xterm"))));
+        assert_eq!(decoded(b"a\tb\n"), "a\tb\n");
+    }
+
+    #[test]
+    fn latin1_right_half() {
+        assert_eq!(decoded(b"caf\xe9 \xa0\xff"), "caf\u{e9} \u{a0}\u{ff}");
+        assert_eq!(decoded(b"\x1b-L\xbf\x1b-A\xbf"), "\u{41f}\u{bf}");
+    }
+
+    #[test]
+    fn cyrillic() {
+        assert_eq!(
+            decoded(b"\x1b-L\xbf\xe0\xd8\xd2\xd5\xe2 \xf0\xfd"),
+            "\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442} \u{2116}\u{a7}"
+        );
+    }
+
+    #[test]
+    fn katakana() {
+        assert_eq!(decoded(b"\x1b)I\xb1\xb2"), "\u{ff71}\u{ff72}");
+        assert_eq!(
+            decode(b"\x1b)I\xe0"),
+            Err(DecodeError::InvalidCompoundText(3))
+        );
+    }
+
+    #[test]
+    fn jis_roman() {
+        assert_eq!(decoded(b"\x1b(J\\~\x1b(B\\~"), "\u{a5}\u{203e}\\~");
+    }
+
+    #[test]
+    fn utf8_segments() {
+        assert_eq!(decoded(b"a\x1b%G\xe2\x82\xac\x1b%@b"), "a\u{20ac}b");
+        assert_eq!(decoded(b"\x1b%G\xe2\x82\xac"), "\u{20ac}");
+        assert!(matches!(
+            decode(b"\x1b%G\xff\x1b%@"),
+            Err(DecodeError::InvalidUtf8(_))
+        ));
+    }
+
+    #[test]
+    fn extended_segments() {
+        assert_eq!(decoded(b"\x1b%/1\x80\x88utf-8\x02\xc3\xa9!"), "\u{e9}!");
+        assert_eq!(decoded(b"\x1b%/1\x80\x8bISO8859-1\x02\xe9"), "\u{e9}");
+        assert_eq!(
+            decode(b"\x1b%/2\x80\x86big5\x02\xa4"),
+            Err(DecodeError::UnsupportedCharset(b"big5".to_vec()))
+        );
+        assert_eq!(
+            decode(b"\x1b%/1\x80\x84utf-8"),
+            Err(DecodeError::InvalidCompoundText(0))
+        );
+    }
+
+    #[test]
+    fn direction() {
+        assert_eq!(decoded(b"\x9b1]abc\x9b]"), "abc");
+        assert_eq!(decoded(b"\x9b2]abc\x9b]"), "abc");
+    }
+
+    #[test]
+    fn truncated() {
+        let truncated: [&[u8]; 6] = [
+            b"ab\x1b",
+            b"ab\x1b(",
+            b"ab\x1b%/1",
+            b"ab\x1b%/1\x80",
+            b"ab\x1b%/1\x80\x90utf-8\x02",
+            b"ab\x9b1",
+        ];
+
+        for data in truncated {
+            assert_eq!(
+                decode(data),
+                Err(DecodeError::InvalidCompoundText(2)),
+                "{:?}",
+                data
+            );
+        }
+
+        assert_eq!(
+            decode(b"\x1b$(A!!"),
+            Err(DecodeError::UnsupportedCharset(b"\x1b$(A".to_vec()))
+        );
+    }
+
+    #[test]
+    fn encoding() {
+        assert!(matches!(encode("xterm"), Ok(Cow::Borrowed(b"xterm"))));
+        assert_eq!(encode("caf\u{e9}").unwrap(), &b"caf\xe9"[..]);
+        assert_eq!(
+            encode("a\u{20ac}\u{20ac}b\u{85}").unwrap(),
+            &b"a\x1b%G\xe2\x82\xac\xe2\x82\xac\x1b%@b\x1b%G\xc2\x85\x1b%@"[..]
+        );
+        assert_eq!(encode("a\0b"), Err(EncodeError::Nul));
+
+        for text in ["caf\u{e9}", "\u{41f}\u{440}\u{438}", "a\u{20ac}b\u{85}"] {
+            assert_eq!(decoded(&encode(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn latin1_encoding() {
+        assert!(matches!(to_latin1("xterm"), Ok(Cow::Borrowed(b"xterm"))));
+        assert_eq!(to_latin1("caf\u{e9}").unwrap(), &b"caf\xe9"[..]);
+        assert_eq!(
+            to_latin1("\u{20ac}"),
+            Err(EncodeError::UnrepresentableChar('\u{20ac}'))
+        );
+        assert_eq!(to_latin1("a\0b"), Err(EncodeError::Nul));
+    }
+}
@@ -0,0 +1,178 @@
+use xcb::x;
+
+use util::gravity;
+
+/// Space a window manager adds around the interior of a client window when framing it.
+///
+/// A border kept on the client while it is framed is part of the extents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrameExtents {
+    pub left: u16,
+    pub right: u16,
+    pub top: u16,
+    pub bottom: u16,
+}
+
+#[cfg(any(feature = "ewmh", feature = "ewmh-native"))]
+impl From<::ewmh::Extents> for FrameExtents {
+    /// The extents of `_NET_FRAME_EXTENTS`, saturated to the size of a window.
+    fn from(extents: ::ewmh::Extents) -> Self {
+        let saturate = |value: u32| value.min(u32::from(u16::MAX)) as u16;
+
+        Self {
+            left: saturate(extents.left),
+            right: saturate(extents.right),
+            top: saturate(extents.top),
+            bottom: saturate(extents.bottom),
+        }
+    }
+}
+
+/// Brings a coordinate back into the range of `i16`.
+fn clamp(value: i32) -> i16 {
+    value.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16
+}
+
+impl FrameExtents {
+    /// How much the frame is moved from the client position to keep the reference point of
+    /// `gravity` in place, as described in ICCCM 4.1.2.3.
+    ///
+    /// `border_width` is the border of the unframed client. `BitForget` is not a window gravity and
+    /// is handled as `NorthWest`, its default.
+    pub fn gravity_offset(&self, gravity: x::Gravity, border_width: u16) -> (i32, i32) {
+        let (x, y) = gravity::offset(
+            gravity,
+            i64::from(border_width),
+            (
+                i64::from(self.left),
+                i64::from(self.right),
+                i64::from(self.top),
+                i64::from(self.bottom),
+            ),
+        );
+
+        // Offsets are bounded by twice the extents and border, which fit in an i32.
+        (x as i32, y as i32)
+    }
+
+    /// The geometry of the frame around `client`, whose size is the one of its interior and whose
+    /// position is the one it asked for.
+    pub fn frame_geometry(
+        &self,
+        gravity: x::Gravity,
+        border_width: u16,
+        client: x::Rectangle,
+    ) -> x::Rectangle {
+        let (x, y) = self.gravity_offset(gravity, border_width);
+
+        x::Rectangle {
+            x: clamp(i32::from(client.x) + x),
+            y: clamp(i32::from(client.y) + y),
+            width: client
+                .width
+                .saturating_add(self.left)
+                .saturating_add(self.right),
+            height: client
+                .height
+                .saturating_add(self.top)
+                .saturating_add(self.bottom),
+        }
+    }
+
+    /// The geometry of the client inside `frame`, with the position it would have once unframed,
+    /// for instance when the window manager exits.
+    pub fn client_geometry(
+        &self,
+        gravity: x::Gravity,
+        border_width: u16,
+        frame: x::Rectangle,
+    ) -> x::Rectangle {
+        let (x, y) = self.gravity_offset(gravity, border_width);
+
+        x::Rectangle {
+            x: clamp(i32::from(frame.x) - x),
+            y: clamp(i32::from(frame.y) - y),
+            width: frame
+                .width
+                .saturating_sub(self.left)
+                .saturating_sub(self.right),
+            height: frame
+                .height
+                .saturating_sub(self.top)
+                .saturating_sub(self.bottom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTENTS: FrameExtents = FrameExtents {
+        left: 2,
+        right: 4,
+        top: 10,
+        bottom: 6,
+    };
+
+    fn parts(rectangle: x::Rectangle) -> (i16, i16, u16, u16) {
+        (rectangle.x, rectangle.y, rectangle.width, rectangle.height)
+    }
+
+    #[test]
+    fn gravity_offsets() {
+        let offsets = [
+            (x::Gravity::BitForget, (0, 0)),
+            (x::Gravity::NorthWest, (0, 0)),
+            (x::Gravity::North, (-2, 0)),
+            (x::Gravity::NorthEast, (-4, 0)),
+            (x::Gravity::West, (0, -7)),
+            (x::Gravity::Center, (-2, -7)),
+            (x::Gravity::East, (-4, -7)),
+            (x::Gravity::SouthWest, (0, -14)),
+            (x::Gravity::South, (-2, -14)),
+            (x::Gravity::SouthEast, (-4, -14)),
+            (x::Gravity::Static, (-1, -9)),
+        ];
+
+        for (gravity, offset) in offsets {
+            assert_eq!(EXTENTS.gravity_offset(gravity, 1), offset, "{:?}", gravity);
+        }
+    }
+
+    #[test]
+    fn geometry_round_trip() {
+        let client = x::Rectangle {
+            x: 100,
+            y: 50,
+            width: 640,
+            height: 480,
+        };
+
+        let frame = EXTENTS.frame_geometry(x::Gravity::SouthEast, 1, client);
+
+        assert_eq!(parts(frame), (96, 36, 646, 496));
+        assert_eq!(
+            parts(EXTENTS.client_geometry(x::Gravity::SouthEast, 1, frame)),
+            parts(client)
+        );
+    }
+
+    #[test]
+    fn geometry_clamps_positions() {
+        let client = x::Rectangle {
+            x: i16::MAX,
+            y: i16::MIN,
+            width: 1,
+            height: 1,
+        };
+
+        let frame = FrameExtents::default().frame_geometry(x::Gravity::Center, 1000, client);
+
+        assert_eq!((frame.x, frame.y), (i16::MAX, i16::MIN + 1000));
+
+        let client = FrameExtents::default().client_geometry(x::Gravity::Center, 1000, client);
+
+        assert_eq!((client.x, client.y), (i16::MAX - 1000, i16::MIN));
+    }
+}
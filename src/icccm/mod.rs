@@ -0,0 +1,254 @@
+use xcb::x;
+
+use atoms::AtomCache;
+
+#[cfg(not(feature = "icccm-native"))]
+pub use xcb_util_sys::icccm as ffi;
+
+#[cfg(feature = "icccm-native")]
+#[path = "native.rs"]
+pub mod ffi;
+
+/// The connection as taken by the functions of `ffi`.
+#[cfg(not(feature = "icccm-native"))]
+fn ffi_connection(connection: &xcb::Connection) -> *mut xcb::ffi::xcb_connection_t {
+    connection.get_raw_conn()
+}
+
+/// The connection as taken by the functions of `ffi`.
+#[cfg(feature = "icccm-native")]
+fn ffi_connection(connection: &xcb::Connection) -> &xcb::Connection {
+    connection
+}
+
+/// Defines the reply and cookies of a property request, wrapping the ones of `x::GetProperty`.
+macro_rules! property_reply {
+    ($reply:ident, $cookie:ident, $cookie_unchecked:ident) => {
+        pub struct $reply(x::GetPropertyReply);
+
+        impl xcb::Reply for $reply {
+            unsafe fn from_raw(raw: *const u8) -> Self {
+                Self(<x::GetPropertyReply as xcb::Reply>::from_raw(raw))
+            }
+
+            unsafe fn into_raw(self) -> *const u8 {
+                <x::GetPropertyReply as xcb::Reply>::into_raw(self.0)
+            }
+
+            unsafe fn as_raw(&self) -> *const u8 {
+                <x::GetPropertyReply as xcb::Reply>::as_raw(&self.0)
+            }
+        }
+
+        pub struct $cookie(x::GetPropertyCookie);
+
+        impl xcb::Cookie for $cookie {
+            unsafe fn from_sequence(seq: u64) -> Self {
+                Self(x::GetPropertyCookie::from_sequence(seq))
+            }
+
+            fn sequence(&self) -> u64 {
+                self.0.sequence()
+            }
+        }
+
+        unsafe impl xcb::CookieChecked for $cookie {}
+
+        unsafe impl xcb::CookieWithReplyChecked for $cookie {
+            type Reply = $reply;
+        }
+
+        pub struct $cookie_unchecked(x::GetPropertyCookieUnchecked);
+
+        impl xcb::Cookie for $cookie_unchecked {
+            unsafe fn from_sequence(seq: u64) -> Self {
+                Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+            }
+
+            fn sequence(&self) -> u64 {
+                self.0.sequence()
+            }
+        }
+
+        unsafe impl xcb::CookieWithReplyUnchecked for $cookie_unchecked {
+            type Reply = $reply;
+        }
+    };
+}
+
+/// Implements the request traits of a property getter.
+macro_rules! property_request {
+    ($request:ident => $reply:ident, $cookie:ident, $cookie_unchecked:ident) => {
+        impl xcb::Request for $request {
+            type Cookie = $cookie;
+
+            const IS_VOID: bool = false;
+        }
+
+        impl xcb::RequestWithReply for $request {
+            type Reply = $reply;
+            type Cookie = $cookie;
+            type CookieUnchecked = $cookie_unchecked;
+        }
+    };
+}
+
+/// Implements the request traits of a property setter.
+macro_rules! void_request {
+    ($request:ident $(<$lt:lifetime>)?) => {
+        impl$(<$lt>)? xcb::Request for $request$(<$lt>)? {
+            type Cookie = xcb::VoidCookie;
+
+            const IS_VOID: bool = true;
+        }
+
+        impl$(<$lt>)? xcb::RequestWithoutReply for $request$(<$lt>)? {}
+    };
+}
+
+mod compound_text;
+mod gravity;
+mod sm_client_id;
+mod state_tracker;
+mod text_property;
+mod wm_class;
+mod wm_client_leader;
+mod wm_client_machine;
+mod wm_colormap_windows;
+mod wm_command;
+mod wm_hints;
+mod wm_icon_name;
+mod wm_locale_name;
+mod wm_name;
+mod wm_protocols;
+mod wm_size_hints;
+mod wm_state;
+mod wm_transient_for;
+mod wm_window_role;
+
+pub use self::gravity::*;
+pub use self::sm_client_id::*;
+pub use self::state_tracker::*;
+pub use self::text_property::*;
+pub use self::wm_class::*;
+pub use self::wm_client_leader::*;
+pub use self::wm_client_machine::*;
+pub use self::wm_colormap_windows::*;
+pub use self::wm_command::*;
+pub use self::wm_hints::*;
+pub use self::wm_icon_name::*;
+pub use self::wm_locale_name::*;
+pub use self::wm_name::*;
+pub use self::wm_protocols::*;
+pub use self::wm_size_hints::*;
+pub use self::wm_state::*;
+pub use self::wm_transient_for::*;
+pub use self::wm_window_role::*;
+
+pub type WmState = ffi::xcb_icccm_wm_state_t;
+pub const WM_STATE_WITHDRAWN: WmState = ffi::XCB_ICCCM_WM_STATE_WITHDRAWN;
+pub const WM_STATE_NORMAL: WmState = ffi::XCB_ICCCM_WM_STATE_NORMAL;
+pub const WM_STATE_ICONIC: WmState = ffi::XCB_ICCCM_WM_STATE_ICONIC;
+
+xcb::atoms_struct! {
+    /// The atoms of the ICCCM which are not predefined, for the requests and helpers needing
+    /// them.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Atoms {
+        pub wm_state => b"WM_STATE" only_if_exists = false,
+        pub wm_change_state => b"WM_CHANGE_STATE" only_if_exists = false,
+        pub wm_protocols => b"WM_PROTOCOLS" only_if_exists = false,
+        pub wm_delete_window => b"WM_DELETE_WINDOW" only_if_exists = false,
+        pub wm_take_focus => b"WM_TAKE_FOCUS" only_if_exists = false,
+        pub wm_colormap_windows => b"WM_COLORMAP_WINDOWS" only_if_exists = false,
+        pub wm_client_leader => b"WM_CLIENT_LEADER" only_if_exists = false,
+        pub wm_window_role => b"WM_WINDOW_ROLE" only_if_exists = false,
+        pub wm_locale_name => b"WM_LOCALE_NAME" only_if_exists = false,
+        pub sm_client_id => b"SM_CLIENT_ID" only_if_exists = false,
+        pub utf8_string => b"UTF8_STRING" only_if_exists = false,
+        pub compound_text => b"COMPOUND_TEXT" only_if_exists = false,
+    }
+}
+
+impl Atoms {
+    /// The atoms looked up in `cache`, the missing ones being interned in a single batch.
+    pub fn from_cache(cache: &AtomCache, connection: &xcb::Connection) -> xcb::Result<Self> {
+        let atoms = cache.get_all(
+            connection,
+            &[
+                b"WM_STATE",
+                b"WM_CHANGE_STATE",
+                b"WM_PROTOCOLS",
+                b"WM_DELETE_WINDOW",
+                b"WM_TAKE_FOCUS",
+                b"WM_COLORMAP_WINDOWS",
+                b"WM_CLIENT_LEADER",
+                b"WM_WINDOW_ROLE",
+                b"WM_LOCALE_NAME",
+                b"SM_CLIENT_ID",
+                b"UTF8_STRING",
+                b"COMPOUND_TEXT",
+            ],
+        )?;
+
+        Ok(Self {
+            wm_state: atoms[0],
+            wm_change_state: atoms[1],
+            wm_protocols: atoms[2],
+            wm_delete_window: atoms[3],
+            wm_take_focus: atoms[4],
+            wm_colormap_windows: atoms[5],
+            wm_client_leader: atoms[6],
+            wm_window_role: atoms[7],
+            wm_locale_name: atoms[8],
+            sm_client_id: atoms[9],
+            utf8_string: atoms[10],
+            compound_text: atoms[11],
+        })
+    }
+}
+
+/// Sends a `ChangeProperty` replacing the value of `property`.
+fn change_property<P: x::PropEl>(
+    connection: &xcb::Connection,
+    checked: bool,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    data: &[P],
+) -> u64 {
+    xcb::RawRequest::raw_request(
+        &x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property,
+            r#type,
+            data,
+        },
+        connection,
+        checked,
+    )
+}
+
+/// Sends a `GetProperty` for the first `long_length` words of `property`.
+fn get_property(
+    connection: &xcb::Connection,
+    checked: bool,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    long_length: u32,
+) -> u64 {
+    xcb::RawRequest::raw_request(
+        &x::GetProperty {
+            delete: false,
+            window,
+            property,
+            r#type,
+            long_offset: 0,
+            long_length,
+        },
+        connection,
+        checked,
+    )
+}
@@ -0,0 +1,919 @@
+//! Rust implementation of the `libxcb-icccm` functions used by this module, built on top of
+//! `xcb::x` requests.
+//!
+//! It mirrors the C API closely so that the rest of the module does not need to know which
+//! backend it is talking to, except for taking the connection by reference. The `WM_SIZE_HINTS`,
+//! `WM_HINTS` and `WM_CLASS` layouts are encoded and decoded by safe functions, which the C-like
+//! entry points wrap.
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{c_char, c_int, c_uint};
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+use xcb::{x, Xid, XidNew};
+
+pub type xcb_atom_t = u32;
+pub type xcb_window_t = u32;
+pub type xcb_pixmap_t = u32;
+pub type xcb_gravity_t = c_uint;
+
+pub type xcb_icccm_wm_state_t = c_uint;
+pub const XCB_ICCCM_WM_STATE_WITHDRAWN: xcb_icccm_wm_state_t = 0;
+pub const XCB_ICCCM_WM_STATE_NORMAL: xcb_icccm_wm_state_t = 1;
+pub const XCB_ICCCM_WM_STATE_ICONIC: xcb_icccm_wm_state_t = 3;
+
+pub type xcb_icccm_size_hints_flags_t = c_uint;
+pub const XCB_ICCCM_SIZE_HINT_US_POSITION: xcb_icccm_size_hints_flags_t = 1 << 0;
+pub const XCB_ICCCM_SIZE_HINT_US_SIZE: xcb_icccm_size_hints_flags_t = 1 << 1;
+pub const XCB_ICCCM_SIZE_HINT_P_POSITION: xcb_icccm_size_hints_flags_t = 1 << 2;
+pub const XCB_ICCCM_SIZE_HINT_P_SIZE: xcb_icccm_size_hints_flags_t = 1 << 3;
+pub const XCB_ICCCM_SIZE_HINT_P_MIN_SIZE: xcb_icccm_size_hints_flags_t = 1 << 4;
+pub const XCB_ICCCM_SIZE_HINT_P_MAX_SIZE: xcb_icccm_size_hints_flags_t = 1 << 5;
+pub const XCB_ICCCM_SIZE_HINT_P_RESIZE_INC: xcb_icccm_size_hints_flags_t = 1 << 6;
+pub const XCB_ICCCM_SIZE_HINT_P_ASPECT: xcb_icccm_size_hints_flags_t = 1 << 7;
+pub const XCB_ICCCM_SIZE_HINT_BASE_SIZE: xcb_icccm_size_hints_flags_t = 1 << 8;
+pub const XCB_ICCCM_SIZE_HINT_P_WIN_GRAVITY: xcb_icccm_size_hints_flags_t = 1 << 9;
+
+pub type xcb_icccm_wm_t = c_uint;
+pub const XCB_ICCCM_WM_HINT_INPUT: xcb_icccm_wm_t = 1 << 0;
+pub const XCB_ICCCM_WM_HINT_STATE: xcb_icccm_wm_t = 1 << 1;
+pub const XCB_ICCCM_WM_HINT_ICON_PIXMAP: xcb_icccm_wm_t = 1 << 2;
+pub const XCB_ICCCM_WM_HINT_ICON_WINDOW: xcb_icccm_wm_t = 1 << 3;
+pub const XCB_ICCCM_WM_HINT_ICON_POSITION: xcb_icccm_wm_t = 1 << 4;
+pub const XCB_ICCCM_WM_HINT_ICON_MASK: xcb_icccm_wm_t = 1 << 5;
+pub const XCB_ICCCM_WM_HINT_WINDOW_GROUP: xcb_icccm_wm_t = 1 << 6;
+pub const XCB_ICCCM_WM_HINT_X_URGENCY: xcb_icccm_wm_t = 1 << 8;
+
+/// Number of words of a `WM_SIZE_HINTS` property.
+pub const XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS: usize = 18;
+/// Number of words of a `WM_HINTS` property.
+pub const XCB_ICCCM_NUM_WM_HINTS_ELEMENTS: usize = 9;
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_get_property_cookie_t {
+    pub sequence: c_uint,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct xcb_void_cookie_t {
+    pub sequence: c_uint,
+}
+
+/// Wire representation of a `GetProperty` reply.
+pub enum xcb_get_property_reply_t {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct xcb_size_hints_t {
+    pub flags: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub min_width: i32,
+    pub min_height: i32,
+    pub max_width: i32,
+    pub max_height: i32,
+    pub width_inc: i32,
+    pub height_inc: i32,
+    pub min_aspect_num: i32,
+    pub min_aspect_den: i32,
+    pub max_aspect_num: i32,
+    pub max_aspect_den: i32,
+    pub base_width: i32,
+    pub base_height: i32,
+    pub win_gravity: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct xcb_icccm_wm_hints_t {
+    pub flags: i32,
+    pub input: u32,
+    pub initial_state: i32,
+    pub icon_pixmap: xcb_pixmap_t,
+    pub icon_window: xcb_window_t,
+    pub icon_x: i32,
+    pub icon_y: i32,
+    pub icon_mask: xcb_pixmap_t,
+    pub window_group: xcb_window_t,
+}
+
+#[repr(C)]
+pub struct xcb_icccm_get_wm_class_reply_t {
+    pub instance_name: *mut c_char,
+    pub class_name: *mut c_char,
+    pub _reply: *mut xcb_get_property_reply_t,
+}
+
+/// Decodes the words of a `WM_SIZE_HINTS` property.
+///
+/// Pre-ICCCM clients only set the first 15 words, in which case the base size and gravity are
+/// dropped.
+pub fn size_hints_from_values(values: &[u32]) -> Option<xcb_size_hints_t> {
+    if values.len() < XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS - 3 {
+        return None;
+    }
+
+    let value = |index: usize| values.get(index).copied().unwrap_or(0);
+
+    let mut flags = XCB_ICCCM_SIZE_HINT_US_POSITION
+        | XCB_ICCCM_SIZE_HINT_US_SIZE
+        | XCB_ICCCM_SIZE_HINT_P_POSITION
+        | XCB_ICCCM_SIZE_HINT_P_SIZE
+        | XCB_ICCCM_SIZE_HINT_P_MIN_SIZE
+        | XCB_ICCCM_SIZE_HINT_P_MAX_SIZE
+        | XCB_ICCCM_SIZE_HINT_P_RESIZE_INC
+        | XCB_ICCCM_SIZE_HINT_P_ASPECT;
+
+    if values.len() >= XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS {
+        flags |= XCB_ICCCM_SIZE_HINT_BASE_SIZE | XCB_ICCCM_SIZE_HINT_P_WIN_GRAVITY;
+    }
+
+    Some(xcb_size_hints_t {
+        flags: value(0) & flags,
+        x: value(1) as i32,
+        y: value(2) as i32,
+        width: value(3) as i32,
+        height: value(4) as i32,
+        min_width: value(5) as i32,
+        min_height: value(6) as i32,
+        max_width: value(7) as i32,
+        max_height: value(8) as i32,
+        width_inc: value(9) as i32,
+        height_inc: value(10) as i32,
+        min_aspect_num: value(11) as i32,
+        min_aspect_den: value(12) as i32,
+        max_aspect_num: value(13) as i32,
+        max_aspect_den: value(14) as i32,
+        base_width: value(15) as i32,
+        base_height: value(16) as i32,
+        win_gravity: value(17),
+    })
+}
+
+/// Encodes the words of a `WM_SIZE_HINTS` property.
+pub fn size_hints_to_values(
+    hints: &xcb_size_hints_t,
+) -> [u32; XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS] {
+    [
+        hints.flags,
+        hints.x as u32,
+        hints.y as u32,
+        hints.width as u32,
+        hints.height as u32,
+        hints.min_width as u32,
+        hints.min_height as u32,
+        hints.max_width as u32,
+        hints.max_height as u32,
+        hints.width_inc as u32,
+        hints.height_inc as u32,
+        hints.min_aspect_num as u32,
+        hints.min_aspect_den as u32,
+        hints.max_aspect_num as u32,
+        hints.max_aspect_den as u32,
+        hints.base_width as u32,
+        hints.base_height as u32,
+        hints.win_gravity,
+    ]
+}
+
+/// Decodes the words of a `WM_HINTS` property.
+///
+/// Pre-ICCCM clients do not set the window group, which is then zero.
+pub fn wm_hints_from_values(values: &[u32]) -> Option<xcb_icccm_wm_hints_t> {
+    if values.len() < XCB_ICCCM_NUM_WM_HINTS_ELEMENTS - 1 {
+        return None;
+    }
+
+    let value = |index: usize| values.get(index).copied().unwrap_or(0);
+
+    Some(xcb_icccm_wm_hints_t {
+        flags: value(0) as i32,
+        input: value(1),
+        initial_state: value(2) as i32,
+        icon_pixmap: value(3),
+        icon_window: value(4),
+        icon_x: value(5) as i32,
+        icon_y: value(6) as i32,
+        icon_mask: value(7),
+        window_group: value(8),
+    })
+}
+
+/// Encodes the words of a `WM_HINTS` property.
+pub fn wm_hints_to_values(hints: &xcb_icccm_wm_hints_t) -> [u32; XCB_ICCCM_NUM_WM_HINTS_ELEMENTS] {
+    [
+        hints.flags as u32,
+        hints.input,
+        hints.initial_state as u32,
+        hints.icon_pixmap,
+        hints.icon_window,
+        hints.icon_x as u32,
+        hints.icon_y as u32,
+        hints.icon_mask,
+        hints.window_group,
+    ]
+}
+
+/// Splits the bytes of a `WM_CLASS` property into its instance and class names.
+///
+/// Both names are expected to be null-terminated, a missing class name is empty.
+pub fn wm_class_from_bytes(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let mut names = bytes.splitn(2, |&b| b == 0);
+
+    let instance = names.next().unwrap_or(&[]);
+    let class = names.next().unwrap_or(&[]);
+    let class = class.split(|&b| b == 0).next().unwrap_or(class);
+
+    (instance, class)
+}
+
+/// Encodes the bytes of a `WM_CLASS` property.
+pub fn wm_class_to_bytes(instance: &[u8], class: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(instance.len() + class.len() + 2);
+
+    bytes.extend_from_slice(instance);
+    bytes.push(0);
+    bytes.extend_from_slice(class);
+    bytes.push(0);
+
+    bytes
+}
+
+/// Borrows the reply behind a raw pointer without taking ownership of it.
+unsafe fn borrow_reply(r: *mut xcb_get_property_reply_t) -> ManuallyDrop<x::GetPropertyReply> {
+    ManuallyDrop::new(<x::GetPropertyReply as xcb::Reply>::from_raw(
+        r as *const u8,
+    ))
+}
+
+fn value<P: x::PropEl>(reply: &x::GetPropertyReply, r#type: x::Atom) -> Option<&[P]> {
+    if reply.r#type() == r#type && reply.format() == P::FORMAT {
+        Some(reply.value::<P>())
+    } else {
+        None
+    }
+}
+
+unsafe fn change_property<P: x::PropEl>(
+    c: &xcb::Connection,
+    checked: bool,
+    window: xcb_window_t,
+    property: x::Atom,
+    r#type: x::Atom,
+    data: &[P],
+) -> xcb_void_cookie_t {
+    let request = x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: x::Window::new(window),
+        property,
+        r#type,
+        data,
+    };
+
+    xcb_void_cookie_t {
+        sequence: xcb::RawRequest::raw_request(&request, c, checked) as c_uint,
+    }
+}
+
+unsafe fn get_property(
+    c: &xcb::Connection,
+    checked: bool,
+    window: xcb_window_t,
+    property: x::Atom,
+    r#type: x::Atom,
+    long_length: u32,
+) -> xcb_get_property_cookie_t {
+    let request = x::GetProperty {
+        delete: false,
+        window: x::Window::new(window),
+        property,
+        r#type,
+        long_offset: 0,
+        long_length,
+    };
+
+    xcb_get_property_cookie_t {
+        sequence: xcb::RawRequest::raw_request(&request, c, checked) as c_uint,
+    }
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_position(
+    hints: *mut xcb_size_hints_t,
+    user_specified: c_int,
+    x: i32,
+    y: i32,
+) {
+    (*hints).flags &= !(XCB_ICCCM_SIZE_HINT_US_POSITION | XCB_ICCCM_SIZE_HINT_P_POSITION);
+    (*hints).flags |= if user_specified != 0 {
+        XCB_ICCCM_SIZE_HINT_US_POSITION
+    } else {
+        XCB_ICCCM_SIZE_HINT_P_POSITION
+    };
+    (*hints).x = x;
+    (*hints).y = y;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_size(
+    hints: *mut xcb_size_hints_t,
+    user_specified: c_int,
+    width: i32,
+    height: i32,
+) {
+    (*hints).flags &= !(XCB_ICCCM_SIZE_HINT_US_SIZE | XCB_ICCCM_SIZE_HINT_P_SIZE);
+    (*hints).flags |= if user_specified != 0 {
+        XCB_ICCCM_SIZE_HINT_US_SIZE
+    } else {
+        XCB_ICCCM_SIZE_HINT_P_SIZE
+    };
+    (*hints).width = width;
+    (*hints).height = height;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_min_size(
+    hints: *mut xcb_size_hints_t,
+    min_width: i32,
+    min_height: i32,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_P_MIN_SIZE;
+    (*hints).min_width = min_width;
+    (*hints).min_height = min_height;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_max_size(
+    hints: *mut xcb_size_hints_t,
+    max_width: i32,
+    max_height: i32,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_P_MAX_SIZE;
+    (*hints).max_width = max_width;
+    (*hints).max_height = max_height;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_resize_inc(
+    hints: *mut xcb_size_hints_t,
+    width_inc: i32,
+    height_inc: i32,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_P_RESIZE_INC;
+    (*hints).width_inc = width_inc;
+    (*hints).height_inc = height_inc;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_aspect(
+    hints: *mut xcb_size_hints_t,
+    min_aspect_num: i32,
+    min_aspect_den: i32,
+    max_aspect_num: i32,
+    max_aspect_den: i32,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_P_ASPECT;
+    (*hints).min_aspect_num = min_aspect_num;
+    (*hints).min_aspect_den = min_aspect_den;
+    (*hints).max_aspect_num = max_aspect_num;
+    (*hints).max_aspect_den = max_aspect_den;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_base_size(
+    hints: *mut xcb_size_hints_t,
+    base_width: i32,
+    base_height: i32,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_BASE_SIZE;
+    (*hints).base_width = base_width;
+    (*hints).base_height = base_height;
+}
+
+pub unsafe fn xcb_icccm_size_hints_set_win_gravity(
+    hints: *mut xcb_size_hints_t,
+    win_gravity: xcb_gravity_t,
+) {
+    (*hints).flags |= XCB_ICCCM_SIZE_HINT_P_WIN_GRAVITY;
+    (*hints).win_gravity = win_gravity;
+}
+
+pub unsafe fn xcb_icccm_set_wm_size_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+    hints: *mut xcb_size_hints_t,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        false,
+        window,
+        x::Atom::new(property),
+        x::ATOM_WM_SIZE_HINTS,
+        &size_hints_to_values(&*hints),
+    )
+}
+
+pub unsafe fn xcb_icccm_set_wm_size_hints_checked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+    hints: *mut xcb_size_hints_t,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        true,
+        window,
+        x::Atom::new(property),
+        x::ATOM_WM_SIZE_HINTS,
+        &size_hints_to_values(&*hints),
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_size_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+) -> xcb_get_property_cookie_t {
+    get_property(
+        c,
+        true,
+        window,
+        x::Atom::new(property),
+        x::ATOM_WM_SIZE_HINTS,
+        XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS as u32,
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_size_hints_unchecked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    property: xcb_atom_t,
+) -> xcb_get_property_cookie_t {
+    get_property(
+        c,
+        false,
+        window,
+        x::Atom::new(property),
+        x::ATOM_WM_SIZE_HINTS,
+        XCB_ICCCM_NUM_WM_SIZE_HINTS_ELEMENTS as u32,
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_size_hints_from_reply(
+    hints: *mut xcb_size_hints_t,
+    reply: *mut xcb_get_property_reply_t,
+) -> u8 {
+    let reply = borrow_reply(reply);
+
+    match value::<u32>(&reply, x::ATOM_WM_SIZE_HINTS).and_then(size_hints_from_values) {
+        Some(value) => {
+            *hints = value;
+            1
+        }
+        None => 0,
+    }
+}
+
+pub unsafe fn xcb_icccm_set_wm_normal_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    hints: *mut xcb_size_hints_t,
+) -> xcb_void_cookie_t {
+    xcb_icccm_set_wm_size_hints(c, window, x::ATOM_WM_NORMAL_HINTS.resource_id(), hints)
+}
+
+pub unsafe fn xcb_icccm_set_wm_normal_hints_checked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    hints: *mut xcb_size_hints_t,
+) -> xcb_void_cookie_t {
+    xcb_icccm_set_wm_size_hints_checked(c, window, x::ATOM_WM_NORMAL_HINTS.resource_id(), hints)
+}
+
+pub unsafe fn xcb_icccm_get_wm_normal_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    xcb_icccm_get_wm_size_hints(c, window, x::ATOM_WM_NORMAL_HINTS.resource_id())
+}
+
+pub unsafe fn xcb_icccm_get_wm_normal_hints_unchecked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    xcb_icccm_get_wm_size_hints_unchecked(c, window, x::ATOM_WM_NORMAL_HINTS.resource_id())
+}
+
+pub unsafe fn xcb_icccm_wm_hints_get_urgency(hints: *const xcb_icccm_wm_hints_t) -> u32 {
+    (*hints).flags as u32 & XCB_ICCCM_WM_HINT_X_URGENCY
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_input(hints: *mut xcb_icccm_wm_hints_t, input: u8) {
+    (*hints).input = input as u32;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_INPUT as i32;
+}
+
+unsafe fn wm_hints_set_state(hints: *mut xcb_icccm_wm_hints_t, state: xcb_icccm_wm_state_t) {
+    (*hints).initial_state = state as i32;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_STATE as i32;
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_iconic(hints: *mut xcb_icccm_wm_hints_t) {
+    wm_hints_set_state(hints, XCB_ICCCM_WM_STATE_ICONIC);
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_normal(hints: *mut xcb_icccm_wm_hints_t) {
+    wm_hints_set_state(hints, XCB_ICCCM_WM_STATE_NORMAL);
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_withdrawn(hints: *mut xcb_icccm_wm_hints_t) {
+    wm_hints_set_state(hints, XCB_ICCCM_WM_STATE_WITHDRAWN);
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_none(hints: *mut xcb_icccm_wm_hints_t) {
+    (*hints).flags &= !(XCB_ICCCM_WM_HINT_STATE as i32);
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_icon_pixmap(
+    hints: *mut xcb_icccm_wm_hints_t,
+    icon_pixmap: xcb_pixmap_t,
+) {
+    (*hints).icon_pixmap = icon_pixmap;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_ICON_PIXMAP as i32;
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_icon_mask(
+    hints: *mut xcb_icccm_wm_hints_t,
+    icon_mask: xcb_pixmap_t,
+) {
+    (*hints).icon_mask = icon_mask;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_ICON_MASK as i32;
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_icon_window(
+    hints: *mut xcb_icccm_wm_hints_t,
+    icon_window: xcb_window_t,
+) {
+    (*hints).icon_window = icon_window;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_ICON_WINDOW as i32;
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_window_group(
+    hints: *mut xcb_icccm_wm_hints_t,
+    window_group: xcb_window_t,
+) {
+    (*hints).window_group = window_group;
+    (*hints).flags |= XCB_ICCCM_WM_HINT_WINDOW_GROUP as i32;
+}
+
+pub unsafe fn xcb_icccm_wm_hints_set_urgency(hints: *mut xcb_icccm_wm_hints_t) {
+    (*hints).flags |= XCB_ICCCM_WM_HINT_X_URGENCY as i32;
+}
+
+pub unsafe fn xcb_icccm_set_wm_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    hints: *mut xcb_icccm_wm_hints_t,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        false,
+        window,
+        x::ATOM_WM_HINTS,
+        x::ATOM_WM_HINTS,
+        &wm_hints_to_values(&*hints),
+    )
+}
+
+pub unsafe fn xcb_icccm_set_wm_hints_checked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    hints: *mut xcb_icccm_wm_hints_t,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        true,
+        window,
+        x::ATOM_WM_HINTS,
+        x::ATOM_WM_HINTS,
+        &wm_hints_to_values(&*hints),
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_hints(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    get_property(
+        c,
+        true,
+        window,
+        x::ATOM_WM_HINTS,
+        x::ATOM_WM_HINTS,
+        XCB_ICCCM_NUM_WM_HINTS_ELEMENTS as u32,
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_hints_unchecked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    get_property(
+        c,
+        false,
+        window,
+        x::ATOM_WM_HINTS,
+        x::ATOM_WM_HINTS,
+        XCB_ICCCM_NUM_WM_HINTS_ELEMENTS as u32,
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_hints_from_reply(
+    hints: *mut xcb_icccm_wm_hints_t,
+    reply: *mut xcb_get_property_reply_t,
+) -> u8 {
+    let reply = borrow_reply(reply);
+
+    match value::<u32>(&reply, x::ATOM_WM_HINTS).and_then(wm_hints_from_values) {
+        Some(value) => {
+            *hints = value;
+            1
+        }
+        None => 0,
+    }
+}
+
+pub unsafe fn xcb_icccm_set_wm_class(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    class_len: u32,
+    class_name: *const c_char,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        false,
+        window,
+        x::ATOM_WM_CLASS,
+        x::ATOM_STRING,
+        std::slice::from_raw_parts(class_name as *const u8, class_len as usize),
+    )
+}
+
+pub unsafe fn xcb_icccm_set_wm_class_checked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+    class_len: u32,
+    class_name: *const c_char,
+) -> xcb_void_cookie_t {
+    change_property(
+        c,
+        true,
+        window,
+        x::ATOM_WM_CLASS,
+        x::ATOM_STRING,
+        std::slice::from_raw_parts(class_name as *const u8, class_len as usize),
+    )
+}
+
+pub unsafe fn xcb_icccm_get_wm_class(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    get_property(c, true, window, x::ATOM_WM_CLASS, x::ATOM_STRING, 2048)
+}
+
+pub unsafe fn xcb_icccm_get_wm_class_unchecked(
+    c: &xcb::Connection,
+    window: xcb_window_t,
+) -> xcb_get_property_cookie_t {
+    get_property(c, false, window, x::ATOM_WM_CLASS, x::ATOM_STRING, 2048)
+}
+
+/// Like in the C library, the names point into `reply`, which is terminated in place and freed
+/// by [`xcb_icccm_get_wm_class_reply_wipe`].
+pub unsafe fn xcb_icccm_get_wm_class_from_reply(
+    prop: *mut xcb_icccm_get_wm_class_reply_t,
+    reply: *mut xcb_get_property_reply_t,
+) -> u8 {
+    static EMPTY: c_char = 0;
+
+    let (offset, len, instance_len) = {
+        let borrowed = borrow_reply(reply);
+
+        let bytes = match value::<u8>(&borrowed, x::ATOM_STRING) {
+            Some(bytes) => bytes,
+            None => return 0,
+        };
+
+        if bytes.is_empty() {
+            *prop = xcb_icccm_get_wm_class_reply_t {
+                instance_name: ptr::addr_of!(EMPTY) as *mut c_char,
+                class_name: ptr::addr_of!(EMPTY) as *mut c_char,
+                _reply: reply,
+            };
+
+            return 1;
+        }
+
+        // The value is followed by padding unless its length is a multiple of 4, in which case
+        // its last byte is overwritten by the terminator.
+        let len = if bytes.len() == borrowed.length() as usize * 4 {
+            bytes.len() - 1
+        } else {
+            bytes.len()
+        };
+
+        (
+            bytes.as_ptr().offset_from(reply as *const u8) as usize,
+            len,
+            wm_class_from_bytes(bytes).0.len(),
+        )
+    };
+
+    let data = (reply as *mut c_char).add(offset);
+    *data.add(len) = 0;
+
+    let instance_len = instance_len.min(len);
+
+    *prop = xcb_icccm_get_wm_class_reply_t {
+        instance_name: data,
+        class_name: data.add((instance_len + 1).min(len)),
+        _reply: reply,
+    };
+
+    1
+}
+
+pub unsafe fn xcb_icccm_get_wm_class_reply_wipe(prop: *mut xcb_icccm_get_wm_class_reply_t) {
+    if !(*prop)._reply.is_null() {
+        drop(<x::GetPropertyReply as xcb::Reply>::from_raw(
+            (*prop)._reply as *const u8,
+        ));
+    }
+
+    (*prop).instance_name = ptr::null_mut();
+    (*prop).class_name = ptr::null_mut();
+    (*prop)._reply = ptr::null_mut();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads property words from little-endian bytes, as received by a little-endian client.
+    fn words(bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect()
+    }
+
+    #[rustfmt::skip]
+    const SIZE_HINTS: [u8; 72] = [
+        0x30, 0x03, 0x00, 0x00, // PMinSize | PMaxSize | PBaseSize | PWinGravity
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0xc8, 0x00, 0x00, 0x00, // min_width = 200
+        0x64, 0x00, 0x00, 0x00, // min_height = 100
+        0x80, 0x07, 0x00, 0x00, // max_width = 1920
+        0x38, 0x04, 0x00, 0x00, // max_height = 1080
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x0a, 0x00, 0x00, 0x00, // base_width = 10
+        0x14, 0x00, 0x00, 0x00, // base_height = 20
+        0x0a, 0x00, 0x00, 0x00, // win_gravity = Static
+    ];
+
+    #[rustfmt::skip]
+    const WM_HINTS: [u8; 36] = [
+        0x43, 0x00, 0x00, 0x00, // InputHint | StateHint | WindowGroupHint
+        0x01, 0x00, 0x00, 0x00, // input = True
+        0x03, 0x00, 0x00, 0x00, // initial_state = IconicState
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x60, 0x00, // window_group = 0x600001
+    ];
+
+    #[test]
+    fn size_hints_round_trip() {
+        let hints = size_hints_from_values(&words(&SIZE_HINTS)).unwrap();
+
+        assert_eq!(
+            hints.flags,
+            XCB_ICCCM_SIZE_HINT_P_MIN_SIZE
+                | XCB_ICCCM_SIZE_HINT_P_MAX_SIZE
+                | XCB_ICCCM_SIZE_HINT_BASE_SIZE
+                | XCB_ICCCM_SIZE_HINT_P_WIN_GRAVITY
+        );
+        assert_eq!((hints.min_width, hints.min_height), (200, 100));
+        assert_eq!((hints.max_width, hints.max_height), (1920, 1080));
+        assert_eq!((hints.base_width, hints.base_height), (10, 20));
+        assert_eq!(hints.win_gravity, 10);
+
+        assert_eq!(size_hints_to_values(&hints).to_vec(), words(&SIZE_HINTS));
+    }
+
+    #[test]
+    fn size_hints_pre_icccm() {
+        let hints = size_hints_from_values(&words(&SIZE_HINTS[..60])).unwrap();
+
+        assert_eq!(
+            hints.flags,
+            XCB_ICCCM_SIZE_HINT_P_MIN_SIZE | XCB_ICCCM_SIZE_HINT_P_MAX_SIZE
+        );
+        assert_eq!((hints.base_width, hints.base_height), (0, 0));
+        assert_eq!(hints.win_gravity, 0);
+
+        assert!(size_hints_from_values(&words(&SIZE_HINTS[..56])).is_none());
+    }
+
+    #[test]
+    fn size_hints_setters() {
+        let mut hints = xcb_size_hints_t::default();
+
+        unsafe {
+            xcb_icccm_size_hints_set_position(&mut hints, 1, 5, 6);
+            xcb_icccm_size_hints_set_position(&mut hints, 0, 7, 8);
+            xcb_icccm_size_hints_set_resize_inc(&mut hints, 8, 16);
+        }
+
+        assert_eq!(
+            hints.flags,
+            XCB_ICCCM_SIZE_HINT_P_POSITION | XCB_ICCCM_SIZE_HINT_P_RESIZE_INC
+        );
+        assert_eq!((hints.x, hints.y), (7, 8));
+        assert_eq!((hints.width_inc, hints.height_inc), (8, 16));
+    }
+
+    #[test]
+    fn wm_hints_round_trip() {
+        let hints = wm_hints_from_values(&words(&WM_HINTS)).unwrap();
+
+        assert_eq!(
+            hints.flags as u32,
+            XCB_ICCCM_WM_HINT_INPUT | XCB_ICCCM_WM_HINT_STATE | XCB_ICCCM_WM_HINT_WINDOW_GROUP
+        );
+        assert_eq!(hints.input, 1);
+        assert_eq!(hints.initial_state as u32, XCB_ICCCM_WM_STATE_ICONIC);
+        assert_eq!(hints.window_group, 0x600001);
+
+        assert_eq!(wm_hints_to_values(&hints).to_vec(), words(&WM_HINTS));
+    }
+
+    #[test]
+    fn wm_hints_pre_icccm() {
+        let hints = wm_hints_from_values(&words(&WM_HINTS[..32])).unwrap();
+
+        assert_eq!(hints.window_group, 0);
+
+        assert!(wm_hints_from_values(&words(&WM_HINTS[..28])).is_none());
+    }
+
+    #[test]
+    fn wm_hints_setters() {
+        let mut hints = xcb_icccm_wm_hints_t::default();
+
+        unsafe {
+            xcb_icccm_wm_hints_set_input(&mut hints, 1);
+            xcb_icccm_wm_hints_set_iconic(&mut hints);
+            xcb_icccm_wm_hints_set_window_group(&mut hints, 0x600001);
+            xcb_icccm_wm_hints_set_urgency(&mut hints);
+
+            assert_ne!(xcb_icccm_wm_hints_get_urgency(&hints), 0);
+
+            xcb_icccm_wm_hints_set_none(&mut hints);
+        }
+
+        assert_eq!(
+            hints.flags as u32,
+            XCB_ICCCM_WM_HINT_INPUT | XCB_ICCCM_WM_HINT_WINDOW_GROUP | XCB_ICCCM_WM_HINT_X_URGENCY
+        );
+    }
+
+    #[test]
+    fn wm_class() {
+        const WM_CLASS: &[u8] = b"navigator\0Firefox\0";
+
+        assert_eq!(
+            wm_class_from_bytes(WM_CLASS),
+            (&b"navigator"[..], &b"Firefox"[..])
+        );
+        assert_eq!(wm_class_to_bytes(b"navigator", b"Firefox"), WM_CLASS);
+
+        assert_eq!(wm_class_from_bytes(b"xterm"), (&b"xterm"[..], &b""[..]));
+        assert_eq!(
+            wm_class_from_bytes(b"xterm\0XTerm"),
+            (&b"xterm"[..], &b"XTerm"[..])
+        );
+        assert_eq!(wm_class_from_bytes(b""), (&b""[..], &b""[..]));
+    }
+}
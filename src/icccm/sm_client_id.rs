@@ -0,0 +1,92 @@
+use xcb::x;
+
+use super::{
+    change_property, compound_text, get_property, Atoms, EncodeError, GetTextPropertyCookie,
+    GetTextPropertyCookieUnchecked, GetTextPropertyReply, GetWmClientLeader,
+};
+
+/// Sets `SM_CLIENT_ID`, which belongs on the client leader.
+pub struct SetSmClientId {
+    window: x::Window,
+    sm_client_id: x::Atom,
+    client_id: Vec<u8>,
+}
+
+impl SetSmClientId {
+    /// Encodes `client_id` as a `STRING`, failing if it is not ISO 8859-1 or holds a NUL character.
+    pub fn new(atoms: &Atoms, window: x::Window, client_id: &str) -> Result<Self, EncodeError> {
+        Ok(Self {
+            window,
+            sm_client_id: atoms.sm_client_id,
+            client_id: compound_text::to_latin1(client_id)?.into_owned(),
+        })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetSmClientId {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.sm_client_id,
+            x::ATOM_STRING,
+            &self.client_id,
+        )
+    }
+}
+
+void_request!(SetSmClientId);
+
+pub struct GetSmClientId {
+    pub window: x::Window,
+    /// The `SM_CLIENT_ID` atom.
+    pub sm_client_id: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetSmClientId {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.sm_client_id,
+            x::ATOM_STRING,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetSmClientId => GetTextPropertyReply, GetTextPropertyCookie, GetTextPropertyCookieUnchecked);
+
+/// The session management client id of `window`, read from its client leader, or from the window
+/// itself if it names no leader.
+///
+/// Returns `None` if the client does not take part in session management.
+pub fn sm_client_id(
+    connection: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+) -> xcb::Result<Option<String>> {
+    let cookie = connection.send_request(&GetWmClientLeader {
+        window,
+        client_leader: atoms.wm_client_leader,
+    });
+    let leader = connection
+        .wait_for_reply(cookie)?
+        .leader()
+        .unwrap_or(window);
+
+    let cookie = connection.send_request(&GetSmClientId {
+        window: leader,
+        sm_client_id: atoms.sm_client_id,
+    });
+    let reply = connection.wait_for_reply(cookie)?;
+    let client_id = reply.name();
+
+    Ok(if client_id.is_empty() {
+        None
+    } else {
+        Some(client_id.into_owned())
+    })
+}
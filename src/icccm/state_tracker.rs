@@ -0,0 +1,336 @@
+use xcb::{x, Xid};
+
+use super::{
+    Atoms, GetWmHints, SetWmState, WmState, WM_STATE_ICONIC, WM_STATE_NORMAL, WM_STATE_WITHDRAWN,
+};
+
+/// What the window manager should do following a change of state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateAction {
+    /// The window became normal, its frame should be mapped.
+    MapFrame,
+    /// The window became iconic, its frame should be unmapped.
+    Iconify,
+    /// The window was withdrawn or destroyed, it should be unmanaged.
+    Forget,
+}
+
+/// A change of state decided from an event, before it is carried out on the connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Transition {
+    /// A withdrawn window asked to be mapped, in the initial state of its `WM_HINTS`.
+    MapInitial,
+    /// The window moves to the state.
+    To(WmState, StateAction),
+    /// The window was destroyed, its `WM_STATE` went with it.
+    Destroyed,
+}
+
+/// Tracks the state of a client window through the transitions of ICCCM 4.1.4, and keeps its
+/// `WM_STATE` up to date.
+///
+/// Events should be fed from a single source, usually `SubstructureRedirect` on the root window
+/// along `SubstructureNotify` on the frame, so that each unmap is seen once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateTracker {
+    window: x::Window,
+    wm_state: x::Atom,
+    wm_change_state: x::Atom,
+    state: WmState,
+    ignored_unmaps: u32,
+}
+
+impl StateTracker {
+    /// Tracks `window`, which starts withdrawn.
+    pub fn new(atoms: &Atoms, window: x::Window) -> Self {
+        Self {
+            window,
+            wm_state: atoms.wm_state,
+            wm_change_state: atoms.wm_change_state,
+            state: WM_STATE_WITHDRAWN,
+            ignored_unmaps: 0,
+        }
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    pub fn state(&self) -> WmState {
+        self.state
+    }
+
+    /// Tells the tracker that the window manager unmaps the window itself, reparenting a mapped
+    /// window included, so that the resulting `UnmapNotify` is not taken for a withdrawal.
+    pub fn expect_unmap(&mut self) {
+        self.ignored_unmaps += 1;
+    }
+
+    /// Moves the window to `state` on behalf of the window manager, for instance to restore an
+    /// iconic window from a task bar.
+    pub fn set_state(&mut self, connection: &xcb::Connection, state: WmState) -> xcb::VoidCookie {
+        self.state = state;
+
+        connection.send_request(&SetWmState {
+            window: self.window,
+            wm_state: self.wm_state,
+            state,
+            icon: x::Window::none(),
+        })
+    }
+
+    /// Handles an event, returning what the window manager should do if the state changed.
+    pub fn handle_event(
+        &mut self,
+        connection: &xcb::Connection,
+        event: &xcb::Event,
+    ) -> xcb::Result<Option<StateAction>> {
+        let (state, action) = match self.transition(event) {
+            None => return Ok(None),
+            Some(Transition::MapInitial) => {
+                let cookie = connection.send_request(&GetWmHints {
+                    window: self.window,
+                });
+
+                let iconic = connection
+                    .wait_for_reply(cookie)?
+                    .hints()
+                    .is_some_and(|hints| hints.is_iconic());
+
+                if iconic {
+                    (WM_STATE_ICONIC, StateAction::Iconify)
+                } else {
+                    (WM_STATE_NORMAL, StateAction::MapFrame)
+                }
+            }
+            Some(Transition::To(state, action)) => (state, action),
+            Some(Transition::Destroyed) => {
+                self.state = WM_STATE_WITHDRAWN;
+                return Ok(Some(StateAction::Forget));
+            }
+        };
+
+        self.set_state(connection, state);
+
+        Ok(Some(action))
+    }
+
+    /// Decides the transition caused by an event, keeping track of the unmaps to ignore.
+    fn transition(&mut self, event: &xcb::Event) -> Option<Transition> {
+        match event {
+            // Withdrawn windows are mapped in their initial state, iconic ones are restored.
+            xcb::Event::X(x::Event::MapRequest(ev)) if ev.window() == self.window => {
+                if self.state == WM_STATE_WITHDRAWN {
+                    Some(Transition::MapInitial)
+                } else {
+                    Some(Transition::To(WM_STATE_NORMAL, StateAction::MapFrame))
+                }
+            }
+
+            // Clients withdraw iconic windows, which are already unmapped, with a synthetic
+            // UnmapNotify sent to the root window.
+            xcb::Event::X(x::Event::UnmapNotify(ev))
+                if ev.window() == self.window && ev.response_type() & 0x80 != 0 =>
+            {
+                self.withdraw()
+            }
+
+            xcb::Event::X(x::Event::UnmapNotify(ev)) if ev.window() == self.window => {
+                if self.ignored_unmaps > 0 {
+                    self.ignored_unmaps -= 1;
+                    return None;
+                }
+
+                self.withdraw()
+            }
+
+            xcb::Event::X(x::Event::DestroyNotify(ev)) if ev.window() == self.window => {
+                self.ignored_unmaps = 0;
+
+                Some(Transition::Destroyed)
+            }
+
+            xcb::Event::X(x::Event::ClientMessage(ev))
+                if ev.window() == self.window && ev.r#type() == self.wm_change_state =>
+            {
+                match ev.data() {
+                    x::ClientMessageData::Data32(data)
+                        if data[0] == WM_STATE_ICONIC && self.state == WM_STATE_NORMAL =>
+                    {
+                        Some(Transition::To(WM_STATE_ICONIC, StateAction::Iconify))
+                    }
+                    _ => None,
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    fn withdraw(&mut self) -> Option<Transition> {
+        if self.state == WM_STATE_WITHDRAWN {
+            return None;
+        }
+
+        self.ignored_unmaps = 0;
+
+        Some(Transition::To(WM_STATE_WITHDRAWN, StateAction::Forget))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xcb::{Raw, XidNew};
+
+    use super::*;
+
+    fn atoms() -> Atoms {
+        let atom = |id| x::Atom::new(id);
+
+        Atoms {
+            wm_state: atom(300),
+            wm_change_state: atom(301),
+            wm_protocols: atom(302),
+            wm_delete_window: atom(303),
+            wm_take_focus: atom(304),
+            wm_colormap_windows: atom(305),
+            wm_client_leader: atom(306),
+            wm_window_role: atom(307),
+            wm_locale_name: atom(308),
+            sm_client_id: atom(309),
+            utf8_string: atom(310),
+            compound_text: atom(311),
+        }
+    }
+
+    fn window() -> x::Window {
+        x::Window::new(0x40_0001)
+    }
+
+    fn root() -> x::Window {
+        x::Window::new(0x100)
+    }
+
+    fn tracker(state: WmState) -> StateTracker {
+        let mut tracker = StateTracker::new(&atoms(), window());
+        tracker.state = state;
+        tracker
+    }
+
+    fn map_request() -> xcb::Event {
+        xcb::Event::X(x::Event::MapRequest(x::MapRequestEvent::new(
+            root(),
+            window(),
+        )))
+    }
+
+    fn unmap_notify(synthetic: bool) -> xcb::Event {
+        let event = x::UnmapNotifyEvent::new(root(), window(), false);
+
+        let event = if synthetic {
+            unsafe {
+                let raw = event.into_raw();
+                (*raw).response_type |= 0x80;
+                x::UnmapNotifyEvent::from_raw(raw)
+            }
+        } else {
+            event
+        };
+
+        xcb::Event::X(x::Event::UnmapNotify(event))
+    }
+
+    fn change_state(state: WmState) -> xcb::Event {
+        xcb::Event::X(x::Event::ClientMessage(x::ClientMessageEvent::new(
+            window(),
+            atoms().wm_change_state,
+            x::ClientMessageData::Data32([state, 0, 0, 0, 0]),
+        )))
+    }
+
+    #[test]
+    fn map_request_transitions() {
+        assert_eq!(
+            tracker(WM_STATE_WITHDRAWN).transition(&map_request()),
+            Some(Transition::MapInitial)
+        );
+        assert_eq!(
+            tracker(WM_STATE_ICONIC).transition(&map_request()),
+            Some(Transition::To(WM_STATE_NORMAL, StateAction::MapFrame))
+        );
+    }
+
+    #[test]
+    fn expected_unmaps_are_ignored() {
+        let mut tracker = tracker(WM_STATE_NORMAL);
+
+        tracker.expect_unmap();
+        tracker.expect_unmap();
+
+        assert_eq!(tracker.transition(&unmap_notify(false)), None);
+        assert_eq!(tracker.transition(&unmap_notify(false)), None);
+        assert_eq!(
+            tracker.transition(&unmap_notify(false)),
+            Some(Transition::To(WM_STATE_WITHDRAWN, StateAction::Forget))
+        );
+    }
+
+    #[test]
+    fn synthetic_unmaps_withdraw() {
+        let mut tracker = tracker(WM_STATE_ICONIC);
+
+        // The window manager reparented the window, but the client withdraws it from iconic.
+        tracker.expect_unmap();
+
+        assert_eq!(
+            tracker.transition(&unmap_notify(true)),
+            Some(Transition::To(WM_STATE_WITHDRAWN, StateAction::Forget))
+        );
+        assert_eq!(tracker.ignored_unmaps, 0);
+
+        tracker.state = WM_STATE_WITHDRAWN;
+        assert_eq!(tracker.transition(&unmap_notify(true)), None);
+    }
+
+    #[test]
+    fn iconify_requests() {
+        assert_eq!(
+            tracker(WM_STATE_NORMAL).transition(&change_state(WM_STATE_ICONIC)),
+            Some(Transition::To(WM_STATE_ICONIC, StateAction::Iconify))
+        );
+        assert_eq!(
+            tracker(WM_STATE_WITHDRAWN).transition(&change_state(WM_STATE_ICONIC)),
+            None
+        );
+        assert_eq!(
+            tracker(WM_STATE_ICONIC).transition(&change_state(WM_STATE_ICONIC)),
+            None
+        );
+        assert_eq!(
+            tracker(WM_STATE_NORMAL).transition(&change_state(WM_STATE_NORMAL)),
+            None
+        );
+    }
+
+    #[test]
+    fn destroyed_windows_are_forgotten() {
+        let mut tracker = tracker(WM_STATE_NORMAL);
+        tracker.expect_unmap();
+
+        let destroy = xcb::Event::X(x::Event::DestroyNotify(x::DestroyNotifyEvent::new(
+            root(),
+            window(),
+        )));
+
+        assert_eq!(tracker.transition(&destroy), Some(Transition::Destroyed));
+        assert_eq!(tracker.ignored_unmaps, 0);
+    }
+
+    #[test]
+    fn other_windows_are_ignored() {
+        let mut tracker = StateTracker::new(&atoms(), x::Window::new(0x40_0002));
+
+        assert_eq!(tracker.transition(&map_request()), None);
+        assert_eq!(tracker.transition(&unmap_notify(false)), None);
+    }
+}
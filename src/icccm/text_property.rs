@@ -0,0 +1,237 @@
+use std::{borrow::Cow, error, fmt, str};
+
+use xcb::x;
+
+use super::{change_property, compound_text, get_property, Atoms};
+
+/// The encodings of text properties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextEncoding {
+    /// `STRING`, ISO 8859-1.
+    String,
+    /// `COMPOUND_TEXT`, the ISO 2022 based X Compound Text Encoding.
+    CompoundText,
+    /// `UTF8_STRING`.
+    Utf8String,
+}
+
+impl TextEncoding {
+    /// The encoding of the `encoding` atom, or `None` if it is not a text encoding.
+    pub fn from_atom(atoms: &Atoms, encoding: x::Atom) -> Option<Self> {
+        if encoding == x::ATOM_STRING {
+            Some(TextEncoding::String)
+        } else if encoding == x::ATOM_NONE {
+            None
+        } else if encoding == atoms.utf8_string {
+            Some(TextEncoding::Utf8String)
+        } else if encoding == atoms.compound_text {
+            Some(TextEncoding::CompoundText)
+        } else {
+            None
+        }
+    }
+
+    pub fn atom(self, atoms: &Atoms) -> x::Atom {
+        match self {
+            TextEncoding::String => x::ATOM_STRING,
+            TextEncoding::CompoundText => atoms.compound_text,
+            TextEncoding::Utf8String => atoms.utf8_string,
+        }
+    }
+
+    /// Encodes `text`, borrowing it when it needs no conversion.
+    pub fn encode(self, text: &str) -> Result<Cow<'_, [u8]>, EncodeError> {
+        match self {
+            TextEncoding::String => compound_text::to_latin1(text),
+            TextEncoding::CompoundText => compound_text::encode(text),
+            TextEncoding::Utf8String if text.contains('\0') => Err(EncodeError::Nul),
+            TextEncoding::Utf8String => Ok(Cow::Borrowed(text.as_bytes())),
+        }
+    }
+
+    /// Decodes `data`, borrowing it when it is already valid UTF-8.
+    pub fn decode(self, data: &[u8]) -> Result<Cow<'_, str>, DecodeError> {
+        match self {
+            TextEncoding::String => Ok(compound_text::latin1(data)),
+            TextEncoding::CompoundText => compound_text::decode(data),
+            TextEncoding::Utf8String => str::from_utf8(data)
+                .map(Cow::Borrowed)
+                .map_err(DecodeError::InvalidUtf8),
+        }
+    }
+}
+
+/// The reasons a text property cannot be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The encoding atom is not one of `STRING`, `COMPOUND_TEXT` or `UTF8_STRING`.
+    UnsupportedEncoding(x::Atom),
+    /// The property is not made of bytes.
+    InvalidFormat(u8),
+    InvalidUtf8(str::Utf8Error),
+    /// The compound text is malformed at the given offset.
+    InvalidCompoundText(usize),
+    /// The compound text uses a character set which cannot be decoded, given by its escape
+    /// sequence or the name of its extended segment.
+    UnsupportedCharset(Vec<u8>),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported text encoding {:?}", encoding)
+            }
+            DecodeError::InvalidFormat(format) => {
+                write!(f, "text property has format {} instead of 8", format)
+            }
+            DecodeError::InvalidUtf8(err) => write!(f, "invalid UTF-8 text: {}", err),
+            DecodeError::InvalidCompoundText(offset) => {
+                write!(f, "invalid compound text at offset {}", offset)
+            }
+            DecodeError::UnsupportedCharset(charset) => write!(
+                f,
+                "unsupported compound text charset {:?}",
+                String::from_utf8_lossy(charset)
+            ),
+        }
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            DecodeError::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The reasons text cannot be encoded in a property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The character is not part of the encoding.
+    UnrepresentableChar(char),
+    /// The text contains a NUL character, which separates the strings of a property.
+    Nul,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::UnrepresentableChar(c) => {
+                write!(f, "character {:?} cannot be encoded", c)
+            }
+            EncodeError::Nul => write!(f, "text contains a NUL character"),
+        }
+    }
+}
+
+impl error::Error for EncodeError {}
+
+pub struct SetTextProperty {
+    window: x::Window,
+    property: x::Atom,
+    encoding: x::Atom,
+    name: Vec<u8>,
+}
+
+impl SetTextProperty {
+    /// Encodes `name` in `encoding`, failing if it cannot represent it.
+    pub fn new(
+        atoms: &Atoms,
+        window: x::Window,
+        property: x::Atom,
+        encoding: TextEncoding,
+        name: &str,
+    ) -> Result<Self, EncodeError> {
+        Ok(Self {
+            window,
+            property,
+            encoding: encoding.atom(atoms),
+            name: encoding.encode(name)?.into_owned(),
+        })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetTextProperty {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.property,
+            self.encoding,
+            &self.name,
+        )
+    }
+}
+
+void_request!(SetTextProperty);
+
+property_reply!(
+    GetTextPropertyReply,
+    GetTextPropertyCookie,
+    GetTextPropertyCookieUnchecked
+);
+
+impl GetTextPropertyReply {
+    pub fn encoding(&self) -> x::Atom {
+        self.0.r#type()
+    }
+
+    pub fn format(&self) -> u8 {
+        self.0.format()
+    }
+
+    /// The text, empty if the property is not set or not made of bytes.
+    ///
+    /// `STRING` is decoded as ISO 8859-1 and anything else as lossy UTF-8, [`Self::text`] decodes
+    /// every encoding properly.
+    pub fn name(&self) -> Cow<'_, str> {
+        if self.0.format() != 8 {
+            return Cow::Borrowed("");
+        }
+
+        if self.0.r#type() == x::ATOM_STRING {
+            return compound_text::latin1(self.0.value());
+        }
+
+        String::from_utf8_lossy(self.0.value::<u8>())
+    }
+
+    /// The text decoded according to its encoding, empty if the property is not set.
+    pub fn text(&self, atoms: &Atoms) -> Result<Cow<'_, str>, DecodeError> {
+        if self.0.r#type() == x::ATOM_NONE {
+            return Ok(Cow::Borrowed(""));
+        }
+
+        if self.0.format() != 8 {
+            return Err(DecodeError::InvalidFormat(self.0.format()));
+        }
+
+        TextEncoding::from_atom(atoms, self.0.r#type())
+            .ok_or(DecodeError::UnsupportedEncoding(self.0.r#type()))?
+            .decode(self.0.value())
+    }
+}
+
+pub struct GetTextProperty {
+    pub window: x::Window,
+    pub property: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetTextProperty {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.property,
+            x::ATOM_ANY,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetTextProperty => GetTextPropertyReply, GetTextPropertyCookie, GetTextPropertyCookieUnchecked);
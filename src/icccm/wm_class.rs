@@ -0,0 +1,224 @@
+use std::{ffi::CStr, mem};
+
+use xcb::{x, Xid};
+
+use super::{compound_text, ffi, ffi_connection, EncodeError};
+
+/// Sets `WM_CLASS`, the instance and class names used to look up the resources of the client.
+pub struct SetWmClass {
+    window: x::Window,
+    value: Vec<u8>,
+}
+
+impl SetWmClass {
+    /// Encodes the names as the `STRING`s of the property, failing if a name is not ISO 8859-1 or
+    /// holds a NUL character.
+    pub fn new(window: x::Window, instance: &str, class: &str) -> Result<Self, EncodeError> {
+        let mut value = Vec::new();
+
+        for name in [instance, class] {
+            value.extend_from_slice(&compound_text::to_latin1(name)?);
+            value.push(0);
+        }
+
+        Ok(Self { window, value })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetWmClass {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        let value = &self.value;
+
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_set_wm_class_checked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    value.len() as u32,
+                    value.as_ptr() as *const _,
+                )
+            } else {
+                ffi::xcb_icccm_set_wm_class(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    value.len() as u32,
+                    value.as_ptr() as *const _,
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+void_request!(SetWmClass);
+
+//TODO: Expose inner reply
+pub struct GetWmClassReply {
+    reply: x::GetPropertyReply,
+    names: Option<(String, String)>,
+}
+
+impl xcb::Reply for GetWmClassReply {
+    unsafe fn from_raw(raw: *const u8) -> Self {
+        let mut class = mem::zeroed::<ffi::xcb_icccm_get_wm_class_reply_t>();
+
+        // The names point into the reply, which is owned and freed by `x::GetPropertyReply` rather
+        // than by `xcb_icccm_get_wm_class_reply_wipe`.
+        let names = if ffi::xcb_icccm_get_wm_class_from_reply(&mut class, raw as *mut _) != 0 {
+            Some((
+                compound_text::latin1(CStr::from_ptr(class.instance_name).to_bytes()).into_owned(),
+                compound_text::latin1(CStr::from_ptr(class.class_name).to_bytes()).into_owned(),
+            ))
+        } else {
+            None
+        };
+
+        Self {
+            reply: <x::GetPropertyReply as xcb::Reply>::from_raw(raw),
+            names,
+        }
+    }
+
+    unsafe fn into_raw(self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::into_raw(self.reply)
+    }
+
+    unsafe fn as_raw(&self) -> *const u8 {
+        <x::GetPropertyReply as xcb::Reply>::as_raw(&self.reply)
+    }
+}
+
+impl GetWmClassReply {
+    pub fn instance(&self) -> Option<&str> {
+        self.names.as_ref().map(|(instance, _)| instance.as_str())
+    }
+
+    pub fn class(&self) -> Option<&str> {
+        self.names.as_ref().map(|(_, class)| class.as_str())
+    }
+}
+
+//TODO: Expose inner cookie
+pub struct GetWmClassCookie(x::GetPropertyCookie);
+
+//TODO: Expose inner cookie
+pub struct GetWmClassCookieUnchecked(x::GetPropertyCookieUnchecked);
+
+impl xcb::Cookie for GetWmClassCookie {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookie::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieChecked for GetWmClassCookie {}
+
+unsafe impl xcb::CookieWithReplyChecked for GetWmClassCookie {
+    type Reply = GetWmClassReply;
+}
+
+impl xcb::Cookie for GetWmClassCookieUnchecked {
+    unsafe fn from_sequence(seq: u64) -> Self {
+        Self(x::GetPropertyCookieUnchecked::from_sequence(seq))
+    }
+
+    fn sequence(&self) -> u64 {
+        self.0.sequence()
+    }
+}
+
+unsafe impl xcb::CookieWithReplyUnchecked for GetWmClassCookieUnchecked {
+    type Reply = GetWmClassReply;
+}
+
+pub struct GetWmClass {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmClass {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_get_wm_class(ffi_connection(connection), self.window.resource_id())
+            } else {
+                ffi::xcb_icccm_get_wm_class_unchecked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+property_request!(GetWmClass => GetWmClassReply, GetWmClassCookie, GetWmClassCookieUnchecked);
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use libc;
+    use xcb::XidNew;
+
+    use super::*;
+
+    /// Builds a `GetProperty` reply as received from the server, with format 8 `value`.
+    fn property(value: &[u8]) -> GetWmClassReply {
+        let mut wire = vec![0; 32];
+        wire[0] = 1;
+        wire[1] = 8;
+        wire[4..8].copy_from_slice(&(value.len().div_ceil(4) as u32).to_ne_bytes());
+        wire[8..12].copy_from_slice(&x::ATOM_STRING.resource_id().to_ne_bytes());
+        wire[16..20].copy_from_slice(&(value.len() as u32).to_ne_bytes());
+        wire.extend_from_slice(value);
+        wire.resize(32 + value.len().div_ceil(4) * 4, 0);
+
+        unsafe {
+            let raw = libc::malloc(wire.len()) as *mut u8;
+            ptr::copy_nonoverlapping(wire.as_ptr(), raw, wire.len());
+
+            <GetWmClassReply as xcb::Reply>::from_raw(raw)
+        }
+    }
+
+    #[test]
+    fn encode_latin1() {
+        let request = SetWmClass::new(x::Window::new(1), "caf\u{e9}", "Caf\u{e9}").unwrap();
+
+        assert_eq!(request.value, b"caf\xe9\0Caf\xe9\0");
+    }
+
+    #[test]
+    fn encode_errors() {
+        let window = x::Window::new(1);
+
+        assert_eq!(
+            SetWmClass::new(window, "\u{20ac}", "Euro").err(),
+            Some(EncodeError::UnrepresentableChar('\u{20ac}'))
+        );
+        assert_eq!(
+            SetWmClass::new(window, "xterm", "X\0Term").err(),
+            Some(EncodeError::Nul)
+        );
+    }
+
+    #[test]
+    fn decode_latin1() {
+        let reply = property(b"caf\xe9\0Caf\xe9\0");
+
+        assert_eq!(reply.instance(), Some("caf\u{e9}"));
+        assert_eq!(reply.class(), Some("Caf\u{e9}"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let request = SetWmClass::new(x::Window::new(1), "navigator", "Firefox").unwrap();
+        let reply = property(&request.value);
+
+        assert_eq!(reply.instance(), Some("navigator"));
+        assert_eq!(reply.class(), Some("Firefox"));
+    }
+}
@@ -0,0 +1,67 @@
+use xcb::{x, Xid};
+
+use super::{change_property, get_property};
+
+pub struct SetWmClientLeader {
+    pub window: x::Window,
+    /// The `WM_CLIENT_LEADER` atom.
+    pub client_leader: x::Atom,
+    pub leader: x::Window,
+}
+
+unsafe impl xcb::RawRequest for SetWmClientLeader {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.client_leader,
+            x::ATOM_WINDOW,
+            &[self.leader],
+        )
+    }
+}
+
+void_request!(SetWmClientLeader);
+
+property_reply!(
+    GetWmClientLeaderReply,
+    GetWmClientLeaderCookie,
+    GetWmClientLeaderCookieUnchecked
+);
+
+impl GetWmClientLeaderReply {
+    /// The client leader, or `None` if the window does not name one.
+    pub fn leader(&self) -> Option<x::Window> {
+        if self.0.r#type() != x::ATOM_WINDOW || self.0.format() != 32 {
+            return None;
+        }
+
+        self.0
+            .value::<x::Window>()
+            .first()
+            .copied()
+            .filter(|window| !window.is_none())
+    }
+}
+
+pub struct GetWmClientLeader {
+    pub window: x::Window,
+    /// The `WM_CLIENT_LEADER` atom.
+    pub client_leader: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmClientLeader {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.client_leader,
+            x::ATOM_WINDOW,
+            1,
+        )
+    }
+}
+
+property_request!(GetWmClientLeader => GetWmClientLeaderReply, GetWmClientLeaderCookie, GetWmClientLeaderCookieUnchecked);
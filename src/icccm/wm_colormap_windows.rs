@@ -0,0 +1,62 @@
+use xcb::x;
+
+use super::{change_property, get_property};
+
+pub struct SetWmColormapWindows<'a> {
+    pub window: x::Window,
+    /// The `WM_COLORMAP_WINDOWS` atom.
+    pub colormap_windows: x::Atom,
+    pub windows: &'a [x::Window],
+}
+
+unsafe impl<'a> xcb::RawRequest for SetWmColormapWindows<'a> {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.colormap_windows,
+            x::ATOM_WINDOW,
+            self.windows,
+        )
+    }
+}
+
+void_request!(SetWmColormapWindows<'a>);
+
+property_reply!(
+    GetWmColormapWindowsReply,
+    GetWmColormapWindowsCookie,
+    GetWmColormapWindowsCookieUnchecked
+);
+
+impl GetWmColormapWindowsReply {
+    pub fn windows(&self) -> &[x::Window] {
+        if self.0.r#type() != x::ATOM_WINDOW || self.0.format() != 32 {
+            return &[];
+        }
+
+        self.0.value()
+    }
+}
+
+pub struct GetWmColormapWindows {
+    pub window: x::Window,
+    /// The `WM_COLORMAP_WINDOWS` atom.
+    pub colormap_windows: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmColormapWindows {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.colormap_windows,
+            x::ATOM_WINDOW,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmColormapWindows => GetWmColormapWindowsReply, GetWmColormapWindowsCookie, GetWmColormapWindowsCookieUnchecked);
@@ -0,0 +1,116 @@
+use xcb::x;
+
+use super::{change_property, compound_text, get_property, EncodeError};
+
+/// Sets `WM_COMMAND`, the argv restarting the client, which belongs on the client leader.
+pub struct SetWmCommand {
+    window: x::Window,
+    argv: Vec<u8>,
+}
+
+impl SetWmCommand {
+    /// Encodes `argv` as the `STRING`s of the property, failing if an argument is not ISO 8859-1
+    /// or holds a NUL character.
+    pub fn new(window: x::Window, argv: &[&str]) -> Result<Self, EncodeError> {
+        let mut data = Vec::new();
+
+        for arg in argv {
+            data.extend_from_slice(&compound_text::to_latin1(arg)?);
+            data.push(0);
+        }
+
+        Ok(Self { window, argv: data })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetWmCommand {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_COMMAND,
+            x::ATOM_STRING,
+            &self.argv,
+        )
+    }
+}
+
+void_request!(SetWmCommand);
+
+property_reply!(
+    GetWmCommandReply,
+    GetWmCommandCookie,
+    GetWmCommandCookieUnchecked
+);
+
+impl GetWmCommandReply {
+    /// The arguments, each terminated by a NUL byte in the property, empty if it is not set.
+    pub fn argv(&self) -> Vec<String> {
+        if self.0.format() != 8 {
+            return Vec::new();
+        }
+
+        argv(self.0.value())
+    }
+}
+
+fn argv(data: &[u8]) -> Vec<String> {
+    let data = match data.split_last() {
+        None => return Vec::new(),
+        // The last argument is usually terminated, but need not be. A single NUL byte is then a
+        // single empty argument.
+        Some((&0, rest)) => rest,
+        Some(_) => data,
+    };
+
+    data.split(|&byte| byte == 0)
+        .map(|arg| compound_text::latin1(arg).into_owned())
+        .collect()
+}
+
+pub struct GetWmCommand {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmCommand {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_COMMAND,
+            x::ATOM_STRING,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmCommand => GetWmCommandReply, GetWmCommandCookie, GetWmCommandCookieUnchecked);
+
+#[cfg(test)]
+mod tests {
+    use xcb::XidNew;
+
+    use super::*;
+
+    fn round_trip(args: &[&str]) -> Vec<String> {
+        argv(&SetWmCommand::new(x::Window::new(1), args).unwrap().argv)
+    }
+
+    #[test]
+    fn argv_round_trip() {
+        assert_eq!(round_trip(&["xterm", "-e", "vi"]), ["xterm", "-e", "vi"]);
+        assert_eq!(round_trip(&["xterm", "", "café"]), ["xterm", "", "café"]);
+        assert_eq!(round_trip(&["xterm", ""]), ["xterm", ""]);
+        assert_eq!(round_trip(&[""]), [""]);
+        assert_eq!(round_trip(&["", ""]), ["", ""]);
+        assert!(round_trip(&[]).is_empty());
+    }
+
+    #[test]
+    fn unterminated_argv() {
+        assert_eq!(argv(b"xterm\0-e\0vi"), ["xterm", "-e", "vi"]);
+        assert_eq!(argv(b"xterm"), ["xterm"]);
+    }
+}
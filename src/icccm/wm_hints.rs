@@ -0,0 +1,247 @@
+use std::mem;
+
+use xcb::{x, Xid, XidNew};
+
+use super::{ffi, ffi_connection, WmState};
+
+#[derive(Clone, Copy, Debug)]
+pub struct WmHints(ffi::xcb_icccm_wm_hints_t);
+#[derive(Clone, Copy, Debug)]
+pub struct WmHintsBuilder(ffi::xcb_icccm_wm_hints_t);
+
+impl WmHints {
+    pub fn empty() -> WmHintsBuilder {
+        unsafe { WmHintsBuilder(mem::zeroed()) }
+    }
+
+    fn has(&self, flag: ffi::xcb_icccm_wm_t) -> bool {
+        self.0.flags as u32 & flag != 0
+    }
+
+    fn initial_state(&self) -> Option<WmState> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_STATE) {
+            Some(self.0.initial_state as WmState)
+        } else {
+            None
+        }
+    }
+
+    pub fn input(&self) -> Option<bool> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_INPUT) {
+            Some(self.0.input != 0)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_iconic(&self) -> bool {
+        self.initial_state() == Some(ffi::XCB_ICCCM_WM_STATE_ICONIC)
+    }
+
+    pub fn is_normal(&self) -> bool {
+        self.initial_state() == Some(ffi::XCB_ICCCM_WM_STATE_NORMAL)
+    }
+
+    pub fn is_withdrawn(&self) -> bool {
+        self.initial_state() == Some(ffi::XCB_ICCCM_WM_STATE_WITHDRAWN)
+    }
+
+    pub fn is_none(&self) -> bool {
+        !self.is_iconic() && !self.is_normal() && !self.is_withdrawn()
+    }
+
+    pub fn icon_pixmap(&self) -> Option<x::Pixmap> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_ICON_PIXMAP) {
+            Some(x::Pixmap::new(self.0.icon_pixmap))
+        } else {
+            None
+        }
+    }
+
+    pub fn icon_mask(&self) -> Option<x::Pixmap> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_ICON_MASK) {
+            Some(x::Pixmap::new(self.0.icon_mask))
+        } else {
+            None
+        }
+    }
+
+    pub fn icon_window(&self) -> Option<x::Window> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_ICON_WINDOW) {
+            Some(x::Window::new(self.0.icon_window))
+        } else {
+            None
+        }
+    }
+
+    pub fn window_group(&self) -> Option<x::Window> {
+        if self.has(ffi::XCB_ICCCM_WM_HINT_WINDOW_GROUP) {
+            Some(x::Window::new(self.0.window_group))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_urgent(&self) -> bool {
+        unsafe { ffi::xcb_icccm_wm_hints_get_urgency(&self.0) != 0 }
+    }
+}
+
+impl WmHintsBuilder {
+    pub fn input(mut self, value: bool) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_input(&mut self.0, value as u8);
+        }
+
+        self
+    }
+
+    pub fn is_iconic(mut self) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_iconic(&mut self.0);
+        }
+
+        self
+    }
+
+    pub fn is_normal(mut self) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_normal(&mut self.0);
+        }
+
+        self
+    }
+
+    pub fn is_withdrawn(mut self) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_withdrawn(&mut self.0);
+        }
+
+        self
+    }
+
+    pub fn is_none(mut self) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_none(&mut self.0);
+        }
+
+        self
+    }
+
+    pub fn icon_pixmap(mut self, icon: x::Pixmap) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_icon_pixmap(&mut self.0, icon.resource_id());
+        }
+
+        self
+    }
+
+    pub fn icon_mask(mut self, icon: x::Pixmap) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_icon_mask(&mut self.0, icon.resource_id());
+        }
+
+        self
+    }
+
+    pub fn icon_window(mut self, icon: x::Window) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_icon_window(&mut self.0, icon.resource_id());
+        }
+
+        self
+    }
+
+    pub fn window_group(mut self, group: x::Window) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_window_group(&mut self.0, group.resource_id());
+        }
+
+        self
+    }
+
+    pub fn is_urgent(mut self) -> Self {
+        unsafe {
+            ffi::xcb_icccm_wm_hints_set_urgency(&mut self.0);
+        }
+
+        self
+    }
+
+    pub fn build(self) -> WmHints {
+        WmHints(self.0)
+    }
+}
+
+pub struct SetWmHints {
+    pub window: x::Window,
+    pub hints: WmHints,
+}
+
+unsafe impl xcb::RawRequest for SetWmHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        let mut hints = self.hints.0;
+
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_set_wm_hints_checked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    &mut hints,
+                )
+            } else {
+                ffi::xcb_icccm_set_wm_hints(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    &mut hints,
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+void_request!(SetWmHints);
+
+property_reply!(GetWmHintsReply, GetWmHintsCookie, GetWmHintsCookieUnchecked);
+
+impl GetWmHintsReply {
+    /// The hints, or `None` if the property is not set or malformed.
+    pub fn hints(&self) -> Option<WmHints> {
+        unsafe {
+            let mut hints = mem::zeroed();
+
+            if ffi::xcb_icccm_get_wm_hints_from_reply(
+                &mut hints,
+                xcb::Reply::as_raw(&self.0) as *mut _,
+            ) != 0
+            {
+                Some(WmHints(hints))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+pub struct GetWmHints {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_get_wm_hints(ffi_connection(connection), self.window.resource_id())
+            } else {
+                ffi::xcb_icccm_get_wm_hints_unchecked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+property_request!(GetWmHints => GetWmHintsReply, GetWmHintsCookie, GetWmHintsCookieUnchecked);
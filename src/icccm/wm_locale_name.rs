@@ -0,0 +1,59 @@
+use xcb::x;
+
+use super::{
+    change_property, compound_text, get_property, Atoms, EncodeError, GetTextPropertyCookie,
+    GetTextPropertyCookieUnchecked, GetTextPropertyReply,
+};
+
+pub struct SetWmLocaleName {
+    window: x::Window,
+    locale_name: x::Atom,
+    locale: Vec<u8>,
+}
+
+impl SetWmLocaleName {
+    /// Encodes `locale` as a `STRING`, failing if it is not ISO 8859-1 or holds a NUL character.
+    pub fn new(atoms: &Atoms, window: x::Window, locale: &str) -> Result<Self, EncodeError> {
+        Ok(Self {
+            window,
+            locale_name: atoms.wm_locale_name,
+            locale: compound_text::to_latin1(locale)?.into_owned(),
+        })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetWmLocaleName {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.locale_name,
+            x::ATOM_STRING,
+            &self.locale,
+        )
+    }
+}
+
+void_request!(SetWmLocaleName);
+
+pub struct GetWmLocaleName {
+    pub window: x::Window,
+    /// The `WM_LOCALE_NAME` atom.
+    pub locale_name: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmLocaleName {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.locale_name,
+            x::ATOM_STRING,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmLocaleName => GetTextPropertyReply, GetTextPropertyCookie, GetTextPropertyCookieUnchecked);
@@ -0,0 +1,62 @@
+use xcb::x;
+
+use super::{
+    change_property, get_property, Atoms, EncodeError, GetTextPropertyCookie,
+    GetTextPropertyCookieUnchecked, GetTextPropertyReply, TextEncoding,
+};
+
+pub struct SetWmName {
+    window: x::Window,
+    encoding: x::Atom,
+    name: Vec<u8>,
+}
+
+impl SetWmName {
+    /// Encodes `name` in `encoding`, failing if it cannot represent it.
+    pub fn new(
+        atoms: &Atoms,
+        window: x::Window,
+        encoding: TextEncoding,
+        name: &str,
+    ) -> Result<Self, EncodeError> {
+        Ok(Self {
+            window,
+            encoding: encoding.atom(atoms),
+            name: encoding.encode(name)?.into_owned(),
+        })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetWmName {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_NAME,
+            self.encoding,
+            &self.name,
+        )
+    }
+}
+
+void_request!(SetWmName);
+
+pub struct GetWmName {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmName {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_NAME,
+            x::ATOM_ANY,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmName => GetTextPropertyReply, GetTextPropertyCookie, GetTextPropertyCookieUnchecked);
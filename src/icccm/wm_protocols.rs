@@ -0,0 +1,436 @@
+use std::time::{Duration, Instant};
+
+use xcb::{x, Xid, XidNew};
+
+use super::{change_property, get_property, Atoms, GetWmHints, WmHints};
+
+pub struct SetWmProtocols<'a> {
+    pub window: x::Window,
+    /// The `WM_PROTOCOLS` atom.
+    pub protocols: x::Atom,
+    pub atoms: &'a [x::Atom],
+}
+
+unsafe impl<'a> xcb::RawRequest for SetWmProtocols<'a> {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.protocols,
+            x::ATOM_ATOM,
+            self.atoms,
+        )
+    }
+}
+
+void_request!(SetWmProtocols<'a>);
+
+property_reply!(
+    GetWmProtocolsReply,
+    GetWmProtocolsCookie,
+    GetWmProtocolsCookieUnchecked
+);
+
+impl GetWmProtocolsReply {
+    pub fn atoms(&self) -> &[x::Atom] {
+        if self.0.r#type() != x::ATOM_ATOM || self.0.format() != 32 {
+            return &[];
+        }
+
+        self.0.value()
+    }
+}
+
+pub struct GetWmProtocols {
+    pub window: x::Window,
+    /// The `WM_PROTOCOLS` atom.
+    pub protocols: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmProtocols {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.protocols,
+            x::ATOM_ATOM,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmProtocols => GetWmProtocolsReply, GetWmProtocolsCookie, GetWmProtocolsCookieUnchecked);
+
+/// Whether `window` lists `protocol` in its `WM_PROTOCOLS`.
+pub fn supports_protocol(
+    connection: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+    protocol: x::Atom,
+) -> xcb::Result<bool> {
+    let cookie = connection.send_request(&GetWmProtocols {
+        window,
+        protocols: atoms.wm_protocols,
+    });
+
+    Ok(connection
+        .wait_for_reply(cookie)?
+        .atoms()
+        .contains(&protocol))
+}
+
+/// Sends a `WM_PROTOCOLS` message for `protocol` to `window`.
+pub fn send_protocol(
+    connection: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+    protocol: x::Atom,
+    time: x::Timestamp,
+) -> xcb::VoidCookie {
+    connection.send_request(&x::SendEvent {
+        propagate: false,
+        destination: x::SendEventDest::Window(window),
+        event_mask: x::EventMask::NO_EVENT,
+        event: &x::ClientMessageEvent::new(
+            window,
+            atoms.wm_protocols,
+            x::ClientMessageData::Data32([protocol.resource_id(), time, 0, 0, 0]),
+        ),
+    })
+}
+
+/// A `WM_PROTOCOLS` message, received by clients from the window manager.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolMessage {
+    /// `WM_DELETE_WINDOW`, the window should be closed.
+    DeleteWindow { time: x::Timestamp },
+    /// `WM_TAKE_FOCUS`, the client should focus one of its windows if it wants to.
+    TakeFocus { time: x::Timestamp },
+    /// Any other protocol, along the rest of the message.
+    Other {
+        protocol: x::Atom,
+        time: x::Timestamp,
+        data: [u32; 3],
+    },
+}
+
+impl ProtocolMessage {
+    pub fn parse(atoms: &Atoms, event: &x::ClientMessageEvent) -> Option<Self> {
+        if event.r#type() != atoms.wm_protocols {
+            return None;
+        }
+
+        let data = match event.data() {
+            x::ClientMessageData::Data32(data) => data,
+            _ => return None,
+        };
+
+        let protocol = x::Atom::new(data[0]);
+        let time = data[1];
+
+        Some(if protocol == atoms.wm_delete_window {
+            ProtocolMessage::DeleteWindow { time }
+        } else if protocol == atoms.wm_take_focus {
+            ProtocolMessage::TakeFocus { time }
+        } else {
+            ProtocolMessage::Other {
+                protocol,
+                time,
+                data: [data[2], data[3], data[4]],
+            }
+        })
+    }
+}
+
+/// The input models of ICCCM 4.1.7, telling how a window takes the input focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputModel {
+    /// The window never takes the focus.
+    NoInput,
+    /// The window is given the focus by the window manager.
+    Passive,
+    /// The window is given the focus by the window manager, and may move it to its other
+    /// windows.
+    LocallyActive,
+    /// The window is never given the focus, it takes it itself when told to.
+    GloballyActive,
+}
+
+impl InputModel {
+    /// The input model of a window from its hints and whether it supports `WM_TAKE_FOCUS`.
+    ///
+    /// Windows without an input hint are given the focus, as most clients expect.
+    pub fn new(hints: Option<&WmHints>, take_focus: bool) -> Self {
+        let input = hints.and_then(WmHints::input).unwrap_or(true);
+
+        match (input, take_focus) {
+            (false, false) => InputModel::NoInput,
+            (true, false) => InputModel::Passive,
+            (true, true) => InputModel::LocallyActive,
+            (false, true) => InputModel::GloballyActive,
+        }
+    }
+
+    /// Whether the window manager sets the focus on the window.
+    pub fn accepts_focus(self) -> bool {
+        self == InputModel::Passive || self == InputModel::LocallyActive
+    }
+
+    /// Whether the window is sent `WM_TAKE_FOCUS` when it should be focused.
+    pub fn takes_focus(self) -> bool {
+        self == InputModel::LocallyActive || self == InputModel::GloballyActive
+    }
+}
+
+/// Focuses `window` according to its input model, which is returned.
+///
+/// `time` should be the time of the event causing the focus change.
+pub fn focus_window(
+    connection: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+    time: x::Timestamp,
+) -> xcb::Result<InputModel> {
+    let take_focus = atoms.wm_take_focus;
+
+    let hints = connection.send_request(&GetWmHints { window });
+    let protocols = connection.send_request(&GetWmProtocols {
+        window,
+        protocols: atoms.wm_protocols,
+    });
+
+    let hints = connection.wait_for_reply(hints)?.hints();
+    let protocols = connection.wait_for_reply(protocols)?;
+    let model = InputModel::new(hints.as_ref(), protocols.atoms().contains(&take_focus));
+
+    if model.accepts_focus() {
+        connection.send_request(&x::SetInputFocus {
+            revert_to: x::InputFocus::PointerRoot,
+            focus: window,
+            time,
+        });
+    }
+
+    if model.takes_focus() {
+        send_protocol(connection, atoms, window, take_focus, time);
+    }
+
+    Ok(model)
+}
+
+/// A window asked to close with `WM_DELETE_WINDOW`, whose client is killed if it does not comply
+/// in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GracefulClose {
+    window: x::Window,
+    deadline: Instant,
+}
+
+impl GracefulClose {
+    /// Asks `window` to close if it supports `WM_DELETE_WINDOW`, or kills its client right away,
+    /// in which case `None` is returned.
+    ///
+    /// The client is killed by [`GracefulClose::escalate`] once `timeout` elapsed.
+    pub fn start(
+        connection: &xcb::Connection,
+        atoms: &Atoms,
+        window: x::Window,
+        time: x::Timestamp,
+        timeout: Duration,
+    ) -> xcb::Result<Option<Self>> {
+        let delete_window = atoms.wm_delete_window;
+
+        if !supports_protocol(connection, atoms, window, delete_window)? {
+            connection.send_request(&x::KillClient {
+                resource: window.resource_id(),
+            });
+
+            return Ok(None);
+        }
+
+        send_protocol(connection, atoms, window, delete_window, time);
+
+        Ok(Some(Self {
+            window,
+            deadline: Instant::now() + timeout,
+        }))
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    /// Time after which the client is killed.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Whether `event` tells that the window was destroyed, after which nothing is left to do.
+    ///
+    /// An unmapped window is not closed, its client may withdraw it and keep running.
+    pub fn is_closed(&self, event: &xcb::Event) -> bool {
+        match event {
+            xcb::Event::X(x::Event::DestroyNotify(ev)) => ev.window() == self.window,
+            _ => false,
+        }
+    }
+
+    /// Kills the client if the deadline passed, returning whether it did.
+    pub fn escalate(&self, connection: &xcb::Connection) -> bool {
+        if Instant::now() < self.deadline {
+            return false;
+        }
+
+        connection.send_request(&x::KillClient {
+            resource: self.window.resource_id(),
+        });
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atoms() -> Atoms {
+        let atom = x::Atom::new;
+
+        Atoms {
+            wm_state: atom(300),
+            wm_change_state: atom(301),
+            wm_protocols: atom(302),
+            wm_delete_window: atom(303),
+            wm_take_focus: atom(304),
+            wm_colormap_windows: atom(305),
+            wm_client_leader: atom(306),
+            wm_window_role: atom(307),
+            wm_locale_name: atom(308),
+            sm_client_id: atom(309),
+            utf8_string: atom(310),
+            compound_text: atom(311),
+        }
+    }
+
+    fn message(r#type: x::Atom, data: x::ClientMessageData) -> x::ClientMessageEvent {
+        x::ClientMessageEvent::new(x::Window::new(0x400001), r#type, data)
+    }
+
+    #[test]
+    fn input_models() {
+        let input = |input| WmHints::empty().input(input).build();
+        let no_input = WmHints::empty().is_normal().build();
+
+        let models = [
+            (Some(input(false)), false, InputModel::NoInput),
+            (Some(input(true)), false, InputModel::Passive),
+            (Some(input(true)), true, InputModel::LocallyActive),
+            (Some(input(false)), true, InputModel::GloballyActive),
+            (Some(no_input), false, InputModel::Passive),
+            (Some(no_input), true, InputModel::LocallyActive),
+            (None, false, InputModel::Passive),
+            (None, true, InputModel::LocallyActive),
+        ];
+
+        for (hints, take_focus, model) in models {
+            assert_eq!(InputModel::new(hints.as_ref(), take_focus), model);
+        }
+
+        assert!(!InputModel::NoInput.accepts_focus() && !InputModel::NoInput.takes_focus());
+        assert!(InputModel::Passive.accepts_focus() && !InputModel::Passive.takes_focus());
+        assert!(
+            InputModel::LocallyActive.accepts_focus() && InputModel::LocallyActive.takes_focus()
+        );
+        assert!(
+            !InputModel::GloballyActive.accepts_focus() && InputModel::GloballyActive.takes_focus()
+        );
+    }
+
+    #[test]
+    fn protocol_messages() {
+        let atoms = atoms();
+        let data = |protocol: x::Atom| {
+            x::ClientMessageData::Data32([protocol.resource_id(), 1234, 5, 6, 7])
+        };
+
+        assert_eq!(
+            ProtocolMessage::parse(
+                &atoms,
+                &message(atoms.wm_protocols, data(atoms.wm_delete_window))
+            ),
+            Some(ProtocolMessage::DeleteWindow { time: 1234 })
+        );
+        assert_eq!(
+            ProtocolMessage::parse(
+                &atoms,
+                &message(atoms.wm_protocols, data(atoms.wm_take_focus))
+            ),
+            Some(ProtocolMessage::TakeFocus { time: 1234 })
+        );
+        assert_eq!(
+            ProtocolMessage::parse(
+                &atoms,
+                &message(atoms.wm_protocols, data(x::Atom::new(400)))
+            ),
+            Some(ProtocolMessage::Other {
+                protocol: x::Atom::new(400),
+                time: 1234,
+                data: [5, 6, 7],
+            })
+        );
+    }
+
+    #[test]
+    fn protocol_messages_rejected() {
+        let atoms = atoms();
+
+        assert_eq!(
+            ProtocolMessage::parse(
+                &atoms,
+                &message(
+                    atoms.wm_state,
+                    x::ClientMessageData::Data32([
+                        atoms.wm_delete_window.resource_id(),
+                        0,
+                        0,
+                        0,
+                        0
+                    ])
+                )
+            ),
+            None
+        );
+        assert_eq!(
+            ProtocolMessage::parse(
+                &atoms,
+                &message(atoms.wm_protocols, x::ClientMessageData::Data8([0; 20]))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn graceful_close_waits_for_destruction() {
+        let window = x::Window::new(0x400001);
+        let other = x::Window::new(0x400002);
+        let close = GracefulClose {
+            window,
+            deadline: Instant::now(),
+        };
+
+        let destroy = |window| {
+            xcb::Event::X(x::Event::DestroyNotify(x::DestroyNotifyEvent::new(
+                window, window,
+            )))
+        };
+        let unmap = xcb::Event::X(x::Event::UnmapNotify(x::UnmapNotifyEvent::new(
+            window, window, false,
+        )));
+
+        assert!(close.is_closed(&destroy(window)));
+        assert!(!close.is_closed(&destroy(other)));
+        assert!(!close.is_closed(&unmap));
+    }
+}
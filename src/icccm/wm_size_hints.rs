@@ -0,0 +1,520 @@
+use std::mem;
+
+use xcb::{x, Xid};
+
+use super::{ffi, ffi_connection};
+
+#[derive(Clone, Copy, Debug)]
+pub struct SizeHints(ffi::xcb_size_hints_t);
+#[derive(Clone, Copy, Debug)]
+pub struct SizeHintsBuilder(ffi::xcb_size_hints_t);
+
+impl SizeHints {
+    pub fn empty() -> SizeHintsBuilder {
+        unsafe { SizeHintsBuilder(mem::zeroed()) }
+    }
+
+    fn has(&self, flag: ffi::xcb_icccm_size_hints_flags_t) -> bool {
+        self.0.flags & flag != 0
+    }
+
+    /// The position, whether it was specified by the user or by the program.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_US_POSITION | ffi::XCB_ICCCM_SIZE_HINT_P_POSITION) {
+            Some((self.0.x, self.0.y))
+        } else {
+            None
+        }
+    }
+
+    /// The size, whether it was specified by the user or by the program.
+    pub fn size(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_US_SIZE | ffi::XCB_ICCCM_SIZE_HINT_P_SIZE) {
+            Some((self.0.width, self.0.height))
+        } else {
+            None
+        }
+    }
+
+    pub fn min_size(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_P_MIN_SIZE) {
+            Some((self.0.min_width, self.0.min_height))
+        } else {
+            None
+        }
+    }
+
+    pub fn max_size(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_P_MAX_SIZE) {
+            Some((self.0.max_width, self.0.max_height))
+        } else {
+            None
+        }
+    }
+
+    pub fn resize(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_P_RESIZE_INC) {
+            Some((self.0.width_inc, self.0.height_inc))
+        } else {
+            None
+        }
+    }
+
+    pub fn aspect(&self) -> Option<((i32, i32), (i32, i32))> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_P_ASPECT) {
+            Some((
+                (self.0.min_aspect_num, self.0.min_aspect_den),
+                (self.0.max_aspect_num, self.0.max_aspect_den),
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn base(&self) -> Option<(i32, i32)> {
+        if self.has(ffi::XCB_ICCCM_SIZE_HINT_BASE_SIZE) {
+            Some((self.0.base_width, self.0.base_height))
+        } else {
+            None
+        }
+    }
+
+    pub fn gravity(&self) -> Option<x::Gravity> {
+        if !self.has(ffi::XCB_ICCCM_SIZE_HINT_P_WIN_GRAVITY) {
+            return None;
+        }
+
+        match self.0.win_gravity {
+            1 => Some(x::Gravity::NorthWest),
+            2 => Some(x::Gravity::North),
+            3 => Some(x::Gravity::NorthEast),
+            4 => Some(x::Gravity::West),
+            5 => Some(x::Gravity::Center),
+            6 => Some(x::Gravity::East),
+            7 => Some(x::Gravity::SouthWest),
+            8 => Some(x::Gravity::South),
+            9 => Some(x::Gravity::SouthEast),
+            10 => Some(x::Gravity::Static),
+            _ => None,
+        }
+    }
+
+    /// The base size, falling back to the minimum size as ICCCM 4.1.2.3 prescribes.
+    fn resolved_base(&self) -> (i32, i32) {
+        let (width, height) = self.base().or_else(|| self.min_size()).unwrap_or((0, 0));
+
+        (width.max(0), height.max(0))
+    }
+
+    /// The minimum size, falling back to the base size as ICCCM 4.1.2.3 prescribes.
+    fn resolved_min(&self) -> (i32, i32) {
+        let (width, height) = self.min_size().or_else(|| self.base()).unwrap_or((0, 0));
+
+        (width.max(1), height.max(1))
+    }
+
+    /// The maximum size, where non positive values mean unbounded.
+    fn resolved_max(&self) -> (i32, i32) {
+        let bound = |value: i32| if value > 0 { value } else { i32::MAX };
+        let (width, height) = self.max_size().unwrap_or((0, 0));
+
+        (bound(width), bound(height))
+    }
+
+    fn resolved_inc(&self) -> (i32, i32) {
+        let (width, height) = self.resize().unwrap_or((1, 1));
+
+        (width.max(1), height.max(1))
+    }
+
+    /// Constrains a proposed size to the hints, following ICCCM 4.1.2.3.
+    ///
+    /// The size is clamped to the minimum and maximum sizes, the aspect ratio of the size minus the
+    /// base size is brought into range, then the size is rounded down to a whole number of
+    /// increments from the base size.
+    pub fn constrain(&self, width: i32, height: i32) -> (i32, i32) {
+        let (min_width, min_height) = self.resolved_min();
+        let (max_width, max_height) = self.resolved_max();
+        let (max_width, max_height) = (max_width.max(min_width), max_height.max(min_height));
+
+        let mut width = width.clamp(min_width, max_width);
+        let mut height = height.clamp(min_height, max_height);
+
+        if let Some(((min_num, min_den), (max_num, max_den))) = self.aspect() {
+            // Only an actual base size is subtracted, the minimum size is not used in its place.
+            let (base_width, base_height) = self.base().unwrap_or((0, 0));
+            let w = i64::from(width) - i64::from(base_width);
+            let h = i64::from(height) - i64::from(base_height);
+
+            if w > 0 && h > 0 {
+                let (min_num, min_den) = (i64::from(min_num), i64::from(min_den));
+                let (max_num, max_den) = (i64::from(max_num), i64::from(max_den));
+
+                if min_num > 0 && min_den > 0 && w * min_den < h * min_num {
+                    // Too narrow, the height is reduced.
+                    height = saturate(i64::from(base_height) + w * min_den / min_num);
+                } else if max_num > 0 && max_den > 0 && w * max_den > h * max_num {
+                    // Too wide, the width is reduced.
+                    width = saturate(i64::from(base_width) + h * max_num / max_den);
+                }
+
+                width = width.clamp(min_width, max_width);
+                height = height.clamp(min_height, max_height);
+            }
+        }
+
+        let (base_width, base_height) = self.resolved_base();
+        let (inc_width, inc_height) = self.resolved_inc();
+
+        let round = |value: i32, base: i32, inc: i32, min: i32, max: i32| {
+            if value <= base {
+                return value;
+            }
+
+            let (value, base, inc) = (i64::from(value), i64::from(base), i64::from(inc));
+            let rounded = base + (value - base) / inc * inc;
+
+            // Rounding down may go below the minimum size, one more increment brings it back.
+            if rounded < i64::from(min) && rounded + inc <= i64::from(max) {
+                saturate(rounded + inc)
+            } else {
+                saturate(rounded)
+            }
+        };
+
+        (
+            round(width, base_width, inc_width, min_width, max_width),
+            round(height, base_height, inc_height, min_height, max_height),
+        )
+    }
+
+    /// The size in increments from the base size, like the columns and rows of a terminal.
+    pub fn size_in_increments(&self, width: i32, height: i32) -> (i32, i32) {
+        let (base_width, base_height) = self.resolved_base();
+        let (inc_width, inc_height) = self.resolved_inc();
+
+        let count = |value: i32, base: i32, inc: i32| {
+            saturate((i64::from(value) - i64::from(base)).max(0) / i64::from(inc))
+        };
+
+        (
+            count(width, base_width, inc_width),
+            count(height, base_height, inc_height),
+        )
+    }
+}
+
+/// Brings a size computed with more room back into the range of `i32`.
+fn saturate(value: i64) -> i32 {
+    value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+}
+
+impl SizeHintsBuilder {
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_position(&mut self.0, 0, x, y) }
+
+        self
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_size(&mut self.0, 0, width, height) }
+
+        self
+    }
+
+    pub fn min_size(mut self, width: i32, height: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_min_size(&mut self.0, width, height) }
+
+        self
+    }
+
+    pub fn max_size(mut self, width: i32, height: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_max_size(&mut self.0, width, height) }
+
+        self
+    }
+
+    pub fn resize(mut self, width: i32, height: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_resize_inc(&mut self.0, width, height) }
+
+        self
+    }
+
+    pub fn aspect(mut self, min: (i32, i32), max: (i32, i32)) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_aspect(&mut self.0, min.0, min.1, max.0, max.1) }
+
+        self
+    }
+
+    pub fn base(mut self, width: i32, height: i32) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_base_size(&mut self.0, width, height) }
+
+        self
+    }
+
+    pub fn gravity(mut self, gravity: x::Gravity) -> Self {
+        unsafe { ffi::xcb_icccm_size_hints_set_win_gravity(&mut self.0, gravity as u32) }
+
+        self
+    }
+
+    pub fn build(self) -> SizeHints {
+        SizeHints(self.0)
+    }
+}
+
+pub struct SetWmSizeHints {
+    pub window: x::Window,
+    pub property: x::Atom,
+    pub hints: SizeHints,
+}
+
+unsafe impl xcb::RawRequest for SetWmSizeHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        let mut hints = self.hints.0;
+
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_set_wm_size_hints_checked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    self.property.resource_id(),
+                    &mut hints,
+                )
+            } else {
+                ffi::xcb_icccm_set_wm_size_hints(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    self.property.resource_id(),
+                    &mut hints,
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+void_request!(SetWmSizeHints);
+
+property_reply!(
+    GetWmSizeHintsReply,
+    GetWmSizeHintsCookie,
+    GetWmSizeHintsCookieUnchecked
+);
+
+impl GetWmSizeHintsReply {
+    /// The hints, or `None` if the property is not set or malformed.
+    pub fn hints(&self) -> Option<SizeHints> {
+        unsafe {
+            let mut hints = mem::zeroed();
+
+            if ffi::xcb_icccm_get_wm_size_hints_from_reply(
+                &mut hints,
+                xcb::Reply::as_raw(&self.0) as *mut _,
+            ) != 0
+            {
+                Some(SizeHints(hints))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+pub struct GetWmSizeHints {
+    pub window: x::Window,
+    pub property: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmSizeHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_get_wm_size_hints(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    self.property.resource_id(),
+                )
+            } else {
+                ffi::xcb_icccm_get_wm_size_hints_unchecked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    self.property.resource_id(),
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+property_request!(GetWmSizeHints => GetWmSizeHintsReply, GetWmSizeHintsCookie, GetWmSizeHintsCookieUnchecked);
+
+pub struct SetWmNormalHints {
+    pub window: x::Window,
+    pub hints: SizeHints,
+}
+
+unsafe impl xcb::RawRequest for SetWmNormalHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        let mut hints = self.hints.0;
+
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_set_wm_normal_hints_checked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    &mut hints,
+                )
+            } else {
+                ffi::xcb_icccm_set_wm_normal_hints(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                    &mut hints,
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+void_request!(SetWmNormalHints);
+
+pub struct GetWmNormalHints {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmNormalHints {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        unsafe {
+            if checked {
+                ffi::xcb_icccm_get_wm_normal_hints(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                )
+            } else {
+                ffi::xcb_icccm_get_wm_normal_hints_unchecked(
+                    ffi_connection(connection),
+                    self.window.resource_id(),
+                )
+            }
+            .sequence as u64
+        }
+    }
+}
+
+property_request!(GetWmNormalHints => GetWmSizeHintsReply, GetWmSizeHintsCookie, GetWmSizeHintsCookieUnchecked);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constrain_clamps_to_min_and_max() {
+        let hints = SizeHints::empty()
+            .min_size(100, 50)
+            .max_size(400, 300)
+            .build();
+
+        assert_eq!(hints.constrain(50, 1000), (100, 300));
+        assert_eq!(hints.constrain(200, 100), (200, 100));
+    }
+
+    #[test]
+    fn constrain_ignores_max_below_min() {
+        let hints = SizeHints::empty()
+            .min_size(200, 200)
+            .max_size(100, 100)
+            .build();
+
+        assert_eq!(hints.constrain(150, 50), (200, 200));
+        assert_eq!(hints.constrain(300, 300), (200, 200));
+    }
+
+    #[test]
+    fn base_falls_back_to_min() {
+        let hints = SizeHints::empty().min_size(10, 20).resize(5, 7).build();
+
+        assert_eq!(hints.constrain(33, 50), (30, 48));
+        assert_eq!(hints.size_in_increments(33, 50), (4, 4));
+    }
+
+    #[test]
+    fn min_falls_back_to_base() {
+        let hints = SizeHints::empty().base(10, 20).resize(5, 7).build();
+
+        assert_eq!(hints.constrain(1, 1), (10, 20));
+        assert_eq!(hints.constrain(33, 50), (30, 48));
+        assert_eq!(hints.size_in_increments(33, 50), (4, 4));
+        assert_eq!(hints.size_in_increments(1, 1), (0, 0));
+    }
+
+    #[test]
+    fn constrain_rounds_to_increments() {
+        let hints = SizeHints::empty()
+            .base(4, 4)
+            .resize(10, 20)
+            .max_size(1000, 1000)
+            .build();
+
+        assert_eq!(hints.constrain(100, 100), (94, 84));
+        assert_eq!(hints.constrain(104, 104), (104, 104));
+        assert_eq!(hints.size_in_increments(104, 104), (10, 5));
+    }
+
+    #[test]
+    fn constrain_rounds_back_up_to_min() {
+        let hints = SizeHints::empty()
+            .base(0, 0)
+            .min_size(25, 25)
+            .resize(10, 10)
+            .max_size(1000, 1000)
+            .build();
+
+        assert_eq!(hints.constrain(25, 37), (30, 30));
+    }
+
+    #[test]
+    fn constrain_applies_min_aspect() {
+        let hints = SizeHints::empty().aspect((1, 1), (2, 1)).build();
+
+        assert_eq!(hints.constrain(100, 200), (100, 100));
+        assert_eq!(hints.constrain(150, 100), (150, 100));
+    }
+
+    #[test]
+    fn constrain_applies_max_aspect() {
+        let hints = SizeHints::empty().aspect((1, 1), (2, 1)).build();
+
+        assert_eq!(hints.constrain(400, 100), (200, 100));
+    }
+
+    #[test]
+    fn constrain_applies_aspect_past_base() {
+        let hints = SizeHints::empty()
+            .base(10, 10)
+            .aspect((1, 1), (1, 1))
+            .build();
+
+        assert_eq!(hints.constrain(110, 60), (60, 60));
+        assert_eq!(hints.constrain(60, 110), (60, 60));
+    }
+
+    #[test]
+    fn constrain_does_not_overflow() {
+        let hints = SizeHints::empty()
+            .base(-10, -10)
+            .aspect((1, 1), (1, 1))
+            .build();
+
+        assert_eq!(hints.constrain(i32::MAX, i32::MAX), (i32::MAX, i32::MAX));
+
+        let hints = SizeHints::empty()
+            .base(5, 5)
+            .min_size(6, 6)
+            .resize(i32::MAX, i32::MAX)
+            .build();
+
+        assert_eq!(hints.constrain(10, 10), (5, 5));
+        assert_eq!(hints.size_in_increments(i32::MIN, i32::MAX), (0, 0));
+    }
+}
@@ -0,0 +1,68 @@
+use xcb::{x, Xid, XidNew};
+
+use super::{change_property, get_property, WmState};
+
+pub struct SetWmState {
+    pub window: x::Window,
+    /// The `WM_STATE` atom.
+    pub wm_state: x::Atom,
+    pub state: WmState,
+    pub icon: x::Window,
+}
+
+unsafe impl xcb::RawRequest for SetWmState {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.wm_state,
+            self.wm_state,
+            &[self.state, self.icon.resource_id()],
+        )
+    }
+}
+
+void_request!(SetWmState);
+
+property_reply!(GetWmStateReply, GetWmStateCookie, GetWmStateCookieUnchecked);
+
+impl GetWmStateReply {
+    fn values(&self) -> &[u32] {
+        if self.0.r#type() == x::ATOM_NONE || self.0.format() != 32 {
+            return &[];
+        }
+
+        self.0.value()
+    }
+
+    /// The state, or `None` if the window is not managed.
+    pub fn state(&self) -> Option<WmState> {
+        self.values().first().copied()
+    }
+
+    pub fn icon(&self) -> Option<x::Window> {
+        self.values().get(1).map(|&icon| x::Window::new(icon))
+    }
+}
+
+pub struct GetWmState {
+    pub window: x::Window,
+    /// The `WM_STATE` atom.
+    pub wm_state: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmState {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.wm_state,
+            self.wm_state,
+            2,
+        )
+    }
+}
+
+property_request!(GetWmState => GetWmStateReply, GetWmStateCookie, GetWmStateCookieUnchecked);
@@ -0,0 +1,124 @@
+use xcb::{x, Xid};
+
+use super::{change_property, get_property, GetWmHints};
+
+pub struct SetWmTransientFor {
+    pub window: x::Window,
+    pub transient_for: x::Window,
+}
+
+unsafe impl xcb::RawRequest for SetWmTransientFor {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_TRANSIENT_FOR,
+            x::ATOM_WINDOW,
+            &[self.transient_for],
+        )
+    }
+}
+
+void_request!(SetWmTransientFor);
+
+property_reply!(
+    GetWmTransientForReply,
+    GetWmTransientForCookie,
+    GetWmTransientForCookieUnchecked
+);
+
+impl GetWmTransientForReply {
+    /// The window this one is transient for, or `None` if it is not a transient window.
+    pub fn transient_for(&self) -> Option<x::Window> {
+        if self.0.r#type() != x::ATOM_WINDOW || self.0.format() != 32 {
+            return None;
+        }
+
+        self.0
+            .value::<x::Window>()
+            .first()
+            .copied()
+            .filter(|window| !window.is_none())
+    }
+}
+
+pub struct GetWmTransientFor {
+    pub window: x::Window,
+}
+
+unsafe impl xcb::RawRequest for GetWmTransientFor {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            x::ATOM_WM_TRANSIENT_FOR,
+            x::ATOM_WINDOW,
+            1,
+        )
+    }
+}
+
+property_request!(GetWmTransientFor => GetWmTransientForReply, GetWmTransientForCookie, GetWmTransientForCookieUnchecked);
+
+/// The end of a chain of transient windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransientOwner {
+    /// The top-level window the chain leads to, which is the window itself if it is not transient.
+    pub owner: x::Window,
+    /// The window group of the owner, or of the window if the owner is not part of a group.
+    pub group: Option<x::Window>,
+}
+
+/// Follows `WM_TRANSIENT_FOR` from `window` up to its top-level owner.
+///
+/// The chain stops at a window which is not transient, at a root window, at a window which no
+/// longer exists and before a window already visited, so that cycles do not loop forever.
+pub fn transient_owner(
+    connection: &xcb::Connection,
+    window: x::Window,
+) -> xcb::Result<TransientOwner> {
+    let roots = connection
+        .get_setup()
+        .roots()
+        .map(|screen| screen.root())
+        .collect::<Vec<_>>();
+
+    let mut chain = vec![window];
+
+    loop {
+        let current = *chain.last().unwrap();
+        let cookie = connection.send_request(&GetWmTransientFor { window: current });
+
+        let next = match connection.wait_for_reply(cookie) {
+            Ok(reply) => reply.transient_for(),
+            Err(xcb::Error::Protocol(_)) if chain.len() > 1 => {
+                chain.pop();
+                break;
+            }
+            Err(err) => return Err(err),
+        };
+
+        match next {
+            Some(next) if !roots.contains(&next) && !chain.contains(&next) => chain.push(next),
+            _ => break,
+        }
+    }
+
+    let owner = *chain.last().unwrap();
+
+    let owner_hints = connection.send_request(&GetWmHints { window: owner });
+    let window_hints = connection.send_request(&GetWmHints { window });
+
+    let group = connection
+        .wait_for_reply(owner_hints)?
+        .hints()
+        .and_then(|hints| hints.window_group())
+        .or(connection
+            .wait_for_reply(window_hints)?
+            .hints()
+            .and_then(|hints| hints.window_group()));
+
+    Ok(TransientOwner { owner, group })
+}
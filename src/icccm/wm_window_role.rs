@@ -0,0 +1,59 @@
+use xcb::x;
+
+use super::{
+    change_property, compound_text, get_property, Atoms, EncodeError, GetTextPropertyCookie,
+    GetTextPropertyCookieUnchecked, GetTextPropertyReply,
+};
+
+pub struct SetWmWindowRole {
+    window: x::Window,
+    window_role: x::Atom,
+    role: Vec<u8>,
+}
+
+impl SetWmWindowRole {
+    /// Encodes `role` as a `STRING`, failing if it is not ISO 8859-1 or holds a NUL character.
+    pub fn new(atoms: &Atoms, window: x::Window, role: &str) -> Result<Self, EncodeError> {
+        Ok(Self {
+            window,
+            window_role: atoms.wm_window_role,
+            role: compound_text::to_latin1(role)?.into_owned(),
+        })
+    }
+}
+
+unsafe impl xcb::RawRequest for SetWmWindowRole {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        change_property(
+            connection,
+            checked,
+            self.window,
+            self.window_role,
+            x::ATOM_STRING,
+            &self.role,
+        )
+    }
+}
+
+void_request!(SetWmWindowRole);
+
+pub struct GetWmWindowRole {
+    pub window: x::Window,
+    /// The `WM_WINDOW_ROLE` atom.
+    pub window_role: x::Atom,
+}
+
+unsafe impl xcb::RawRequest for GetWmWindowRole {
+    fn raw_request(&self, connection: &xcb::Connection, checked: bool) -> u64 {
+        get_property(
+            connection,
+            checked,
+            self.window,
+            self.window_role,
+            x::ATOM_STRING,
+            u32::MAX,
+        )
+    }
+}
+
+property_request!(GetWmWindowRole => GetTextPropertyReply, GetTextPropertyCookie, GetTextPropertyCookieUnchecked);
@@ -2,18 +2,20 @@
 
 #[macro_use]
 extern crate bitflags;
+extern crate libc;
 extern crate xcb;
 extern crate xcb_util_sys;
 
 pub mod ffi;
 
-#[macro_use]
 mod util;
 
-#[cfg(feature = "icccm")]
+pub mod atoms;
+
+#[cfg(any(feature = "icccm", feature = "icccm-native"))]
 pub mod icccm;
 
-#[cfg(feature = "ewmh")]
+#[cfg(any(feature = "ewmh", feature = "ewmh-native"))]
 pub mod ewmh;
 
 #[cfg(feature = "image")]
@@ -25,8 +27,32 @@ pub mod cursor;
 #[cfg(feature = "keysyms")]
 pub mod keysyms;
 
-#[cfg(feature = "misc")]
+#[cfg(all(feature = "misc", any(feature = "icccm", feature = "icccm-native")))]
 pub mod misc;
 
+#[cfg(all(
+    feature = "misc",
+    not(any(feature = "icccm", feature = "icccm-native"))
+))]
+compile_error!("the `misc` feature requires either the `icccm` or the `icccm-native` feature");
+
 #[cfg(feature = "render")]
 pub mod render;
+
+#[cfg(feature = "manager")]
+pub mod manager;
+
+#[cfg(feature = "tray")]
+pub mod tray;
+
+#[cfg(feature = "xembed")]
+pub mod xembed;
+
+#[cfg(feature = "xsettings")]
+pub mod xsettings;
+
+#[cfg(feature = "selection")]
+pub mod selection;
+
+#[cfg(feature = "clipboard-manager")]
+pub mod clipboard_manager;
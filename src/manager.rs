@@ -0,0 +1,362 @@
+//! Manager selections, as described in section 2.8 of the ICCCM.
+//!
+//! Window managers, compositors, trays, clipboard and settings managers all advertise themselves
+//! by owning a selection and broadcasting a `MANAGER` message on the root window.
+//! [`ManagerSelection`] implements the owner side, [`Announcement`] parses the message on the side
+//! of the clients.
+
+use std::time::{Duration, Instant};
+
+use xcb::{x, Xid, XidNew};
+
+use util::event;
+
+/// Time given to the server to answer [`server_time`] while acquiring a selection.
+const SERVER_TIME_TIMEOUT: Duration = Duration::from_secs(5);
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub manager => b"MANAGER" only_if_exists = false,
+    }
+}
+
+fn root(connection: &xcb::Connection, screen_nbr: i32) -> Option<x::Window> {
+    connection
+        .get_setup()
+        .roots()
+        .nth(screen_nbr as usize)
+        .map(|screen| screen.root())
+}
+
+/// The current owner of `selection`, if any.
+pub fn owner(connection: &xcb::Connection, selection: x::Atom) -> xcb::Result<Option<x::Window>> {
+    let owner = connection
+        .wait_for_reply(connection.send_request(&x::GetSelectionOwner { selection }))?
+        .owner();
+
+    Ok(if owner.is_none() { None } else { Some(owner) })
+}
+
+/// Gets a timestamp from the server by appending nothing to a property of `window` and waiting for
+/// the resulting `PropertyNotify`.
+///
+/// Returns `None` if the event is not received within `timeout`. The event mask of `window` is
+/// restored afterwards, other events received in the meantime are pushed to `events`.
+pub fn server_time(
+    connection: &xcb::Connection,
+    window: x::Window,
+    property: x::Atom,
+    timeout: Duration,
+    events: &mut Vec<xcb::Event>,
+) -> xcb::Result<Option<x::Timestamp>> {
+    let event_mask = connection
+        .wait_for_reply(connection.send_request(&x::GetWindowAttributes { window }))?
+        .your_event_mask();
+
+    connection.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(event_mask | x::EventMask::PROPERTY_CHANGE)],
+    });
+
+    connection.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Append,
+        window,
+        property,
+        r#type: x::ATOM_INTEGER,
+        data: &[] as &[u32],
+    });
+
+    let deadline = Instant::now() + timeout;
+
+    let time = loop {
+        match event::wait_until(connection, deadline)? {
+            Some(xcb::Event::X(x::Event::PropertyNotify(ev)))
+                if ev.window() == window && ev.atom() == property =>
+            {
+                break Some(ev.time());
+            }
+            Some(event) => events.push(event),
+            None => break None,
+        }
+    };
+
+    connection.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(event_mask)],
+    });
+
+    Ok(time)
+}
+
+/// A manager selection owned by this client.
+pub struct ManagerSelection<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    root: x::Window,
+    selection: x::Atom,
+    window: x::Window,
+    timestamp: x::Timestamp,
+}
+
+impl<'a> ManagerSelection<'a> {
+    /// Makes `window` the owner of `selection` on `screen_nbr` and announces it with a `MANAGER`
+    /// message on the root window.
+    ///
+    /// If another client owns the selection, `replace` is the time to wait for it to destroy its
+    /// window once the selection is taken over, and `None` leaves it alone. Returns `None` if the
+    /// screen does not exist, the server did not give a timestamp in time, the selection is kept
+    /// by its previous owner, or the previous owner did not go away in time, in which case the
+    /// selection is released again.
+    ///
+    /// Events received while acquiring the selection are pushed to `events`, for the caller to
+    /// handle.
+    pub fn acquire(
+        connection: &'a xcb::Connection,
+        screen_nbr: i32,
+        selection: x::Atom,
+        window: x::Window,
+        replace: Option<Duration>,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<Self>> {
+        let root = match root(connection, screen_nbr) {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+
+        let atoms = Atoms::intern_all(connection)?;
+        let mut previous = owner(connection, selection)?;
+
+        if let Some(previous_owner) = previous {
+            if replace.is_none() {
+                return Ok(None);
+            }
+
+            // Selecting the events first is needed to not miss the destruction of the window, it
+            // also tells whether the window still exists.
+            let cookie = connection.send_request_checked(&x::ChangeWindowAttributes {
+                window: previous_owner,
+                value_list: &[x::Cw::EventMask(x::EventMask::STRUCTURE_NOTIFY)],
+            });
+
+            if connection.check_request(cookie).is_err() {
+                previous = None;
+            }
+        }
+
+        let timestamp =
+            match server_time(connection, window, selection, SERVER_TIME_TIMEOUT, events)? {
+                Some(timestamp) => timestamp,
+                None => return Ok(None),
+            };
+
+        connection.send_request(&x::SetSelectionOwner {
+            owner: window,
+            selection,
+            time: timestamp,
+        });
+
+        if owner(connection, selection)? != Some(window) {
+            return Ok(None);
+        }
+
+        let manager = Self {
+            connection,
+            atoms,
+            root,
+            selection,
+            window,
+            timestamp,
+        };
+
+        if let (Some(previous), Some(timeout)) = (previous, replace) {
+            if !manager.wait_for_destroy(previous, timeout, events)? {
+                manager.release();
+
+                return Ok(None);
+            }
+        }
+
+        manager.announce([0, 0]);
+
+        Ok(Some(manager))
+    }
+
+    fn wait_for_destroy(
+        &self,
+        window: x::Window,
+        timeout: Duration,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<bool> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match event::wait_until(self.connection, deadline)? {
+                Some(xcb::Event::X(x::Event::DestroyNotify(ev))) if ev.window() == window => {
+                    return Ok(true);
+                }
+                Some(event) => events.push(event),
+                None => return Ok(false),
+            }
+        }
+    }
+
+    /// Broadcasts the `MANAGER` message again, with selection specific `data`.
+    pub fn announce(&self, data: [u32; 2]) -> xcb::VoidCookie {
+        self.connection.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(self.root),
+            event_mask: x::EventMask::STRUCTURE_NOTIFY,
+            event: &Announcement {
+                timestamp: self.timestamp,
+                selection: self.selection,
+                owner: self.window,
+                data,
+            }
+            .to_event(&self.atoms, self.root),
+        })
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    pub fn root(&self) -> x::Window {
+        self.root
+    }
+
+    pub fn selection(&self) -> x::Atom {
+        self.selection
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    /// Time at which the selection was acquired.
+    pub fn timestamp(&self) -> x::Timestamp {
+        self.timestamp
+    }
+
+    /// Whether `event` tells that another client took the selection over.
+    ///
+    /// The manager should then clean up and destroy its window, which is what the new owner waits
+    /// for.
+    pub fn is_lost(&self, event: &xcb::Event) -> bool {
+        match event {
+            xcb::Event::X(x::Event::SelectionClear(ev)) => {
+                ev.selection() == self.selection && ev.owner() == self.window
+            }
+            _ => false,
+        }
+    }
+
+    /// Gives the selection up.
+    pub fn release(self) -> xcb::VoidCookie {
+        self.connection.send_request(&x::SetSelectionOwner {
+            owner: x::Window::none(),
+            selection: self.selection,
+            time: self.timestamp,
+        })
+    }
+}
+
+/// A `MANAGER` message, broadcast on the root window when a manager selection changes owner.
+///
+/// Clients receive it by selecting `StructureNotify` on the root window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Announcement {
+    pub timestamp: x::Timestamp,
+    pub selection: x::Atom,
+    pub owner: x::Window,
+    pub data: [u32; 2],
+}
+
+impl Announcement {
+    pub fn parse(atoms: &Atoms, event: &x::ClientMessageEvent) -> Option<Self> {
+        if event.r#type() != atoms.manager {
+            return None;
+        }
+
+        match event.data() {
+            x::ClientMessageData::Data32(data) => Some(Self {
+                timestamp: data[0],
+                selection: x::Atom::new(data[1]),
+                owner: x::Window::new(data[2]),
+                data: [data[3], data[4]],
+            }),
+            _ => None,
+        }
+    }
+
+    /// The `MANAGER` message to broadcast on `root`.
+    pub fn to_event(&self, atoms: &Atoms, root: x::Window) -> x::ClientMessageEvent {
+        x::ClientMessageEvent::new(
+            root,
+            atoms.manager,
+            x::ClientMessageData::Data32([
+                self.timestamp,
+                self.selection.resource_id(),
+                self.owner.resource_id(),
+                self.data[0],
+                self.data[1],
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atoms() -> Atoms {
+        Atoms {
+            manager: x::Atom::new(300),
+        }
+    }
+
+    fn announcement() -> Announcement {
+        Announcement {
+            timestamp: 1234,
+            selection: x::Atom::new(301),
+            owner: x::Window::new(0x0020_0001),
+            data: [5, 6],
+        }
+    }
+
+    #[test]
+    fn encode_announcement() {
+        let root = x::Window::new(0x0000_0100);
+        let event = announcement().to_event(&atoms(), root);
+
+        assert_eq!(event.window(), root);
+        assert_eq!(event.r#type(), x::Atom::new(300));
+        assert_eq!(event.format(), 32);
+
+        match event.data() {
+            x::ClientMessageData::Data32(data) => {
+                assert_eq!(data, [1234, 301, 0x0020_0001, 5, 6])
+            }
+            data => panic!("unexpected data {:?}", data),
+        }
+
+        assert_eq!(Announcement::parse(&atoms(), &event), Some(announcement()));
+    }
+
+    #[test]
+    fn parse_rejects_other_messages() {
+        let root = x::Window::new(0x0000_0100);
+        let event = announcement().to_event(&atoms(), root);
+
+        let other = Atoms {
+            manager: x::Atom::new(302),
+        };
+
+        assert_eq!(Announcement::parse(&other, &event), None);
+
+        let event =
+            x::ClientMessageEvent::new(root, atoms().manager, x::ClientMessageData::Data8([0; 20]));
+
+        assert_eq!(Announcement::parse(&atoms(), &event), None);
+    }
+}
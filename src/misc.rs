@@ -1,18 +1,52 @@
 use icccm;
+use util::reply;
 use xcb::x;
 
-pub fn client_window(c: &xcb::Connection, window: x::Window) -> Option<x::Window> {
-    fn try_children(c: &xcb::Connection, window: x::Window) -> Option<x::Window> {
+fn has_wm_state(c: &xcb::Connection, cookie: icccm::GetWmStateCookie) -> bool {
+    c.wait_for_reply(cookie)
+        .is_ok_and(|reply| reply.state().is_some())
+}
+
+pub fn client_window(
+    c: &xcb::Connection,
+    atoms: &icccm::Atoms,
+    window: x::Window,
+) -> Option<x::Window> {
+    fn try_children(
+        c: &xcb::Connection,
+        wm_state: x::Atom,
+        window: x::Window,
+    ) -> Option<x::Window> {
         let cookie = c.send_request(&x::QueryTree { window });
 
         if let Ok(query) = c.wait_for_reply(cookie) {
+            // Ask for the state of all the children at once, instead of a round-trip per child.
+            let mut cookies = query
+                .children()
+                .iter()
+                .map(|&child| {
+                    c.send_request(&icccm::GetWmState {
+                        window: child,
+                        wm_state,
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter();
+
             for &child in query.children() {
-                if icccm::get_wm_state(c, child).get_reply().is_ok() {
-                    return Some(child);
-                }
+                let found = if has_wm_state(c, cookies.next().unwrap()) {
+                    Some(child)
+                } else {
+                    try_children(c, wm_state, child)
+                };
+
+                if found.is_some() {
+                    // The states of the remaining children are not needed anymore.
+                    for cookie in cookies {
+                        reply::discard(c, cookie);
+                    }
 
-                if let Some(window) = try_children(c, child) {
-                    return Some(window);
+                    return found;
                 }
             }
         }
@@ -20,9 +54,11 @@ pub fn client_window(c: &xcb::Connection, window: x::Window) -> Option<x::Window
         None
     }
 
-    if icccm::get_wm_state(c, window).get_reply().is_ok() {
+    let wm_state = atoms.wm_state;
+
+    if has_wm_state(c, c.send_request(&icccm::GetWmState { window, wm_state })) {
         Some(window)
     } else {
-        try_children(c, window)
+        try_children(c, wm_state, window)
     }
 }
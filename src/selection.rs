@@ -0,0 +1,1132 @@
+//! Selections, as described in section 2 of the ICCCM, and the clipboard built on them.
+//!
+//! [`SelectionOwner`] owns a selection such as `PRIMARY` or `CLIPBOARD` and converts its contents
+//! for other clients, [`SelectionRequestor`] converts the selection of another client. Both sides
+//! transfer data larger than a request incrementally with `INCR`.
+
+use std::{
+    convert::TryFrom,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use xcb::{x, Xid, XidNew};
+
+use util::event;
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub clipboard => b"CLIPBOARD" only_if_exists = false,
+        pub targets => b"TARGETS" only_if_exists = false,
+        pub timestamp => b"TIMESTAMP" only_if_exists = false,
+        pub multiple => b"MULTIPLE" only_if_exists = false,
+        pub incr => b"INCR" only_if_exists = false,
+        pub atom_pair => b"ATOM_PAIR" only_if_exists = false,
+        pub text => b"TEXT" only_if_exists = false,
+        pub utf8_string => b"UTF8_STRING" only_if_exists = false,
+        pub text_plain_utf8 => b"text/plain;charset=utf-8" only_if_exists = false,
+    }
+}
+
+/// The largest amount of data sent in a single property, above which `INCR` is used.
+///
+/// It is a multiple of 4 bytes, so that chunks hold whole items whatever their format.
+fn chunk_size(connection: &xcb::Connection) -> usize {
+    // Leaves room for the header of ChangeProperty.
+    (connection.get_maximum_request_length() as usize * 4)
+        .saturating_sub(32)
+        .clamp(1024, 1 << 18)
+}
+
+/// Sends `data`, made of items of `format` in native byte order, as the value of `property`.
+///
+/// A trailing partial item is dropped.
+fn change_property(
+    connection: &xcb::Connection,
+    mode: x::PropMode,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    format: u8,
+    data: &[u8],
+) -> xcb::VoidCookie {
+    match format {
+        16 => connection.send_request(&x::ChangeProperty {
+            mode,
+            window,
+            property,
+            r#type,
+            data: &items16(data),
+        }),
+        32 => connection.send_request(&x::ChangeProperty {
+            mode,
+            window,
+            property,
+            r#type,
+            data: &items32(data),
+        }),
+        _ => connection.send_request(&x::ChangeProperty {
+            mode,
+            window,
+            property,
+            r#type,
+            data,
+        }),
+    }
+}
+
+fn items16(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|item| u16::from_ne_bytes([item[0], item[1]]))
+        .collect()
+}
+
+fn items32(data: &[u8]) -> Vec<u32> {
+    data.chunks_exact(4)
+        .map(|item| u32::from_ne_bytes([item[0], item[1], item[2], item[3]]))
+        .collect()
+}
+
+/// The value of a property as bytes, whatever its format.
+fn property_bytes(reply: &x::GetPropertyReply) -> Vec<u8> {
+    match reply.format() {
+        16 => reply
+            .value::<u16>()
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect(),
+        32 => reply
+            .value::<u32>()
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect(),
+        _ => reply.value::<u8>().to_vec(),
+    }
+}
+
+fn latin1(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).ok())
+        .collect()
+}
+
+/// Whether a request made at `time` is for the owner which acquired the selection at
+/// `timestamp`, rather than for a previous one. The comparison accounts for the wrap around of
+/// timestamps.
+fn in_time(time: x::Timestamp, timestamp: x::Timestamp) -> bool {
+    time == x::CURRENT_TIME || time.wrapping_sub(timestamp) < 1 << 31
+}
+
+/// Converts each pair of target and property of a `MULTIPLE` request with `convert`, replacing
+/// the property of the pairs which cannot be converted by `None`.
+fn convert_pairs<F>(pairs: &mut [x::Atom], multiple: x::Atom, mut convert: F) -> xcb::Result<()>
+where
+    F: FnMut(x::Atom, x::Atom) -> xcb::Result<bool>,
+{
+    for pair in pairs.chunks_exact_mut(2) {
+        let (target, property) = (pair[0], pair[1]);
+
+        if target == multiple || property == x::ATOM_NONE || !convert(target, property)? {
+            pair[1] = x::ATOM_NONE;
+        }
+    }
+
+    Ok(())
+}
+
+/// Tells the requestor of `request` that the selection was converted into `property`, or could
+/// not be if it is `None`.
+pub fn notify(
+    connection: &xcb::Connection,
+    request: &x::SelectionRequestEvent,
+    property: x::Atom,
+) -> xcb::VoidCookie {
+    connection.send_request(&x::SendEvent {
+        propagate: false,
+        destination: x::SendEventDest::Window(request.requestor()),
+        event_mask: x::EventMask::NO_EVENT,
+        event: &x::SelectionNotifyEvent::new(
+            request.time(),
+            request.requestor(),
+            request.selection(),
+            request.target(),
+            property,
+        ),
+    })
+}
+
+/// What happened to a selection owned by this client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OwnerEvent {
+    /// Another client took the selection over.
+    Lost,
+    /// `target` was converted for `requestor`, possibly starting an incremental transfer.
+    Converted {
+        requestor: x::Window,
+        target: x::Atom,
+    },
+    /// `target` could not be converted for `requestor`.
+    Refused {
+        requestor: x::Window,
+        target: x::Atom,
+    },
+}
+
+/// An incremental transfer to a requestor.
+struct Outgoing {
+    requestor: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    format: u8,
+    data: Rc<[u8]>,
+    offset: usize,
+}
+
+/// A selection owned by this client.
+pub struct SelectionOwner<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    selection: x::Atom,
+    window: x::Window,
+    timestamp: x::Timestamp,
+    /// Targets along the type, format and data they are converted to.
+    contents: Vec<(x::Atom, x::Atom, u8, Rc<[u8]>)>,
+    transfers: Vec<Outgoing>,
+}
+
+impl<'a> SelectionOwner<'a> {
+    /// Makes `window` the owner of `selection` at `time`, which should be the time of the event
+    /// causing it, or one from `manager::server_time` when there is none.
+    ///
+    /// Returns `None` if the selection could not be acquired, for instance because another client
+    /// acquired it later than `time`.
+    pub fn acquire(
+        connection: &'a xcb::Connection,
+        selection: x::Atom,
+        window: x::Window,
+        time: x::Timestamp,
+    ) -> xcb::Result<Option<Self>> {
+        connection.send_request(&x::SetSelectionOwner {
+            owner: window,
+            selection,
+            time,
+        });
+
+        let owner = connection
+            .wait_for_reply(connection.send_request(&x::GetSelectionOwner { selection }))?
+            .owner();
+
+        if owner != window {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            connection,
+            atoms: Atoms::intern_all(connection)?,
+            selection,
+            window,
+            timestamp: time,
+            contents: Vec::new(),
+            transfers: Vec::new(),
+        }))
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    pub fn selection(&self) -> x::Atom {
+        self.selection
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    /// Time at which the selection was acquired.
+    pub fn timestamp(&self) -> x::Timestamp {
+        self.timestamp
+    }
+
+    /// The targets the selection can be converted to, besides `TARGETS`, `TIMESTAMP` and
+    /// `MULTIPLE` which are always supported.
+    pub fn targets(&self) -> impl Iterator<Item = x::Atom> + '_ {
+        self.contents.iter().map(|&(target, _, _, _)| target)
+    }
+
+    /// Offers the selection as `target`, usually a MIME type, converted to `data` of type
+    /// `r#type`, replacing what was offered for it before.
+    pub fn offer(&mut self, target: x::Atom, r#type: x::Atom, data: impl Into<Rc<[u8]>>) {
+        self.offer_with_format(target, r#type, 8, data);
+    }
+
+    /// Offers the selection as `target` like [`SelectionOwner::offer`], with `data` made of items
+    /// of `format` 16 or 32 in native byte order, as in [`SelectionData`]. Other formats are
+    /// handled as 8.
+    pub fn offer_with_format(
+        &mut self,
+        target: x::Atom,
+        r#type: x::Atom,
+        format: u8,
+        data: impl Into<Rc<[u8]>>,
+    ) {
+        let format = match format {
+            16 | 32 => format,
+            _ => 8,
+        };
+        let content = (target, r#type, format, data.into());
+
+        match self.contents.iter_mut().find(|(t, _, _, _)| *t == target) {
+            Some(previous) => *previous = content,
+            None => self.contents.push(content),
+        }
+    }
+
+    /// Offers `text` as `UTF8_STRING`, `TEXT` and `text/plain;charset=utf-8`, and as `STRING` if
+    /// it can be represented in ISO 8859-1.
+    pub fn offer_text(&mut self, text: &str) {
+        let utf8 = Rc::<[u8]>::from(text.as_bytes());
+
+        self.offer(self.atoms.utf8_string, self.atoms.utf8_string, utf8.clone());
+        self.offer(self.atoms.text, self.atoms.utf8_string, utf8.clone());
+        self.offer(self.atoms.text_plain_utf8, self.atoms.text_plain_utf8, utf8);
+
+        match latin1(text) {
+            Some(string) => self.offer(x::ATOM_STRING, x::ATOM_STRING, string),
+            None => self.withdraw(x::ATOM_STRING),
+        }
+    }
+
+    /// Stops offering the selection as `target`.
+    pub fn withdraw(&mut self, target: x::Atom) {
+        self.contents.retain(|&(t, _, _, _)| t != target);
+    }
+
+    /// Stops offering the selection as any target.
+    pub fn clear(&mut self) {
+        self.contents.clear();
+    }
+
+    /// Whether `event` is about the selection or its incremental transfers.
+    pub fn concerns(&self, event: &xcb::Event) -> bool {
+        match event {
+            xcb::Event::X(x::Event::SelectionRequest(ev)) => {
+                ev.owner() == self.window && ev.selection() == self.selection
+            }
+            xcb::Event::X(x::Event::SelectionClear(ev)) => {
+                ev.owner() == self.window && ev.selection() == self.selection
+            }
+            xcb::Event::X(x::Event::PropertyNotify(ev)) if ev.state() == x::Property::Delete => {
+                self.transfers
+                    .iter()
+                    .any(|t| t.requestor == ev.window() && t.property == ev.atom())
+            }
+            xcb::Event::X(x::Event::DestroyNotify(ev)) => {
+                self.transfers.iter().any(|t| t.requestor == ev.window())
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles an event, answering conversion requests and continuing incremental transfers.
+    pub fn handle_event(&mut self, event: &xcb::Event) -> xcb::Result<Option<OwnerEvent>> {
+        match event {
+            xcb::Event::X(x::Event::SelectionRequest(ev))
+                if ev.owner() == self.window && ev.selection() == self.selection =>
+            {
+                self.answer(ev).map(Some)
+            }
+            xcb::Event::X(x::Event::SelectionClear(ev))
+                if ev.owner() == self.window && ev.selection() == self.selection =>
+            {
+                Ok(Some(OwnerEvent::Lost))
+            }
+            xcb::Event::X(x::Event::PropertyNotify(ev)) if ev.state() == x::Property::Delete => {
+                self.send_chunk(ev.window(), ev.atom());
+                Ok(None)
+            }
+            xcb::Event::X(x::Event::DestroyNotify(ev)) => {
+                self.transfers
+                    .retain(|transfer| transfer.requestor != ev.window());
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn answer(&mut self, request: &x::SelectionRequestEvent) -> xcb::Result<OwnerEvent> {
+        let requestor = request.requestor();
+        let target = request.target();
+
+        // Obsolete clients do not give a property, in which case the target is used.
+        let property = if request.property() == x::ATOM_NONE {
+            target
+        } else {
+            request.property()
+        };
+
+        // Requests from before the selection was acquired are for the previous owner.
+        let converted = in_time(request.time(), self.timestamp)
+            && if target == self.atoms.multiple {
+                request.property() != x::ATOM_NONE && self.convert_multiple(requestor, property)?
+            } else {
+                self.convert(requestor, target, property)?
+            };
+
+        notify(
+            self.connection,
+            request,
+            if converted { property } else { x::ATOM_NONE },
+        );
+
+        Ok(if converted {
+            OwnerEvent::Converted { requestor, target }
+        } else {
+            OwnerEvent::Refused { requestor, target }
+        })
+    }
+
+    /// Stores `target` in `property` of `requestor`, returning whether the target is supported.
+    fn convert(
+        &mut self,
+        requestor: x::Window,
+        target: x::Atom,
+        property: x::Atom,
+    ) -> xcb::Result<bool> {
+        if target == self.atoms.targets {
+            let mut targets = vec![
+                self.atoms.targets,
+                self.atoms.timestamp,
+                self.atoms.multiple,
+            ];
+            targets.extend(self.targets());
+
+            self.connection.send_request(&x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: requestor,
+                property,
+                r#type: x::ATOM_ATOM,
+                data: &targets,
+            });
+
+            return Ok(true);
+        }
+
+        if target == self.atoms.timestamp {
+            self.connection.send_request(&x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window: requestor,
+                property,
+                r#type: x::ATOM_INTEGER,
+                data: &[self.timestamp],
+            });
+
+            return Ok(true);
+        }
+
+        let (r#type, format, data) = match self.contents.iter().find(|&&(t, _, _, _)| t == target) {
+            Some(&(_, r#type, format, ref data)) => (r#type, format, data.clone()),
+            None => return Ok(false),
+        };
+
+        if data.len() <= chunk_size(self.connection) {
+            change_property(
+                self.connection,
+                x::PropMode::Replace,
+                requestor,
+                property,
+                r#type,
+                format,
+                &data,
+            );
+
+            return Ok(true);
+        }
+
+        // The requestor deletes the INCR property to ask for the first chunk, which is noticed
+        // through the events of its window. They are added to those already selected on it.
+        let attributes = self.connection.wait_for_reply(
+            self.connection
+                .send_request(&x::GetWindowAttributes { window: requestor }),
+        );
+
+        let event_mask = match attributes {
+            Ok(attributes) => attributes.your_event_mask(),
+            // The requestor went away.
+            Err(xcb::Error::Protocol(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        self.connection.send_request(&x::ChangeWindowAttributes {
+            window: requestor,
+            value_list: &[x::Cw::EventMask(
+                event_mask | x::EventMask::PROPERTY_CHANGE | x::EventMask::STRUCTURE_NOTIFY,
+            )],
+        });
+
+        self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: requestor,
+            property,
+            r#type: self.atoms.incr,
+            data: &[data.len() as u32],
+        });
+
+        self.transfers
+            .retain(|t| t.requestor != requestor || t.property != property);
+        self.transfers.push(Outgoing {
+            requestor,
+            property,
+            r#type,
+            format,
+            data,
+            offset: 0,
+        });
+
+        Ok(true)
+    }
+
+    /// Converts each pair of target and property listed in `property` of `requestor`, replacing
+    /// the property of the pairs which cannot be converted by `None`.
+    fn convert_multiple(&mut self, requestor: x::Window, property: x::Atom) -> xcb::Result<bool> {
+        let reply = self
+            .connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window: requestor,
+                property,
+                r#type: self.atoms.atom_pair,
+                long_offset: 0,
+                long_length: u32::MAX,
+            }))?;
+
+        if reply.format() != 32 {
+            return Ok(false);
+        }
+
+        let mut pairs = reply.value::<x::Atom>().to_vec();
+
+        convert_pairs(&mut pairs, self.atoms.multiple, |target, property| {
+            self.convert(requestor, target, property)
+        })?;
+
+        self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: requestor,
+            property,
+            r#type: self.atoms.atom_pair,
+            data: &pairs,
+        });
+
+        Ok(true)
+    }
+
+    /// Appends the next chunk of the transfer to `property` of `requestor`, ending with an empty
+    /// chunk.
+    fn send_chunk(&mut self, requestor: x::Window, property: x::Atom) {
+        let index = match self
+            .transfers
+            .iter()
+            .position(|t| t.requestor == requestor && t.property == property)
+        {
+            Some(index) => index,
+            None => return,
+        };
+
+        let chunk_size = chunk_size(self.connection);
+        let transfer = &mut self.transfers[index];
+        let end = (transfer.offset + chunk_size).min(transfer.data.len());
+
+        change_property(
+            self.connection,
+            x::PropMode::Append,
+            requestor,
+            property,
+            transfer.r#type,
+            transfer.format,
+            &transfer.data[transfer.offset..end],
+        );
+
+        if transfer.offset == end {
+            self.transfers.remove(index);
+        } else {
+            transfer.offset = end;
+        }
+    }
+
+    /// Gives the selection up.
+    pub fn release(self) -> xcb::VoidCookie {
+        self.connection.send_request(&x::SetSelectionOwner {
+            owner: x::Window::none(),
+            selection: self.selection,
+            time: self.timestamp,
+        })
+    }
+}
+
+/// A selection converted by its owner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectionData {
+    pub selection: x::Atom,
+    pub target: x::Atom,
+    pub r#type: x::Atom,
+    pub format: u8,
+    /// The value, with items of formats 16 and 32 in native byte order.
+    pub data: Vec<u8>,
+}
+
+impl SelectionData {
+    /// The value as atoms, as returned for `TARGETS`, empty if it is not made of atoms.
+    pub fn atoms(&self) -> Vec<x::Atom> {
+        if self.r#type != x::ATOM_ATOM || self.format != 32 {
+            return Vec::new();
+        }
+
+        self.data
+            .chunks_exact(4)
+            .map(|atom| x::Atom::new(u32::from_ne_bytes([atom[0], atom[1], atom[2], atom[3]])))
+            .collect()
+    }
+
+    /// The value as text, `STRING` being decoded as ISO 8859-1 and anything else as lossy UTF-8.
+    pub fn text(&self) -> String {
+        if self.r#type == x::ATOM_STRING {
+            self.data.iter().map(|&byte| byte as char).collect()
+        } else {
+            String::from_utf8_lossy(&self.data).into_owned()
+        }
+    }
+}
+
+/// The outcome of a conversion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conversion {
+    Converted(SelectionData),
+    /// The selection has no owner or the owner cannot convert it to `target`.
+    Refused {
+        selection: x::Atom,
+        target: x::Atom,
+    },
+    /// The incremental transfer of the data went over the size limit or stalled.
+    Aborted {
+        selection: x::Atom,
+        target: x::Atom,
+    },
+}
+
+/// An incremental transfer from an owner.
+struct Incoming {
+    selection: x::Atom,
+    target: x::Atom,
+    property: x::Atom,
+    r#type: x::Atom,
+    format: u8,
+    data: Vec<u8>,
+    /// Time by which the next chunk must be received.
+    deadline: Instant,
+}
+
+impl Incoming {
+    fn aborted(&self) -> Conversion {
+        Conversion::Aborted {
+            selection: self.selection,
+            target: self.target,
+        }
+    }
+}
+
+/// Converts selections owned by other clients.
+pub struct SelectionRequestor<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    window: x::Window,
+    transfers: Vec<Incoming>,
+    max_size: usize,
+    chunk_timeout: Duration,
+}
+
+impl<'a> SelectionRequestor<'a> {
+    /// Converts selections into properties of `window`, whose events are selected to follow
+    /// incremental transfers.
+    pub fn new(connection: &'a xcb::Connection, window: x::Window) -> xcb::Result<Self> {
+        let event_mask = connection
+            .wait_for_reply(connection.send_request(&x::GetWindowAttributes { window }))?
+            .your_event_mask();
+
+        connection.send_request(&x::ChangeWindowAttributes {
+            window,
+            value_list: &[x::Cw::EventMask(event_mask | x::EventMask::PROPERTY_CHANGE)],
+        });
+
+        Ok(Self {
+            connection,
+            atoms: Atoms::intern_all(connection)?,
+            window,
+            transfers: Vec::new(),
+            max_size: 64 << 20,
+            chunk_timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// Aborts incremental transfers whose data grows over `max_size` bytes, 64 MiB by default.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    /// Aborts incremental transfers whose owner sends no chunk for `timeout`, 10 seconds by
+    /// default. Stalled transfers are only noticed by [`SelectionRequestor::expire`].
+    pub fn set_chunk_timeout(&mut self, timeout: Duration) {
+        self.chunk_timeout = timeout;
+    }
+
+    /// The earliest time by which an incremental transfer must receive its next chunk, when
+    /// [`SelectionRequestor::expire`] should be called.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.transfers.iter().map(|t| t.deadline).min()
+    }
+
+    /// Aborts the incremental transfers which stalled.
+    pub fn expire(&mut self) -> Vec<Conversion> {
+        let now = Instant::now();
+        let mut aborted = Vec::new();
+
+        self.transfers.retain(|transfer| {
+            if transfer.deadline > now {
+                return true;
+            }
+
+            aborted.push(transfer.aborted());
+            false
+        });
+
+        aborted
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    /// Asks the owner of `selection` to convert it to `target`, the result is stored in the
+    /// property of the window named after the selection.
+    pub fn convert(
+        &self,
+        selection: x::Atom,
+        target: x::Atom,
+        time: x::Timestamp,
+    ) -> xcb::VoidCookie {
+        self.connection.send_request(&x::ConvertSelection {
+            requestor: self.window,
+            selection,
+            target,
+            property: selection,
+            time,
+        })
+    }
+
+    /// Handles an event, returning the outcome of a conversion once its data is complete.
+    pub fn handle_event(&mut self, event: &xcb::Event) -> xcb::Result<Option<Conversion>> {
+        match event {
+            xcb::Event::X(x::Event::SelectionNotify(ev)) if ev.requestor() == self.window => {
+                if ev.property() == x::ATOM_NONE {
+                    return Ok(Some(Conversion::Refused {
+                        selection: ev.selection(),
+                        target: ev.target(),
+                    }));
+                }
+
+                let reply = self.take_property(ev.property())?;
+
+                if reply.r#type() == self.atoms.incr {
+                    // Deleting the property asked for the first chunk.
+                    self.transfers.retain(|t| t.property != ev.property());
+                    self.transfers.push(Incoming {
+                        selection: ev.selection(),
+                        target: ev.target(),
+                        property: ev.property(),
+                        r#type: x::ATOM_NONE,
+                        format: 8,
+                        data: Vec::new(),
+                        deadline: Instant::now() + self.chunk_timeout,
+                    });
+
+                    return Ok(None);
+                }
+
+                Ok(Some(Conversion::Converted(SelectionData {
+                    selection: ev.selection(),
+                    target: ev.target(),
+                    r#type: reply.r#type(),
+                    format: reply.format(),
+                    data: property_bytes(&reply),
+                })))
+            }
+            xcb::Event::X(x::Event::PropertyNotify(ev))
+                if ev.window() == self.window && ev.state() == x::Property::NewValue =>
+            {
+                let index = match self.transfers.iter().position(|t| t.property == ev.atom()) {
+                    Some(index) => index,
+                    None => return Ok(None),
+                };
+
+                let reply = self.take_property(ev.atom())?;
+                let chunk = property_bytes(&reply);
+
+                if chunk.is_empty() {
+                    let transfer = self.transfers.remove(index);
+
+                    return Ok(Some(Conversion::Converted(SelectionData {
+                        selection: transfer.selection,
+                        target: transfer.target,
+                        r#type: transfer.r#type,
+                        format: transfer.format,
+                        data: transfer.data,
+                    })));
+                }
+
+                if self.transfers[index].data.len() + chunk.len() > self.max_size {
+                    return Ok(Some(self.transfers.remove(index).aborted()));
+                }
+
+                let transfer = &mut self.transfers[index];
+                transfer.r#type = reply.r#type();
+                transfer.format = reply.format();
+                transfer.data.extend(chunk);
+                transfer.deadline = Instant::now() + self.chunk_timeout;
+
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether `event` is about the conversions into the window.
+    fn concerns(&self, event: &xcb::Event) -> bool {
+        match event {
+            xcb::Event::X(x::Event::SelectionNotify(ev)) => ev.requestor() == self.window,
+            xcb::Event::X(x::Event::PropertyNotify(ev)) => ev.window() == self.window,
+            _ => false,
+        }
+    }
+
+    /// Reads and deletes `property` of the window, which for incremental transfers asks for the
+    /// next chunk.
+    fn take_property(&self, property: x::Atom) -> xcb::Result<x::GetPropertyReply> {
+        self.connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: true,
+                window: self.window,
+                property,
+                r#type: x::ATOM_ANY,
+                long_offset: 0,
+                long_length: u32::MAX,
+            }))
+    }
+
+    /// Converts `selection` to `target` and waits up to `timeout` for the outcome.
+    ///
+    /// Returns `None` on timeout or if the conversion is refused or aborted. Other events received
+    /// in the meantime are appended to `events`.
+    pub fn wait_for(
+        &mut self,
+        selection: x::Atom,
+        target: x::Atom,
+        timeout: Duration,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<SelectionData>> {
+        let deadline = Instant::now() + timeout;
+        let wanted = |s: x::Atom, t: x::Atom| s == selection && t == target;
+
+        self.convert(selection, target, x::CURRENT_TIME);
+
+        loop {
+            // Other stalled transfers are left to `expire`.
+            let transfer = self
+                .transfers
+                .iter()
+                .position(|transfer| wanted(transfer.selection, transfer.target));
+            let until = match transfer {
+                Some(index) => self.transfers[index].deadline.min(deadline),
+                None => deadline,
+            };
+
+            let event = match event::wait_until(self.connection, until)? {
+                Some(event) => event,
+                None => break,
+            };
+
+            if !self.concerns(&event) {
+                events.push(event);
+                continue;
+            }
+
+            match self.handle_event(&event)? {
+                Some(Conversion::Converted(data)) if wanted(data.selection, data.target) => {
+                    return Ok(Some(data));
+                }
+                Some(Conversion::Refused {
+                    selection: s,
+                    target: t,
+                })
+                | Some(Conversion::Aborted {
+                    selection: s,
+                    target: t,
+                }) if wanted(s, t) => return Ok(None),
+                _ => {}
+            }
+        }
+
+        // A transfer still going on is given up.
+        self.transfers
+            .retain(|transfer| !wanted(transfer.selection, transfer.target));
+
+        Ok(None)
+    }
+
+    /// Converts `selection` to text, as `UTF8_STRING` or else as `STRING`, waiting up to
+    /// `timeout` for each.
+    ///
+    /// Other events received in the meantime are appended to `events`.
+    pub fn wait_for_text(
+        &mut self,
+        selection: x::Atom,
+        timeout: Duration,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<String>> {
+        for target in [self.atoms.utf8_string, x::ATOM_STRING] {
+            if let Some(data) = self.wait_for(selection, target, timeout, events)? {
+                return Ok(Some(data.text()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads the text in the clipboard, waiting up to `timeout` for its owner to convert it.
+///
+/// A window is created on `screen_nbr` for the time of the conversion. Returns `None` if the
+/// screen does not exist, the clipboard is empty or not convertible to text, or on timeout. Other
+/// events received in the meantime are appended to `events`.
+pub fn get_clipboard_text(
+    connection: &xcb::Connection,
+    screen_nbr: i32,
+    timeout: Duration,
+    events: &mut Vec<xcb::Event>,
+) -> xcb::Result<Option<String>> {
+    let screen = match connection.get_setup().roots().nth(screen_nbr as usize) {
+        Some(screen) => screen,
+        None => return Ok(None),
+    };
+
+    let window = connection.generate_id();
+
+    connection.send_request(&x::CreateWindow {
+        depth: x::COPY_FROM_PARENT as u8,
+        wid: window,
+        parent: screen.root(),
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+        border_width: 0,
+        class: x::WindowClass::InputOnly,
+        visual: screen.root_visual(),
+        value_list: &[],
+    });
+
+    let text = SelectionRequestor::new(connection, window).and_then(|mut requestor| {
+        let clipboard = requestor.atoms().clipboard;
+        requestor.wait_for_text(clipboard, timeout, events)
+    });
+
+    connection.send_request(&x::DestroyWindow { window });
+    connection.flush()?;
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use libc;
+
+    use super::*;
+
+    fn atom(id: u32) -> x::Atom {
+        x::Atom::new(id)
+    }
+
+    /// Builds a `GetProperty` reply as received from the server.
+    fn property(format: u8, items: u32, value: &[u8]) -> x::GetPropertyReply {
+        let padded = value.len().div_ceil(4) * 4;
+
+        let mut wire = vec![0; 32];
+        wire[0] = 1;
+        wire[1] = format;
+        wire[4..8].copy_from_slice(&(padded as u32 / 4).to_ne_bytes());
+        wire[8..12].copy_from_slice(&x::ATOM_INTEGER.resource_id().to_ne_bytes());
+        wire[16..20].copy_from_slice(&items.to_ne_bytes());
+        wire.extend_from_slice(value);
+        wire.resize(32 + padded, 0);
+
+        unsafe {
+            let raw = libc::malloc(wire.len()) as *mut u8;
+            ptr::copy_nonoverlapping(wire.as_ptr(), raw, wire.len());
+
+            <x::GetPropertyReply as xcb::Reply>::from_raw(raw)
+        }
+    }
+
+    fn data(r#type: x::Atom, format: u8, data: &[u8]) -> SelectionData {
+        SelectionData {
+            selection: atom(300),
+            target: atom(301),
+            r#type,
+            format,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn items() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+
+        assert_eq!(
+            items16(&bytes),
+            [
+                u16::from_ne_bytes([0x01, 0x02]),
+                u16::from_ne_bytes([0x03, 0x04]),
+                u16::from_ne_bytes([0x05, 0x06])
+            ]
+        );
+        assert_eq!(
+            items32(&bytes),
+            [u32::from_ne_bytes([0x01, 0x02, 0x03, 0x04])]
+        );
+
+        // Items read back as bytes are sent unchanged.
+        let reply = property(16, 3, &bytes[..6]);
+        assert_eq!(items16(&property_bytes(&reply)), reply.value::<u16>());
+
+        let reply = property(32, 1, &bytes[..4]);
+        assert_eq!(items32(&property_bytes(&reply)), reply.value::<u32>());
+    }
+
+    #[test]
+    fn latin1_text() {
+        assert_eq!(latin1("caf\u{e9}"), Some(b"caf\xe9".to_vec()));
+        assert_eq!(latin1(""), Some(Vec::new()));
+        assert_eq!(latin1("\u{20ac}5"), None);
+    }
+
+    #[test]
+    fn property_bytes_of_every_format() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+
+        assert_eq!(property_bytes(&property(8, 3, &bytes[..3])), bytes[..3]);
+        assert_eq!(property_bytes(&property(16, 3, &bytes[..6])), bytes[..6]);
+        assert_eq!(property_bytes(&property(32, 2, &bytes)), bytes);
+        assert!(property_bytes(&property(0, 0, &[])).is_empty());
+    }
+
+    #[test]
+    fn selection_data_atoms() {
+        let atoms = [atom(300), atom(301)]
+            .iter()
+            .flat_map(|atom| atom.resource_id().to_ne_bytes())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            data(x::ATOM_ATOM, 32, &atoms).atoms(),
+            [atom(300), atom(301)]
+        );
+        assert_eq!(data(x::ATOM_ATOM, 32, &atoms[..7]).atoms(), [atom(300)]);
+        assert!(data(x::ATOM_INTEGER, 32, &atoms).atoms().is_empty());
+        assert!(data(x::ATOM_ATOM, 8, &atoms).atoms().is_empty());
+    }
+
+    #[test]
+    fn selection_data_text() {
+        assert_eq!(data(x::ATOM_STRING, 8, b"caf\xe9").text(), "caf\u{e9}");
+        assert_eq!(
+            data(atom(302), 8, "caf\u{e9}".as_bytes()).text(),
+            "caf\u{e9}"
+        );
+        assert_eq!(data(atom(302), 8, b"caf\xe9").text(), "caf\u{fffd}");
+    }
+
+    #[test]
+    fn requests_in_time() {
+        assert!(in_time(x::CURRENT_TIME, 1000));
+        assert!(in_time(1000, 1000));
+        assert!(in_time(1001, 1000));
+        assert!(!in_time(999, 1000));
+
+        // The server time wrapped around since the selection was acquired.
+        assert!(in_time(10, u32::MAX - 10));
+        assert!(!in_time(u32::MAX - 11, u32::MAX - 10));
+        assert!(!in_time(1000 + (1 << 31), 1000));
+    }
+
+    #[test]
+    fn multiple_pairs() {
+        let multiple = atom(310);
+        let (utf8_string, png, html) = (atom(311), atom(312), atom(313));
+        let (first, second, third, fourth) = (atom(320), atom(321), atom(322), atom(323));
+
+        let mut pairs = vec![
+            utf8_string,
+            first,
+            png,
+            second,
+            multiple,
+            third,
+            html,
+            x::ATOM_NONE,
+            html,
+            fourth,
+        ];
+        let mut converted = Vec::new();
+
+        convert_pairs(&mut pairs, multiple, |target, property| {
+            converted.push((target, property));
+            Ok(target != png)
+        })
+        .unwrap();
+
+        assert_eq!(
+            converted,
+            [(utf8_string, first), (png, second), (html, fourth)]
+        );
+        assert_eq!(
+            pairs,
+            [
+                utf8_string,
+                first,
+                png,
+                x::ATOM_NONE,
+                multiple,
+                x::ATOM_NONE,
+                html,
+                x::ATOM_NONE,
+                html,
+                fourth
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_pairs_ignore_trailing_atom() {
+        let mut pairs = vec![atom(311), atom(320), atom(312)];
+
+        convert_pairs(&mut pairs, atom(310), |_, _| Ok(true)).unwrap();
+
+        assert_eq!(pairs, [atom(311), atom(320), atom(312)]);
+    }
+}
@@ -0,0 +1,607 @@
+//! The freedesktop system tray protocol.
+//!
+//! [`TrayHost`] implements the tray side: it owns the `_NET_SYSTEM_TRAY_Sn` selection, receives
+//! dock requests and balloon messages, and embeds icons. [`TrayClient`] implements the icon side.
+
+use std::{collections::HashMap, time::Duration};
+
+use xcb::{x, Xid, XidNew};
+
+use manager::{self, Announcement, ManagerSelection};
+use xembed::{Embedder, Transition, XEmbed};
+
+/// `SYSTEM_TRAY_REQUEST_DOCK` opcode.
+pub const REQUEST_DOCK: u32 = 0;
+/// `SYSTEM_TRAY_BEGIN_MESSAGE` opcode.
+pub const BEGIN_MESSAGE: u32 = 1;
+/// `SYSTEM_TRAY_CANCEL_MESSAGE` opcode.
+pub const CANCEL_MESSAGE: u32 = 2;
+
+/// Longest balloon message accepted from an icon, longer ones are ignored.
+pub const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub opcode => b"_NET_SYSTEM_TRAY_OPCODE" only_if_exists = false,
+        pub orientation => b"_NET_SYSTEM_TRAY_ORIENTATION" only_if_exists = false,
+        pub visual => b"_NET_SYSTEM_TRAY_VISUAL" only_if_exists = false,
+        pub message_data => b"_NET_SYSTEM_TRAY_MESSAGE_DATA" only_if_exists = false,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Orientation {
+    Horz = 0,
+    Vert = 1,
+}
+
+impl From<u32> for Orientation {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Vert,
+            _ => Self::Horz,
+        }
+    }
+}
+
+fn root(connection: &xcb::Connection, screen_nbr: i32) -> Option<x::Window> {
+    connection
+        .get_setup()
+        .roots()
+        .nth(screen_nbr as usize)
+        .map(|screen| screen.root())
+}
+
+fn values(reply: &x::GetPropertyReply) -> &[u32] {
+    if reply.format() == 32 {
+        reply.value()
+    } else {
+        &[]
+    }
+}
+
+/// Interns the `_NET_SYSTEM_TRAY_Sn` selection atom of `screen_nbr`.
+pub fn selection_atom(connection: &xcb::Connection, screen_nbr: i32) -> xcb::Result<x::Atom> {
+    let name = format!("_NET_SYSTEM_TRAY_S{}", screen_nbr);
+
+    Ok(connection
+        .wait_for_reply(connection.send_request(&x::InternAtom {
+            only_if_exists: false,
+            name: name.as_bytes(),
+        }))?
+        .atom())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrayEvent {
+    /// An icon asked to be docked, see [`TrayHost::embed`].
+    DockRequest(x::Window),
+    /// A docked icon was destroyed or reparented away from the tray.
+    Undocked(x::Window),
+    /// An icon sent a balloon message, reassembled from its `_NET_SYSTEM_TRAY_MESSAGE_DATA`
+    /// chunks.
+    Message {
+        icon: x::Window,
+        id: u32,
+        timeout: u32,
+        text: String,
+    },
+    /// An icon canceled a balloon message.
+    CancelMessage { icon: x::Window, id: u32 },
+    /// Another tray took the selection over.
+    SelectionLost,
+}
+
+struct PendingMessage {
+    id: u32,
+    timeout: u32,
+    length: usize,
+    data: Vec<u8>,
+}
+
+/// The tray side of the protocol.
+pub struct TrayHost<'a> {
+    connection: &'a xcb::Connection,
+    xembed: XEmbed<'a>,
+    atoms: Atoms,
+    manager: ManagerSelection<'a>,
+    icons: Vec<Embedder>,
+    messages: HashMap<x::Window, PendingMessage>,
+}
+
+impl<'a> TrayHost<'a> {
+    /// Makes `window` the owner of the tray selection of `screen_nbr` and announces it.
+    ///
+    /// `replace` and `events` are passed to [`ManagerSelection::acquire`], returns `None` if
+    /// another tray kept the selection.
+    pub fn acquire(
+        connection: &'a xcb::Connection,
+        screen_nbr: i32,
+        window: x::Window,
+        replace: Option<Duration>,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<Self>> {
+        let selection = selection_atom(connection, screen_nbr)?;
+
+        let manager = match ManagerSelection::acquire(
+            connection, screen_nbr, selection, window, replace, events,
+        )? {
+            Some(manager) => manager,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            connection,
+            xembed: XEmbed::new(connection)?,
+            atoms: Atoms::intern_all(connection)?,
+            manager,
+            icons: Vec::new(),
+            messages: HashMap::new(),
+        }))
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    pub fn xembed(&self) -> &XEmbed<'a> {
+        &self.xembed
+    }
+
+    pub fn manager(&self) -> &ManagerSelection<'a> {
+        &self.manager
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.manager.window()
+    }
+
+    pub fn selection(&self) -> x::Atom {
+        self.manager.selection()
+    }
+
+    /// The docked icons.
+    pub fn icons(&self) -> impl Iterator<Item = x::Window> + '_ {
+        self.icons.iter().map(|icon| icon.client())
+    }
+
+    pub fn set_orientation(&self, orientation: Orientation) -> xcb::VoidCookie {
+        self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: self.manager.window(),
+            property: self.atoms.orientation,
+            r#type: x::ATOM_CARDINAL,
+            data: &[orientation as u32],
+        })
+    }
+
+    /// Advertises the visual icons should use, typically an ARGB one for transparent icons.
+    pub fn set_visual(&self, visual: x::Visualid) -> xcb::VoidCookie {
+        self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: self.manager.window(),
+            property: self.atoms.visual,
+            r#type: x::ATOM_VISUALID,
+            data: &[visual],
+        })
+    }
+
+    /// Embeds `icon` into `container` with XEmbed, see [`Embedder::embed`].
+    pub fn embed(&mut self, icon: x::Window, container: x::Window) -> xcb::Result<()> {
+        let embedder = Embedder::embed(&self.xembed, container, icon, x::CURRENT_TIME)?;
+
+        self.icons.retain(|i| i.client() != icon);
+        self.icons.push(embedder);
+
+        Ok(())
+    }
+
+    fn undock(&mut self, icon: x::Window) -> Option<TrayEvent> {
+        let index = self.icons.iter().position(|i| i.client() == icon)?;
+
+        self.icons.remove(index);
+        self.messages.remove(&icon);
+
+        Some(TrayEvent::Undocked(icon))
+    }
+
+    /// Handles an event received by the tray, returning what happened if it is relevant to the
+    /// protocol.
+    ///
+    /// Icons are mapped and unmapped following their `_XEMBED_INFO` as a side effect.
+    pub fn handle_event(&mut self, event: &xcb::Event) -> xcb::Result<Option<TrayEvent>> {
+        let mut undocked = None;
+
+        for icon in &mut self.icons {
+            if let Some(Transition::Unembedded) = icon.handle_event(&self.xembed, event)? {
+                undocked = Some(icon.client());
+            }
+        }
+
+        if let Some(icon) = undocked {
+            return Ok(self.undock(icon));
+        }
+
+        Ok(match event {
+            xcb::Event::X(x::Event::ClientMessage(ev)) => self.handle_client_message(ev),
+            event if self.manager.is_lost(event) => Some(TrayEvent::SelectionLost),
+            _ => None,
+        })
+    }
+
+    fn handle_client_message(&mut self, ev: &x::ClientMessageEvent) -> Option<TrayEvent> {
+        match ev.data() {
+            x::ClientMessageData::Data32(data) if ev.r#type() == self.atoms.opcode => {
+                handle_opcode(&mut self.messages, ev.window(), data)
+            }
+            x::ClientMessageData::Data8(data) if ev.r#type() == self.atoms.message_data => {
+                handle_message_data(&mut self.messages, ev.window(), &data)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Decodes a `_NET_SYSTEM_TRAY_OPCODE` message sent by `window`.
+fn handle_opcode(
+    messages: &mut HashMap<x::Window, PendingMessage>,
+    window: x::Window,
+    data: [u32; 5],
+) -> Option<TrayEvent> {
+    match data[1] {
+        REQUEST_DOCK => Some(TrayEvent::DockRequest(x::Window::new(data[2]))),
+        BEGIN_MESSAGE => {
+            let length = data[3] as usize;
+
+            if length > MAX_MESSAGE_LENGTH {
+                messages.remove(&window);
+                return None;
+            }
+
+            let message = PendingMessage {
+                id: data[4],
+                timeout: data[2],
+                length,
+                data: Vec::with_capacity(length),
+            };
+
+            if message.length == 0 {
+                return Some(TrayEvent::Message {
+                    icon: window,
+                    id: message.id,
+                    timeout: message.timeout,
+                    text: String::new(),
+                });
+            }
+
+            messages.insert(window, message);
+
+            None
+        }
+        CANCEL_MESSAGE => {
+            if messages.get(&window).is_some_and(|m| m.id == data[2]) {
+                messages.remove(&window);
+            }
+
+            Some(TrayEvent::CancelMessage {
+                icon: window,
+                id: data[2],
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Appends a `_NET_SYSTEM_TRAY_MESSAGE_DATA` chunk sent by `window` to its balloon message.
+fn handle_message_data(
+    messages: &mut HashMap<x::Window, PendingMessage>,
+    window: x::Window,
+    data: &[u8],
+) -> Option<TrayEvent> {
+    let message = messages.get_mut(&window)?;
+    let remaining = message.length - message.data.len();
+
+    message
+        .data
+        .extend_from_slice(&data[..remaining.min(data.len())]);
+
+    if message.data.len() < message.length {
+        return None;
+    }
+
+    let message = messages.remove(&window)?;
+
+    Some(TrayEvent::Message {
+        icon: window,
+        id: message.id,
+        timeout: message.timeout,
+        text: String::from_utf8_lossy(&message.data).into_owned(),
+    })
+}
+
+/// The icon side of the protocol.
+pub struct TrayClient<'a> {
+    connection: &'a xcb::Connection,
+    xembed: XEmbed<'a>,
+    atoms: Atoms,
+    manager_atoms: manager::Atoms,
+    selection: x::Atom,
+    root: x::Window,
+}
+
+impl<'a> TrayClient<'a> {
+    pub fn new(connection: &'a xcb::Connection, screen_nbr: i32) -> xcb::Result<Option<Self>> {
+        let root = match root(connection, screen_nbr) {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            connection,
+            xembed: XEmbed::new(connection)?,
+            atoms: Atoms::intern_all(connection)?,
+            manager_atoms: manager::Atoms::intern_all(connection)?,
+            selection: selection_atom(connection, screen_nbr)?,
+            root,
+        }))
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    /// The window of the running tray, if any.
+    pub fn find_tray(&self) -> xcb::Result<Option<x::Window>> {
+        manager::owner(self.connection, self.selection)
+    }
+
+    /// Selects `StructureNotify` on the root window so that the `MANAGER` message of a tray
+    /// starting later is received, see [`TrayClient::tray_started`].
+    pub fn watch(&self) -> xcb::VoidCookie {
+        self.connection.send_request(&x::ChangeWindowAttributes {
+            window: self.root,
+            value_list: &[x::Cw::EventMask(x::EventMask::STRUCTURE_NOTIFY)],
+        })
+    }
+
+    /// The window of the tray announced by `event`, if it is a `MANAGER` message for the tray
+    /// selection.
+    pub fn tray_started(&self, event: &x::ClientMessageEvent) -> Option<x::Window> {
+        Announcement::parse(&self.manager_atoms, event)
+            .filter(|announcement| announcement.selection == self.selection)
+            .map(|announcement| announcement.owner)
+    }
+
+    pub fn orientation(&self, tray: x::Window) -> xcb::Result<Option<Orientation>> {
+        let reply = self
+            .connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window: tray,
+                property: self.atoms.orientation,
+                r#type: x::ATOM_CARDINAL,
+                long_offset: 0,
+                long_length: 1,
+            }))?;
+
+        Ok(match values(&reply) {
+            [orientation, ..] => Some(Orientation::from(*orientation)),
+            _ => None,
+        })
+    }
+
+    pub fn visual(&self, tray: x::Window) -> xcb::Result<Option<x::Visualid>> {
+        let reply = self
+            .connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window: tray,
+                property: self.atoms.visual,
+                r#type: x::ATOM_VISUALID,
+                long_offset: 0,
+                long_length: 1,
+            }))?;
+
+        Ok(match values(&reply) {
+            [visual, ..] => Some(*visual),
+            _ => None,
+        })
+    }
+
+    pub fn xembed(&self) -> &XEmbed<'a> {
+        &self.xembed
+    }
+
+    fn send_opcode(&self, tray: x::Window, window: x::Window, data: [u32; 5]) -> xcb::VoidCookie {
+        self.connection.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(tray),
+            event_mask: x::EventMask::NO_EVENT,
+            event: &x::ClientMessageEvent::new(
+                window,
+                self.atoms.opcode,
+                x::ClientMessageData::Data32(data),
+            ),
+        })
+    }
+
+    /// Asks `tray` to dock `icon`, whose `_XEMBED_INFO` should have been set with
+    /// [`xembed::Client::new`](crate::xembed::Client::new) beforehand.
+    pub fn dock(&self, tray: x::Window, icon: x::Window) -> xcb::VoidCookie {
+        self.send_opcode(
+            tray,
+            tray,
+            [x::CURRENT_TIME, REQUEST_DOCK, icon.resource_id(), 0, 0],
+        )
+    }
+
+    /// Shows a balloon message for `icon`, `timeout` is in milliseconds, zero meaning no timeout.
+    pub fn send_message(
+        &self,
+        tray: x::Window,
+        icon: x::Window,
+        id: u32,
+        timeout: u32,
+        text: &str,
+    ) -> xcb::VoidCookie {
+        let mut cookie = self.send_opcode(
+            tray,
+            icon,
+            [
+                x::CURRENT_TIME,
+                BEGIN_MESSAGE,
+                timeout,
+                text.len() as u32,
+                id,
+            ],
+        );
+
+        for chunk in text.as_bytes().chunks(20) {
+            let mut data = [0; 20];
+            data[..chunk.len()].copy_from_slice(chunk);
+
+            cookie = self.connection.send_request(&x::SendEvent {
+                propagate: false,
+                destination: x::SendEventDest::Window(tray),
+                event_mask: x::EventMask::NO_EVENT,
+                event: &x::ClientMessageEvent::new(
+                    icon,
+                    self.atoms.message_data,
+                    x::ClientMessageData::Data8(data),
+                ),
+            });
+        }
+
+        cookie
+    }
+
+    pub fn cancel_message(&self, tray: x::Window, icon: x::Window, id: u32) -> xcb::VoidCookie {
+        self.send_opcode(tray, icon, [x::CURRENT_TIME, CANCEL_MESSAGE, id, 0, 0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn icon() -> x::Window {
+        x::Window::new(0x0060_0001)
+    }
+
+    fn chunk(text: &[u8]) -> [u8; 20] {
+        let mut data = [0; 20];
+        data[..text.len()].copy_from_slice(text);
+        data
+    }
+
+    #[test]
+    fn dock_request() {
+        let mut messages = HashMap::new();
+
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, REQUEST_DOCK, 0x0060_0002, 0, 0]),
+            Some(TrayEvent::DockRequest(x::Window::new(0x0060_0002)))
+        );
+        assert_eq!(handle_opcode(&mut messages, icon(), [0, 3, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn balloon_message() {
+        let mut messages = HashMap::new();
+        let text = b"Battery low, 10 minutes remaining";
+
+        assert_eq!(
+            handle_opcode(
+                &mut messages,
+                icon(),
+                [0, BEGIN_MESSAGE, 5000, text.len() as u32, 7],
+            ),
+            None
+        );
+        assert_eq!(
+            handle_message_data(&mut messages, icon(), &chunk(&text[..20])),
+            None
+        );
+        assert_eq!(
+            handle_message_data(&mut messages, icon(), &chunk(&text[20..])),
+            Some(TrayEvent::Message {
+                icon: icon(),
+                id: 7,
+                timeout: 5000,
+                text: "Battery low, 10 minutes remaining".to_owned(),
+            })
+        );
+        assert!(messages.is_empty());
+
+        // Data without a message in progress is ignored.
+        assert_eq!(
+            handle_message_data(&mut messages, icon(), &chunk(b"stray")),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_balloon_message() {
+        let mut messages = HashMap::new();
+
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, BEGIN_MESSAGE, 0, 0, 1]),
+            Some(TrayEvent::Message {
+                icon: icon(),
+                id: 1,
+                timeout: 0,
+                text: String::new(),
+            })
+        );
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn overlong_balloon_message() {
+        let mut messages = HashMap::new();
+        let length = MAX_MESSAGE_LENGTH as u32;
+
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, BEGIN_MESSAGE, 0, length, 1]),
+            None
+        );
+        assert_eq!(messages[&icon()].length, MAX_MESSAGE_LENGTH);
+
+        // A longer message replaces the one in progress and is ignored.
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, BEGIN_MESSAGE, 0, length + 1, 2]),
+            None
+        );
+        assert!(messages.is_empty());
+        assert_eq!(
+            handle_message_data(&mut messages, icon(), &chunk(b"ignored")),
+            None
+        );
+    }
+
+    #[test]
+    fn cancel_message() {
+        let mut messages = HashMap::new();
+
+        handle_opcode(&mut messages, icon(), [0, BEGIN_MESSAGE, 0, 40, 3]);
+
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, CANCEL_MESSAGE, 4, 0, 0]),
+            Some(TrayEvent::CancelMessage {
+                icon: icon(),
+                id: 4
+            })
+        );
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            handle_opcode(&mut messages, icon(), [0, CANCEL_MESSAGE, 3, 0, 0]),
+            Some(TrayEvent::CancelMessage {
+                icon: icon(),
+                id: 3
+            })
+        );
+        assert!(messages.is_empty());
+    }
+}
@@ -1,482 +1,104 @@
-macro_rules! define {
-    (cookie $cookie:ident for $inner:ident => $reply:ident) => {
-        pub struct $cookie(
-            x::GetPropertyCookie,
-            unsafe extern "C" fn(
-                *mut xcb_connection_t,
-                xcb_get_property_cookie_t,
-                *mut $inner,
-                *mut *mut xcb_generic_error_t,
-            ) -> u8,
-        );
-
-        #[cfg(feature = "thread")]
-        unsafe impl Send for $cookie {}
-        #[cfg(feature = "thread")]
-        unsafe impl Sync for $cookie {}
-
-        impl $cookie {
-            pub fn get_reply(&self) -> Option<$reply> {
-                unsafe {
-                    if self.0.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res = self.1(
-                            self.0.conn.get_raw_conn(),
-                            self.0.cookie,
-                            &mut reply,
-                            &mut err,
-                        );
-
-                        if err.is_null() && res != 0 {
-                            Some($reply(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        self.1(
-                            self.0.conn.get_raw_conn(),
-                            self.0.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some($reply(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident with $func:ident => $reply:ident) => {
-        pub struct $cookie(x::GetPropertyCookie);
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl $cookie {
-            pub fn get_reply(&self) -> Option<$reply> {
-                unsafe {
-                    if self.0.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res = $func(
-                            self.0.conn.get_raw_conn(),
-                            self.0.cookie,
-                            &mut reply,
-                            &mut err,
-                        );
-
-                        if err.is_null() && res != 0 {
-                            Some($reply(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.0.conn.get_raw_conn(),
-                            self.0.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some($reply(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident => $reply:ident) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<$reply> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some($reply(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some($reply(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident as ($first:path, $second:path)) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<($first, $second)> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut first = mem::zeroed();
-                        let mut second = mem::zeroed();
-                        let res = $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut first,
-                            &mut second,
-                            &mut err,
-                        );
-
-                        if err.is_null() && res != 0 {
-                            Some((first, second))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut first = mem::zeroed();
-                        let mut second = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut first,
-                            &mut second,
-                            ptr::null_mut(),
-                        );
-
-                        Some((first, second))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident as u32) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<u32> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some(reply)
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some(reply)
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident as u64) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<u64> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some(reply)
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some(reply)
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident as x::Window) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<x::Window> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some(x::Window::new(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some(x::Window::new(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident through $conn:ident with $func:ident as $reply:path) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: xcb_get_property_cookie_t,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<$reply> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some($reply(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some($reply(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (cookie $cookie:ident($inner:path) through $conn:ident with $func:ident as $reply:path) => {
-        pub struct $cookie<'a> {
-            conn: &'a $conn,
-            cookie: $inner,
-            checked: bool,
-        }
-
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Send for $cookie<'a> {}
-        #[cfg(feature = "thread")]
-        unsafe impl<'a> Sync for $cookie<'a> {}
-
-        impl<'a> $cookie<'a> {
-            pub fn get_reply(&self) -> Option<$reply> {
-                unsafe {
-                    if self.checked {
-                        let mut err: *mut xcb_generic_error_t = ptr::null_mut();
-                        let mut reply = mem::zeroed();
-                        let res =
-                            $func(self.conn.get_raw_conn(), self.cookie, &mut reply, &mut err);
-
-                        if err.is_null() && res != 0 {
-                            Some(x::Window::new(reply))
-                        } else {
-                            None
-                        }
-                    } else {
-                        let mut reply = mem::zeroed();
-                        $func(
-                            self.conn.get_raw_conn(),
-                            self.cookie,
-                            &mut reply,
-                            ptr::null_mut(),
-                        );
-
-                        Some(x::Window::new(reply))
-                    }
-                }
-            }
-        }
-    };
-
-    (reply $reply:ident for $inner:ident with $wipe:ident) => {
-        pub struct $reply($inner);
-
-        impl Drop for $reply {
-            fn drop(&mut self) {
-                unsafe {
-                    $wipe(&mut self.0);
-                }
-            }
-        }
-    };
-
-    (reply $reply:ident for $inner:ident) => {
-        pub struct $reply($inner);
-    };
-}
-
-macro_rules! void {
-    (checked, $cookie:expr) => {
-        unsafe { xcb::VoidCookieChecked::from_sequence($cookie.sequence as u64) }
-    };
-
-    (unchecked, $cookie:expr) => {
-        unsafe { xcb::VoidCookie::from_sequence($cookie.sequence as u64) }
-    };
+#[cfg(any(
+    feature = "icccm",
+    feature = "icccm-native",
+    feature = "ewmh",
+    feature = "ewmh-native"
+))]
+pub mod gravity {
+    use xcb::x;
+
+    /// How much a frame with the given extents is moved from the client position to keep the
+    /// reference point of `gravity` in place, as described in ICCCM 4.1.2.3.
+    ///
+    /// `border_width` is the border of the unframed client. `BitForget` is not a window gravity
+    /// and is handled as `NorthWest`, its default.
+    pub fn offset(
+        gravity: x::Gravity,
+        border_width: i64,
+        (left, right, top, bottom): (i64, i64, i64, i64),
+    ) -> (i64, i64) {
+        let border = 2 * border_width;
+        let horizontal = left + right;
+        let vertical = top + bottom;
+
+        let x = match gravity {
+            x::Gravity::BitForget
+            | x::Gravity::NorthWest
+            | x::Gravity::West
+            | x::Gravity::SouthWest => 0,
+            x::Gravity::North | x::Gravity::Center | x::Gravity::South => (border - horizontal) / 2,
+            x::Gravity::NorthEast | x::Gravity::East | x::Gravity::SouthEast => border - horizontal,
+            x::Gravity::Static => border_width - left,
+        };
+
+        let y = match gravity {
+            x::Gravity::BitForget
+            | x::Gravity::NorthWest
+            | x::Gravity::North
+            | x::Gravity::NorthEast => 0,
+            x::Gravity::West | x::Gravity::Center | x::Gravity::East => (border - vertical) / 2,
+            x::Gravity::SouthWest | x::Gravity::South | x::Gravity::SouthEast => border - vertical,
+            x::Gravity::Static => border_width - top,
+        };
+
+        (x, y)
+    }
 }
 
-macro_rules! property {
-    (checked $name:ident -> $conn:expr, $cookie:expr) => {
-        unsafe {
-            $name {
-                conn: $conn,
-                cookie: $cookie,
-                checked: true,
-            }
-        }
-    };
+pub mod reply {
+    extern "C" {
+        fn xcb_discard_reply64(c: *mut xcb::ffi::xcb_connection_t, sequence: u64);
+    }
 
-    (unchecked $name:ident -> $conn:expr, $cookie:expr) => {
+    /// Tells the connection that the reply of `cookie` will not be waited for, so that it does
+    /// not pile up in the queue. Unlike waiting for it, this does not block.
+    pub fn discard<C: xcb::Cookie>(connection: &xcb::Connection, cookie: C) {
         unsafe {
-            $name {
-                conn: $conn,
-                cookie: $cookie,
-                checked: false,
-            }
+            xcb_discard_reply64(connection.get_raw_conn(), cookie.sequence());
         }
-    };
+    }
 }
 
-pub mod utf8 {
-    use std::ffi::c_char;
-    use std::slice;
-    use std::str;
+#[cfg(any(feature = "manager", feature = "selection"))]
+pub mod event {
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    use libc;
+
+    /// Waits for the next event, or returns `None` once `deadline` is reached.
+    ///
+    /// Pending requests are flushed first, and the connection is polled rather than spun on.
+    pub fn wait_until(
+        connection: &xcb::Connection,
+        deadline: Instant,
+    ) -> xcb::Result<Option<xcb::Event>> {
+        connection.flush()?;
+
+        loop {
+            if let Some(event) = connection.poll_for_event()? {
+                return Ok(Some(event));
+            }
 
-    pub fn into<'a>(data: *const c_char, length: u32) -> Vec<&'a str> {
-        if length == 0 {
-            return Vec::new();
-        }
+            let now = Instant::now();
 
-        unsafe {
-            let mut result =
-                str::from_utf8_unchecked(slice::from_raw_parts(data as *mut u8, length as usize))
-                    .split('\0')
-                    .collect::<Vec<_>>();
-
-            // Data is sometimes NULL-terminated and sometimes not. If there is a
-            // NULL terminator, then our call to .split() will result in an extra
-            // empty-string element at the end, so pop it.
-            if let Some(&"") = result.last() {
-                result.pop();
+            if now >= deadline {
+                return Ok(None);
             }
 
-            result
-        }
-    }
+            // Rounded up, so that the last millisecond is not spent spinning.
+            let timeout = (deadline - now).as_micros().div_ceil(1000);
 
-    pub fn from<'a, T: IntoIterator<Item = &'a str>>(data: T) -> Vec<u8> {
-        let mut result = String::new();
+            let mut fd = libc::pollfd {
+                fd: connection.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
 
-        for item in data {
-            result.push_str(item);
-            result.push('\0');
+            // Errors, including interruptions, are left for the next poll of the connection.
+            unsafe {
+                libc::poll(&mut fd, 1, timeout.min(i32::MAX as u128) as libc::c_int);
+            }
         }
-
-        result.into_bytes()
     }
 }
@@ -0,0 +1,870 @@
+//! The XEmbed protocol, used to embed windows of another client such as tray icons or plugins.
+//!
+//! [`XEmbed`] sends and parses `_XEMBED` messages and handles the `_XEMBED_INFO` property, while
+//! [`Embedder`] and [`Client`] track the state of each side of an embedding.
+
+use xcb::{x, Xid, XidNew};
+
+/// Version of the protocol implemented.
+pub const XEMBED_VERSION: u32 = 0;
+
+pub const XEMBED_EMBEDDED_NOTIFY: u32 = 0;
+pub const XEMBED_WINDOW_ACTIVATE: u32 = 1;
+pub const XEMBED_WINDOW_DEACTIVATE: u32 = 2;
+pub const XEMBED_REQUEST_FOCUS: u32 = 3;
+pub const XEMBED_FOCUS_IN: u32 = 4;
+pub const XEMBED_FOCUS_OUT: u32 = 5;
+pub const XEMBED_FOCUS_NEXT: u32 = 6;
+pub const XEMBED_FOCUS_PREV: u32 = 7;
+pub const XEMBED_MODALITY_ON: u32 = 10;
+pub const XEMBED_MODALITY_OFF: u32 = 11;
+pub const XEMBED_REGISTER_ACCELERATOR: u32 = 12;
+pub const XEMBED_UNREGISTER_ACCELERATOR: u32 = 13;
+pub const XEMBED_ACTIVATE_ACCELERATOR: u32 = 14;
+
+pub const XEMBED_FOCUS_CURRENT: u32 = 0;
+pub const XEMBED_FOCUS_FIRST: u32 = 1;
+pub const XEMBED_FOCUS_LAST: u32 = 2;
+
+const XEMBED_ACCELERATOR_OVERLOADED: u32 = 1 << 0;
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub xembed => b"_XEMBED" only_if_exists = false,
+        pub xembed_info => b"_XEMBED_INFO" only_if_exists = false,
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct InfoFlags: u32 {
+        const Mapped = 1 << 0;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Modifiers: u32 {
+        const Shift = 1 << 0;
+        const Control = 1 << 1;
+        const Alt = 1 << 2;
+        const Super = 1 << 3;
+        const Hyper = 1 << 4;
+    }
+}
+
+/// Contents of the `_XEMBED_INFO` property of a client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Info {
+    pub version: u32,
+    pub flags: InfoFlags,
+}
+
+impl Info {
+    pub fn is_mapped(&self) -> bool {
+        self.flags.contains(InfoFlags::Mapped)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Focus {
+    Current = XEMBED_FOCUS_CURRENT,
+    First = XEMBED_FOCUS_FIRST,
+    Last = XEMBED_FOCUS_LAST,
+}
+
+impl From<u32> for Focus {
+    fn from(value: u32) -> Self {
+        match value {
+            XEMBED_FOCUS_FIRST => Self::First,
+            XEMBED_FOCUS_LAST => Self::Last,
+            _ => Self::Current,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Message {
+    EmbeddedNotify {
+        embedder: x::Window,
+        version: u32,
+    },
+    WindowActivate,
+    WindowDeactivate,
+    RequestFocus,
+    FocusIn(Focus),
+    FocusOut,
+    FocusNext,
+    FocusPrev,
+    ModalityOn,
+    ModalityOff,
+    RegisterAccelerator {
+        id: u32,
+        keysym: u32,
+        modifiers: Modifiers,
+    },
+    UnregisterAccelerator {
+        id: u32,
+    },
+    ActivateAccelerator {
+        id: u32,
+        overloaded: bool,
+    },
+}
+
+impl Message {
+    /// The `detail`, `data1` and `data2` fields of the message, following its opcode.
+    fn encode(&self) -> [u32; 4] {
+        match *self {
+            Self::EmbeddedNotify { embedder, version } => {
+                [XEMBED_EMBEDDED_NOTIFY, 0, embedder.resource_id(), version]
+            }
+            Self::WindowActivate => [XEMBED_WINDOW_ACTIVATE, 0, 0, 0],
+            Self::WindowDeactivate => [XEMBED_WINDOW_DEACTIVATE, 0, 0, 0],
+            Self::RequestFocus => [XEMBED_REQUEST_FOCUS, 0, 0, 0],
+            Self::FocusIn(focus) => [XEMBED_FOCUS_IN, focus as u32, 0, 0],
+            Self::FocusOut => [XEMBED_FOCUS_OUT, 0, 0, 0],
+            Self::FocusNext => [XEMBED_FOCUS_NEXT, 0, 0, 0],
+            Self::FocusPrev => [XEMBED_FOCUS_PREV, 0, 0, 0],
+            Self::ModalityOn => [XEMBED_MODALITY_ON, 0, 0, 0],
+            Self::ModalityOff => [XEMBED_MODALITY_OFF, 0, 0, 0],
+            Self::RegisterAccelerator {
+                id,
+                keysym,
+                modifiers,
+            } => [XEMBED_REGISTER_ACCELERATOR, id, keysym, modifiers.bits()],
+            Self::UnregisterAccelerator { id } => [XEMBED_UNREGISTER_ACCELERATOR, id, 0, 0],
+            Self::ActivateAccelerator { id, overloaded } => [
+                XEMBED_ACTIVATE_ACCELERATOR,
+                id,
+                if overloaded {
+                    XEMBED_ACCELERATOR_OVERLOADED
+                } else {
+                    0
+                },
+                0,
+            ],
+        }
+    }
+
+    fn decode(data: &[u32; 5]) -> Option<Self> {
+        let [_, opcode, detail, data1, data2] = *data;
+
+        Some(match opcode {
+            XEMBED_EMBEDDED_NOTIFY => Self::EmbeddedNotify {
+                embedder: x::Window::new(data1),
+                version: data2,
+            },
+            XEMBED_WINDOW_ACTIVATE => Self::WindowActivate,
+            XEMBED_WINDOW_DEACTIVATE => Self::WindowDeactivate,
+            XEMBED_REQUEST_FOCUS => Self::RequestFocus,
+            XEMBED_FOCUS_IN => Self::FocusIn(Focus::from(detail)),
+            XEMBED_FOCUS_OUT => Self::FocusOut,
+            XEMBED_FOCUS_NEXT => Self::FocusNext,
+            XEMBED_FOCUS_PREV => Self::FocusPrev,
+            XEMBED_MODALITY_ON => Self::ModalityOn,
+            XEMBED_MODALITY_OFF => Self::ModalityOff,
+            XEMBED_REGISTER_ACCELERATOR => Self::RegisterAccelerator {
+                id: detail,
+                keysym: data1,
+                modifiers: Modifiers::from_bits_truncate(data2),
+            },
+            XEMBED_UNREGISTER_ACCELERATOR => Self::UnregisterAccelerator { id: detail },
+            XEMBED_ACTIVATE_ACCELERATOR => Self::ActivateAccelerator {
+                id: detail,
+                overloaded: data1 & XEMBED_ACCELERATOR_OVERLOADED != 0,
+            },
+            _ => return None,
+        })
+    }
+
+    /// The `_XEMBED` client message sending the message to `window`.
+    pub fn to_event(
+        &self,
+        atoms: &Atoms,
+        window: x::Window,
+        time: x::Timestamp,
+    ) -> x::ClientMessageEvent {
+        let [opcode, detail, data1, data2] = self.encode();
+
+        x::ClientMessageEvent::new(
+            window,
+            atoms.xembed,
+            x::ClientMessageData::Data32([time, opcode, detail, data1, data2]),
+        )
+    }
+
+    /// Parses an `_XEMBED` message, returning its time and contents.
+    pub fn parse(atoms: &Atoms, event: &x::ClientMessageEvent) -> Option<(x::Timestamp, Self)> {
+        if event.r#type() != atoms.xembed {
+            return None;
+        }
+
+        match event.data() {
+            x::ClientMessageData::Data32(data) => Some((data[0], Self::decode(&data)?)),
+            _ => None,
+        }
+    }
+}
+
+pub struct XEmbed<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+}
+
+impl<'a> XEmbed<'a> {
+    pub fn new(connection: &'a xcb::Connection) -> xcb::Result<Self> {
+        Ok(Self {
+            connection,
+            atoms: Atoms::intern_all(connection)?,
+        })
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    /// Sends `message` to `window`, `time` should be the time of the event that caused it.
+    pub fn send(
+        &self,
+        window: x::Window,
+        time: x::Timestamp,
+        message: &Message,
+    ) -> xcb::VoidCookie {
+        self.connection.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(window),
+            event_mask: x::EventMask::NO_EVENT,
+            event: &message.to_event(&self.atoms, window, time),
+        })
+    }
+
+    /// Parses an `_XEMBED` message, see [`Message::parse`].
+    pub fn parse(&self, event: &x::ClientMessageEvent) -> Option<(x::Timestamp, Message)> {
+        Message::parse(&self.atoms, event)
+    }
+
+    /// Reads the `_XEMBED_INFO` property of `window`, `None` if it is missing or malformed.
+    pub fn get_info(&self, window: x::Window) -> xcb::Result<Option<Info>> {
+        let reply = self
+            .connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window,
+                property: self.atoms.xembed_info,
+                r#type: self.atoms.xembed_info,
+                long_offset: 0,
+                long_length: 2,
+            }))?;
+
+        if reply.format() != 32 {
+            return Ok(None);
+        }
+
+        Ok(match reply.value::<u32>() {
+            [version, flags, ..] => Some(Info {
+                version: *version,
+                flags: InfoFlags::from_bits_truncate(*flags),
+            }),
+            _ => None,
+        })
+    }
+
+    pub fn set_info(&self, window: x::Window, info: Info) -> xcb::VoidCookie {
+        self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.xembed_info,
+            r#type: self.atoms.xembed_info,
+            data: &[info.version, info.flags.bits()],
+        })
+    }
+}
+
+/// What an [`Embedder`] or a [`Client`] should do after an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transition {
+    /// The client got embedded into `embedder`.
+    Embedded {
+        embedder: x::Window,
+    },
+    /// The client left its embedder.
+    Unembedded,
+    /// The embedder mapped or unmapped the client following its `_XEMBED_INFO`.
+    Mapped(bool),
+    /// The embedder window became active or inactive.
+    Active(bool),
+    /// The client got or lost the focus.
+    Focused(Option<Focus>),
+    /// The client asks for the focus, the embedder should focus its own window and send
+    /// `XEMBED_FOCUS_IN`.
+    RequestFocus,
+    /// The client reached the end of its focus chain and asks to move the focus to the next or
+    /// previous widget of the embedder.
+    FocusNext,
+    FocusPrev,
+    /// The embedder entered or left a modal state.
+    Modal(bool),
+    /// An accelerator message, left to the application.
+    Accelerator(Message),
+}
+
+/// State of the embedder side, one per embedded client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Embedder {
+    container: x::Window,
+    client: x::Window,
+    version: u32,
+    mapped: bool,
+    active: bool,
+    focused: bool,
+}
+
+impl Embedder {
+    /// Reparents `client` into `container`, sends `XEMBED_EMBEDDED_NOTIFY` and maps it unless its
+    /// `_XEMBED_INFO` says otherwise.
+    pub fn embed(
+        xembed: &XEmbed,
+        container: x::Window,
+        client: x::Window,
+        time: x::Timestamp,
+    ) -> xcb::Result<Self> {
+        xembed.connection.send_request(&x::ChangeWindowAttributes {
+            window: client,
+            value_list: &[x::Cw::EventMask(
+                x::EventMask::STRUCTURE_NOTIFY | x::EventMask::PROPERTY_CHANGE,
+            )],
+        });
+
+        xembed.connection.send_request(&x::ReparentWindow {
+            window: client,
+            parent: container,
+            x: 0,
+            y: 0,
+        });
+
+        let info = xembed.get_info(client)?.unwrap_or(Info {
+            version: XEMBED_VERSION,
+            flags: InfoFlags::Mapped,
+        });
+
+        // The version sent is the lowest of both sides, which is ours as long as we only implement
+        // the first one.
+        let version = XEMBED_VERSION;
+
+        xembed.send(
+            client,
+            time,
+            &Message::EmbeddedNotify {
+                embedder: container,
+                version,
+            },
+        );
+
+        if info.is_mapped() {
+            xembed
+                .connection
+                .send_request(&x::MapWindow { window: client });
+        }
+
+        Ok(Self {
+            container,
+            client,
+            version,
+            mapped: info.is_mapped(),
+            active: false,
+            focused: false,
+        })
+    }
+
+    pub fn container(&self) -> x::Window {
+        self.container
+    }
+
+    pub fn client(&self) -> x::Window {
+        self.client
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Tells the client whether the toplevel window of the embedder is active.
+    pub fn set_active(
+        &mut self,
+        xembed: &XEmbed,
+        time: x::Timestamp,
+        active: bool,
+    ) -> Option<xcb::VoidCookie> {
+        if self.active == active {
+            return None;
+        }
+
+        self.active = active;
+
+        Some(xembed.send(
+            self.client,
+            time,
+            if active {
+                &Message::WindowActivate
+            } else {
+                &Message::WindowDeactivate
+            },
+        ))
+    }
+
+    /// Moves the logical focus into the client, or out of it with `None`.
+    pub fn set_focus(
+        &mut self,
+        xembed: &XEmbed,
+        time: x::Timestamp,
+        focus: Option<Focus>,
+    ) -> Option<xcb::VoidCookie> {
+        if !self.focused && focus.is_none() {
+            return None;
+        }
+
+        self.focused = focus.is_some();
+
+        Some(xembed.send(
+            self.client,
+            time,
+            &match focus {
+                Some(focus) => Message::FocusIn(focus),
+                None => Message::FocusOut,
+            },
+        ))
+    }
+
+    pub fn set_modal(&self, xembed: &XEmbed, time: x::Timestamp, modal: bool) -> xcb::VoidCookie {
+        xembed.send(
+            self.client,
+            time,
+            if modal {
+                &Message::ModalityOn
+            } else {
+                &Message::ModalityOff
+            },
+        )
+    }
+
+    /// Handles an event received for the client window, or a message sent to the container.
+    pub fn handle_event(
+        &mut self,
+        xembed: &XEmbed,
+        event: &xcb::Event,
+    ) -> xcb::Result<Option<Transition>> {
+        Ok(match event {
+            xcb::Event::X(x::Event::PropertyNotify(ev))
+                if ev.window() == self.client && ev.atom() == xembed.atoms.xembed_info =>
+            {
+                let mapped = xembed
+                    .get_info(self.client)?
+                    .is_none_or(|info| info.is_mapped());
+
+                if mapped == self.mapped {
+                    return Ok(None);
+                }
+
+                self.mapped = mapped;
+
+                if mapped {
+                    xembed.connection.send_request(&x::MapWindow {
+                        window: self.client,
+                    });
+                } else {
+                    xembed.connection.send_request(&x::UnmapWindow {
+                        window: self.client,
+                    });
+                }
+
+                Some(Transition::Mapped(mapped))
+            }
+            xcb::Event::X(x::Event::ClientMessage(ev)) => self.handle_message(&xembed.atoms, ev),
+            xcb::Event::X(x::Event::DestroyNotify(ev)) if ev.window() == self.client => {
+                Some(Transition::Unembedded)
+            }
+            xcb::Event::X(x::Event::ReparentNotify(ev))
+                if ev.window() == self.client && ev.parent() != self.container =>
+            {
+                Some(Transition::Unembedded)
+            }
+            _ => None,
+        })
+    }
+
+    /// Handles a message from the client, which sends them to the container.
+    fn handle_message(&mut self, atoms: &Atoms, ev: &x::ClientMessageEvent) -> Option<Transition> {
+        if ev.window() != self.container {
+            return None;
+        }
+
+        match Message::parse(atoms, ev)?.1 {
+            Message::RequestFocus => Some(Transition::RequestFocus),
+            Message::FocusNext => {
+                self.focused = false;
+                Some(Transition::FocusNext)
+            }
+            Message::FocusPrev => {
+                self.focused = false;
+                Some(Transition::FocusPrev)
+            }
+            message @ (Message::RegisterAccelerator { .. }
+            | Message::UnregisterAccelerator { .. }) => Some(Transition::Accelerator(message)),
+            _ => None,
+        }
+    }
+}
+
+/// State of the client side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Client {
+    window: x::Window,
+    embedder: Option<x::Window>,
+    version: u32,
+    active: bool,
+    focused: Option<Focus>,
+    modal: bool,
+}
+
+impl Client {
+    /// Sets the `_XEMBED_INFO` of `window`, which must be done before it is embedded.
+    pub fn new(xembed: &XEmbed, window: x::Window, mapped: bool) -> Self {
+        let client = Self {
+            window,
+            embedder: None,
+            version: XEMBED_VERSION,
+            active: false,
+            focused: None,
+            modal: false,
+        };
+
+        client.set_mapped(xembed, mapped);
+
+        client
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    pub fn embedder(&self) -> Option<x::Window> {
+        self.embedder
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn focus(&self) -> Option<Focus> {
+        self.focused
+    }
+
+    pub fn is_modal(&self) -> bool {
+        self.modal
+    }
+
+    /// Asks the embedder to map or unmap the window.
+    pub fn set_mapped(&self, xembed: &XEmbed, mapped: bool) -> xcb::VoidCookie {
+        xembed.set_info(
+            self.window,
+            Info {
+                version: XEMBED_VERSION,
+                flags: if mapped {
+                    InfoFlags::Mapped
+                } else {
+                    InfoFlags::empty()
+                },
+            },
+        )
+    }
+
+    fn send(
+        &self,
+        xembed: &XEmbed,
+        time: x::Timestamp,
+        message: &Message,
+    ) -> Option<xcb::VoidCookie> {
+        self.embedder
+            .map(|embedder| xembed.send(embedder, time, message))
+    }
+
+    pub fn request_focus(&self, xembed: &XEmbed, time: x::Timestamp) -> Option<xcb::VoidCookie> {
+        self.send(xembed, time, &Message::RequestFocus)
+    }
+
+    /// Gives the focus back to the embedder when tabbing past the last widget of the client.
+    pub fn focus_next(&mut self, xembed: &XEmbed, time: x::Timestamp) -> Option<xcb::VoidCookie> {
+        self.focused = None;
+        self.send(xembed, time, &Message::FocusNext)
+    }
+
+    /// Gives the focus back to the embedder when tabbing back past the first widget of the client.
+    pub fn focus_prev(&mut self, xembed: &XEmbed, time: x::Timestamp) -> Option<xcb::VoidCookie> {
+        self.focused = None;
+        self.send(xembed, time, &Message::FocusPrev)
+    }
+
+    pub fn register_accelerator(
+        &self,
+        xembed: &XEmbed,
+        time: x::Timestamp,
+        id: u32,
+        keysym: u32,
+        modifiers: Modifiers,
+    ) -> Option<xcb::VoidCookie> {
+        self.send(
+            xembed,
+            time,
+            &Message::RegisterAccelerator {
+                id,
+                keysym,
+                modifiers,
+            },
+        )
+    }
+
+    pub fn unregister_accelerator(
+        &self,
+        xembed: &XEmbed,
+        time: x::Timestamp,
+        id: u32,
+    ) -> Option<xcb::VoidCookie> {
+        self.send(xembed, time, &Message::UnregisterAccelerator { id })
+    }
+
+    /// Handles an event received for the client window.
+    pub fn handle_event(&mut self, xembed: &XEmbed, event: &xcb::Event) -> Option<Transition> {
+        match event {
+            xcb::Event::X(x::Event::ClientMessage(ev)) if ev.window() == self.window => {
+                let (_, message) = xembed.parse(ev)?;
+
+                match message {
+                    Message::EmbeddedNotify { embedder, version } => {
+                        self.embedder = Some(embedder);
+                        self.version = version;
+
+                        Some(Transition::Embedded { embedder })
+                    }
+                    Message::WindowActivate | Message::WindowDeactivate => {
+                        self.active = message == Message::WindowActivate;
+
+                        Some(Transition::Active(self.active))
+                    }
+                    Message::FocusIn(focus) => {
+                        self.focused = Some(focus);
+
+                        Some(Transition::Focused(Some(focus)))
+                    }
+                    Message::FocusOut => {
+                        self.focused = None;
+
+                        Some(Transition::Focused(None))
+                    }
+                    Message::ModalityOn | Message::ModalityOff => {
+                        self.modal = message == Message::ModalityOn;
+
+                        Some(Transition::Modal(self.modal))
+                    }
+                    Message::ActivateAccelerator { .. } => Some(Transition::Accelerator(message)),
+                    _ => None,
+                }
+            }
+            xcb::Event::X(x::Event::ReparentNotify(ev))
+                if ev.window() == self.window
+                    && self.embedder.is_some()
+                    && Some(ev.parent()) != self.embedder =>
+            {
+                *self = Self {
+                    embedder: None,
+                    active: false,
+                    focused: None,
+                    modal: false,
+                    ..*self
+                };
+
+                Some(Transition::Unembedded)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atoms() -> Atoms {
+        Atoms {
+            xembed: x::Atom::new(300),
+            xembed_info: x::Atom::new(301),
+        }
+    }
+
+    fn embedder() -> Embedder {
+        Embedder {
+            container: x::Window::new(0x0020_0001),
+            client: x::Window::new(0x0040_0001),
+            version: XEMBED_VERSION,
+            mapped: true,
+            active: true,
+            focused: true,
+        }
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let messages = [
+            Message::EmbeddedNotify {
+                embedder: x::Window::new(0x0020_0001),
+                version: 1,
+            },
+            Message::WindowActivate,
+            Message::WindowDeactivate,
+            Message::RequestFocus,
+            Message::FocusIn(Focus::Current),
+            Message::FocusIn(Focus::First),
+            Message::FocusIn(Focus::Last),
+            Message::FocusOut,
+            Message::FocusNext,
+            Message::FocusPrev,
+            Message::ModalityOn,
+            Message::ModalityOff,
+            Message::RegisterAccelerator {
+                id: 7,
+                keysym: 0x61,
+                modifiers: Modifiers::Control | Modifiers::Alt,
+            },
+            Message::UnregisterAccelerator { id: 7 },
+            Message::ActivateAccelerator {
+                id: 7,
+                overloaded: true,
+            },
+            Message::ActivateAccelerator {
+                id: 8,
+                overloaded: false,
+            },
+        ];
+
+        for message in messages {
+            let [opcode, detail, data1, data2] = message.encode();
+
+            assert_eq!(
+                Message::decode(&[42, opcode, detail, data1, data2]),
+                Some(message)
+            );
+
+            let event = message.to_event(&atoms(), x::Window::new(0x0040_0001), 42);
+
+            assert_eq!(Message::parse(&atoms(), &event), Some((42, message)));
+        }
+    }
+
+    #[test]
+    fn message_fixtures() {
+        assert_eq!(
+            Message::EmbeddedNotify {
+                embedder: x::Window::new(0x0020_0001),
+                version: 0,
+            }
+            .encode(),
+            [XEMBED_EMBEDDED_NOTIFY, 0, 0x0020_0001, 0]
+        );
+        assert_eq!(
+            Message::decode(&[0, XEMBED_REGISTER_ACCELERATOR, 3, 0x61, 0xff]),
+            Some(Message::RegisterAccelerator {
+                id: 3,
+                keysym: 0x61,
+                modifiers: Modifiers::all(),
+            })
+        );
+        assert_eq!(
+            Message::decode(&[0, XEMBED_FOCUS_IN, 9, 0, 0]),
+            Some(Message::FocusIn(Focus::Current))
+        );
+        assert_eq!(Message::decode(&[0, 8, 0, 0, 0]), None);
+        assert_eq!(Message::decode(&[0, 15, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn parse_rejects_other_messages() {
+        let window = x::Window::new(0x0040_0001);
+        let event = x::ClientMessageEvent::new(
+            window,
+            x::Atom::new(302),
+            x::ClientMessageData::Data32([0, XEMBED_FOCUS_OUT, 0, 0, 0]),
+        );
+
+        assert_eq!(Message::parse(&atoms(), &event), None);
+
+        let event = x::ClientMessageEvent::new(
+            window,
+            atoms().xembed,
+            x::ClientMessageData::Data8([0; 20]),
+        );
+
+        assert_eq!(Message::parse(&atoms(), &event), None);
+    }
+
+    #[test]
+    fn embedder_handles_client_messages() {
+        let mut embedder = embedder();
+
+        // Client::send addresses its messages to the embedder, which is the container window.
+        let event = Message::RequestFocus.to_event(&atoms(), embedder.container(), 0);
+
+        assert_eq!(
+            embedder.handle_message(&atoms(), &event),
+            Some(Transition::RequestFocus)
+        );
+
+        let event = Message::FocusNext.to_event(&atoms(), embedder.container(), 0);
+
+        assert_eq!(
+            embedder.handle_message(&atoms(), &event),
+            Some(Transition::FocusNext)
+        );
+        assert!(!embedder.is_focused());
+
+        let accelerator = Message::UnregisterAccelerator { id: 1 };
+        let event = accelerator.to_event(&atoms(), embedder.container(), 0);
+
+        assert_eq!(
+            embedder.handle_message(&atoms(), &event),
+            Some(Transition::Accelerator(accelerator))
+        );
+    }
+
+    #[test]
+    fn embedder_ignores_messages_for_other_windows() {
+        let mut embedder = embedder();
+
+        // Messages the embedder sends itself go to the client window.
+        let event = Message::FocusPrev.to_event(&atoms(), embedder.client(), 0);
+
+        assert_eq!(embedder.handle_message(&atoms(), &event), None);
+        assert!(embedder.is_focused());
+
+        let event = Message::WindowActivate.to_event(&atoms(), embedder.container(), 0);
+
+        assert_eq!(embedder.handle_message(&atoms(), &event), None);
+    }
+}
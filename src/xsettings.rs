@@ -0,0 +1,773 @@
+//! The XSETTINGS protocol, used by desktop environments to share toolkit settings such as the
+//! theme, the DPI or the cursor size.
+//!
+//! Settings are published by the owner of the `_XSETTINGS_Sn` selection in its
+//! `_XSETTINGS_SETTINGS` property. [`SettingsClient`] reads and watches them, [`SettingsManager`]
+//! publishes them.
+
+use std::{collections::BTreeMap, convert::TryFrom, time::Duration};
+
+use xcb::x;
+
+use manager::{self, Announcement, ManagerSelection};
+
+const TYPE_INTEGER: u8 = 0;
+const TYPE_STRING: u8 = 1;
+const TYPE_COLOR: u8 = 2;
+
+xcb::atoms_struct! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct Atoms {
+        pub settings => b"_XSETTINGS_SETTINGS" only_if_exists = false,
+    }
+}
+
+/// Interns the `_XSETTINGS_Sn` selection atom of `screen_nbr`.
+pub fn selection_atom(connection: &xcb::Connection, screen_nbr: i32) -> xcb::Result<x::Atom> {
+    let name = format!("_XSETTINGS_S{}", screen_nbr);
+
+    Ok(connection
+        .wait_for_reply(connection.send_request(&x::InternAtom {
+            only_if_exists: false,
+            name: name.as_bytes(),
+        }))?
+        .atom())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    LsbFirst,
+    MsbFirst,
+}
+
+impl ByteOrder {
+    pub fn native() -> Self {
+        if cfg!(target_endian = "little") {
+            Self::LsbFirst
+        } else {
+            Self::MsbFirst
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub alpha: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Value {
+    Integer(i32),
+    String(String),
+    Color(Color),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Setting {
+    pub value: Value,
+    /// Serial of the settings when this one last changed.
+    pub last_change_serial: u32,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    byte_order: ByteOrder,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.data.len() < length {
+            return None;
+        }
+
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+
+        Some(bytes)
+    }
+
+    /// Reads `length` bytes followed by the padding to a multiple of 4.
+    fn padded(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes(length)?;
+        self.bytes((4 - length % 4) % 4)?;
+
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = [self.u8()?, self.u8()?];
+
+        Some(match self.byte_order {
+            ByteOrder::LsbFirst => u16::from_le_bytes(bytes),
+            ByteOrder::MsbFirst => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = [self.u8()?, self.u8()?, self.u8()?, self.u8()?];
+
+        Some(match self.byte_order {
+            ByteOrder::LsbFirst => u32::from_le_bytes(bytes),
+            ByteOrder::MsbFirst => u32::from_be_bytes(bytes),
+        })
+    }
+}
+
+struct Writer {
+    data: Vec<u8>,
+    byte_order: ByteOrder,
+}
+
+impl Writer {
+    fn padded(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+        self.data
+            .resize(self.data.len() + (4 - bytes.len() % 4) % 4, 0);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        match self.byte_order {
+            ByteOrder::LsbFirst => self.data.extend_from_slice(&value.to_le_bytes()),
+            ByteOrder::MsbFirst => self.data.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        match self.byte_order {
+            ByteOrder::LsbFirst => self.data.extend_from_slice(&value.to_le_bytes()),
+            ByteOrder::MsbFirst => self.data.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// The contents of a `_XSETTINGS_SETTINGS` property.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Settings {
+    serial: u32,
+    settings: BTreeMap<String, Setting>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the property data, in either byte order.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let byte_order = match *data.first()? {
+            0 => ByteOrder::LsbFirst,
+            1 => ByteOrder::MsbFirst,
+            _ => return None,
+        };
+
+        let mut reader = Reader { data, byte_order };
+        reader.bytes(4)?;
+
+        let serial = reader.u32()?;
+        let count = reader.u32()?;
+
+        let mut settings = BTreeMap::new();
+
+        for _ in 0..count {
+            let kind = reader.u8()?;
+            reader.u8()?;
+
+            let length = reader.u16()? as usize;
+            let name = String::from_utf8_lossy(reader.padded(length)?).into_owned();
+            let last_change_serial = reader.u32()?;
+
+            let value = match kind {
+                TYPE_INTEGER => Value::Integer(reader.u32()? as i32),
+                TYPE_STRING => {
+                    let length = reader.u32()? as usize;
+
+                    Value::String(String::from_utf8_lossy(reader.padded(length)?).into_owned())
+                }
+                TYPE_COLOR => Value::Color(Color {
+                    red: reader.u16()?,
+                    blue: reader.u16()?,
+                    green: reader.u16()?,
+                    alpha: reader.u16()?,
+                }),
+                _ => return None,
+            };
+
+            settings.insert(
+                name,
+                Setting {
+                    value,
+                    last_change_serial,
+                },
+            );
+        }
+
+        Some(Self { serial, settings })
+    }
+
+    /// Encodes the settings as property data, or returns `None` if a name is longer than the
+    /// 65535 bytes the format allows.
+    pub fn encode(&self, byte_order: ByteOrder) -> Option<Vec<u8>> {
+        let mut writer = Writer {
+            data: Vec::new(),
+            byte_order,
+        };
+
+        writer.u8(match byte_order {
+            ByteOrder::LsbFirst => 0,
+            ByteOrder::MsbFirst => 1,
+        });
+        writer.data.extend_from_slice(&[0; 3]);
+        writer.u32(self.serial);
+        writer.u32(self.settings.len() as u32);
+
+        for (name, setting) in &self.settings {
+            writer.u8(match setting.value {
+                Value::Integer(_) => TYPE_INTEGER,
+                Value::String(_) => TYPE_STRING,
+                Value::Color(_) => TYPE_COLOR,
+            });
+            writer.u8(0);
+            writer.u16(u16::try_from(name.len()).ok()?);
+            writer.padded(name.as_bytes());
+            writer.u32(setting.last_change_serial);
+
+            match setting.value {
+                Value::Integer(value) => writer.u32(value as u32),
+                Value::String(ref value) => {
+                    writer.u32(value.len() as u32);
+                    writer.padded(value.as_bytes());
+                }
+                Value::Color(color) => {
+                    writer.u16(color.red);
+                    writer.u16(color.blue);
+                    writer.u16(color.green);
+                    writer.u16(color.alpha);
+                }
+            }
+        }
+
+        Some(writer.data)
+    }
+
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    pub fn len(&self) -> usize {
+        self.settings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.settings.get(name).map(|setting| &setting.value)
+    }
+
+    pub fn setting(&self, name: &str) -> Option<&Setting> {
+        self.settings.get(name)
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i32> {
+        match self.get(name)? {
+            Value::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self, name: &str) -> Option<Color> {
+        match self.get(name)? {
+            Value::Color(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Setting)> {
+        self.settings
+            .iter()
+            .map(|(name, setting)| (name.as_str(), setting))
+    }
+
+    /// Sets `name` to `value`, marking it as changed in the current serial if the value differs.
+    pub fn set(&mut self, name: &str, value: Value) {
+        match self.settings.get_mut(name) {
+            Some(setting) if setting.value == value => {}
+            Some(setting) => {
+                setting.value = value;
+                setting.last_change_serial = self.serial;
+            }
+            None => {
+                self.settings.insert(
+                    name.to_owned(),
+                    Setting {
+                        value,
+                        last_change_serial: self.serial,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.settings.remove(name).map(|setting| setting.value)
+    }
+
+    /// Starts a new serial once the settings are published, for the changes that follow.
+    fn next_serial(&mut self) {
+        self.serial = self.serial.wrapping_add(1);
+    }
+
+    /// Names of the settings added, changed or removed since `previous`.
+    pub fn changes<'b>(&'b self, previous: &'b Settings) -> Vec<&'b str> {
+        let changed = self
+            .iter()
+            .filter(|(name, setting)| previous.get(name) != Some(&setting.value))
+            .map(|(name, _)| name);
+
+        let removed = previous
+            .iter()
+            .filter(|(name, _)| !self.settings.contains_key(*name))
+            .map(|(name, _)| name);
+
+        changed.chain(removed).collect()
+    }
+}
+
+/// Reads the settings of a screen and follows their changes.
+pub struct SettingsClient<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    manager_atoms: manager::Atoms,
+    selection: x::Atom,
+    owner: Option<x::Window>,
+}
+
+impl<'a> SettingsClient<'a> {
+    /// Looks the settings manager of `screen_nbr` up and selects the events needed to follow the
+    /// settings, including on the root window to notice a manager starting later.
+    ///
+    /// Returns `None` if the screen does not exist.
+    pub fn new(connection: &'a xcb::Connection, screen_nbr: i32) -> xcb::Result<Option<Self>> {
+        let root = match connection.get_setup().roots().nth(screen_nbr as usize) {
+            Some(screen) => screen.root(),
+            None => return Ok(None),
+        };
+
+        let event_mask = connection
+            .wait_for_reply(connection.send_request(&x::GetWindowAttributes { window: root }))?
+            .your_event_mask();
+
+        connection.send_request(&x::ChangeWindowAttributes {
+            window: root,
+            value_list: &[x::Cw::EventMask(
+                event_mask | x::EventMask::STRUCTURE_NOTIFY,
+            )],
+        });
+
+        let mut client = Self {
+            connection,
+            atoms: Atoms::intern_all(connection)?,
+            manager_atoms: manager::Atoms::intern_all(connection)?,
+            selection: selection_atom(connection, screen_nbr)?,
+            owner: None,
+        };
+
+        client.update_owner()?;
+
+        Ok(Some(client))
+    }
+
+    fn update_owner(&mut self) -> xcb::Result<()> {
+        self.connection.send_request(&x::GrabServer {});
+
+        // The server is ungrabbed before any error is returned.
+        let owner = manager::owner(self.connection, self.selection);
+
+        if let Ok(Some(owner)) = owner {
+            self.connection.send_request(&x::ChangeWindowAttributes {
+                window: owner,
+                value_list: &[x::Cw::EventMask(
+                    x::EventMask::PROPERTY_CHANGE | x::EventMask::STRUCTURE_NOTIFY,
+                )],
+            });
+        }
+
+        self.connection.send_request(&x::UngrabServer {});
+        self.owner = owner?;
+
+        Ok(())
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    /// The window of the settings manager, if one is running.
+    pub fn owner(&self) -> Option<x::Window> {
+        self.owner
+    }
+
+    /// Reads the current settings, empty if no manager is running or its property is invalid.
+    pub fn fetch(&self) -> xcb::Result<Settings> {
+        let owner = match self.owner {
+            Some(owner) => owner,
+            None => return Ok(Settings::new()),
+        };
+
+        let reply = self
+            .connection
+            .wait_for_reply(self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window: owner,
+                property: self.atoms.settings,
+                r#type: self.atoms.settings,
+                long_offset: 0,
+                long_length: u32::MAX,
+            }))?;
+
+        if reply.format() != 8 {
+            return Ok(Settings::new());
+        }
+
+        Ok(Settings::parse(reply.value()).unwrap_or_default())
+    }
+
+    /// Handles an event, returning the new settings if they may have changed.
+    pub fn handle_event(&mut self, event: &xcb::Event) -> xcb::Result<Option<Settings>> {
+        match event {
+            xcb::Event::X(x::Event::PropertyNotify(ev))
+                if Some(ev.window()) == self.owner && ev.atom() == self.atoms.settings =>
+            {
+                self.fetch().map(Some)
+            }
+            xcb::Event::X(x::Event::DestroyNotify(ev)) if Some(ev.window()) == self.owner => {
+                self.update_owner()?;
+                self.fetch().map(Some)
+            }
+            xcb::Event::X(x::Event::ClientMessage(ev)) => {
+                match Announcement::parse(&self.manager_atoms, ev) {
+                    Some(announcement) if announcement.selection == self.selection => {
+                        self.update_owner()?;
+                        self.fetch().map(Some)
+                    }
+                    _ => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Owns the settings selection of a screen and publishes settings.
+pub struct SettingsManager<'a> {
+    connection: &'a xcb::Connection,
+    atoms: Atoms,
+    manager: ManagerSelection<'a>,
+    settings: Settings,
+}
+
+impl<'a> SettingsManager<'a> {
+    /// Makes `window` the settings manager of `screen_nbr` and publishes an empty set of
+    /// settings, see [`ManagerSelection::acquire`] for `replace` and `events`.
+    pub fn acquire(
+        connection: &'a xcb::Connection,
+        screen_nbr: i32,
+        window: x::Window,
+        replace: Option<Duration>,
+        events: &mut Vec<xcb::Event>,
+    ) -> xcb::Result<Option<Self>> {
+        let selection = selection_atom(connection, screen_nbr)?;
+
+        let manager = match ManagerSelection::acquire(
+            connection, screen_nbr, selection, window, replace, events,
+        )? {
+            Some(manager) => manager,
+            None => return Ok(None),
+        };
+
+        let mut manager = Self {
+            connection,
+            atoms: Atoms::intern_all(connection)?,
+            manager,
+            settings: Settings::new(),
+        };
+
+        manager.publish();
+
+        Ok(Some(manager))
+    }
+
+    pub fn manager(&self) -> &ManagerSelection<'a> {
+        &self.manager
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Sets `name` to `value`, which is sent to clients by the next [`SettingsManager::publish`].
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.settings.set(name, value);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.settings.remove(name)
+    }
+
+    /// Writes the settings to the `_XSETTINGS_SETTINGS` property and starts a new serial.
+    ///
+    /// Returns `None`, and leaves the property alone, if the settings cannot be encoded.
+    pub fn publish(&mut self) -> Option<xcb::VoidCookie> {
+        let data = self.settings.encode(ByteOrder::native())?;
+
+        let cookie = self.connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: self.manager.window(),
+            property: self.atoms.settings,
+            r#type: self.atoms.settings,
+            data: &data,
+        });
+
+        self.settings.next_serial();
+
+        Some(cookie)
+    }
+
+    /// Whether `event` tells that another settings manager took over, see
+    /// [`ManagerSelection::is_lost`].
+    pub fn is_lost(&self, event: &xcb::Event) -> bool {
+        self.manager.is_lost(event)
+    }
+
+    /// The window publishing the settings.
+    pub fn window(&self) -> x::Window {
+        self.manager.window()
+    }
+
+    pub fn selection(&self) -> x::Atom {
+        self.manager.selection()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Gtk/Color`, `Net/ThemeName` and `Xft/DPI` at serial 5, least significant byte first.
+    fn lsb_fixture() -> Vec<u8> {
+        [
+            &[0, 0, 0, 0, 5, 0, 0, 0, 3, 0, 0, 0][..],
+            &[TYPE_COLOR, 0, 9, 0],
+            b"Gtk/Color\0\0\0",
+            &[4, 0, 0, 0, 0xff, 0xff, 0x00, 0x80, 0x00, 0x00, 0xff, 0xff],
+            &[TYPE_STRING, 0, 13, 0],
+            b"Net/ThemeName\0\0\0",
+            &[5, 0, 0, 0, 7, 0, 0, 0],
+            b"Adwaita\0",
+            &[TYPE_INTEGER, 0, 7, 0],
+            b"Xft/DPI\0",
+            &[2, 0, 0, 0, 0x00, 0x80, 0x01, 0x00],
+        ]
+        .concat()
+    }
+
+    /// The settings of [`lsb_fixture`], most significant byte first.
+    fn msb_fixture() -> Vec<u8> {
+        [
+            &[1, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 3][..],
+            &[TYPE_COLOR, 0, 0, 9],
+            b"Gtk/Color\0\0\0",
+            &[0, 0, 0, 4, 0xff, 0xff, 0x80, 0x00, 0x00, 0x00, 0xff, 0xff],
+            &[TYPE_STRING, 0, 0, 13],
+            b"Net/ThemeName\0\0\0",
+            &[0, 0, 0, 5, 0, 0, 0, 7],
+            b"Adwaita\0",
+            &[TYPE_INTEGER, 0, 0, 7],
+            b"Xft/DPI\0",
+            &[0, 0, 0, 2, 0x00, 0x01, 0x80, 0x00],
+        ]
+        .concat()
+    }
+
+    fn check_fixture(settings: &Settings) {
+        assert_eq!(settings.serial(), 5);
+        assert_eq!(settings.len(), 3);
+        assert_eq!(
+            settings.color("Gtk/Color"),
+            Some(Color {
+                red: 0xffff,
+                green: 0,
+                blue: 0x8000,
+                alpha: 0xffff,
+            })
+        );
+        assert_eq!(settings.string("Net/ThemeName"), Some("Adwaita"));
+        assert_eq!(settings.integer("Xft/DPI"), Some(96 * 1024));
+        assert_eq!(settings.setting("Gtk/Color").unwrap().last_change_serial, 4);
+        assert_eq!(settings.setting("Xft/DPI").unwrap().last_change_serial, 2);
+    }
+
+    #[test]
+    fn parse_both_byte_orders() {
+        let lsb = Settings::parse(&lsb_fixture()).unwrap();
+        let msb = Settings::parse(&msb_fixture()).unwrap();
+
+        check_fixture(&lsb);
+        assert_eq!(lsb, msb);
+    }
+
+    #[test]
+    fn encode_both_byte_orders() {
+        let settings = Settings::parse(&lsb_fixture()).unwrap();
+
+        assert_eq!(settings.encode(ByteOrder::LsbFirst), Some(lsb_fixture()));
+        assert_eq!(settings.encode(ByteOrder::MsbFirst), Some(msb_fixture()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut settings = Settings::new();
+        settings.set("Net/CursorBlinkTime", Value::Integer(-1));
+        settings.set("Gtk/FontName", Value::String("Sans 10".to_owned()));
+        settings.set("Net/Empty", Value::String(String::new()));
+        settings.set(
+            "Gtk/Color",
+            Value::Color(Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }),
+        );
+
+        for byte_order in [ByteOrder::LsbFirst, ByteOrder::MsbFirst] {
+            let data = settings.encode(byte_order).unwrap();
+
+            assert_eq!(data.len() % 4, 0);
+            assert_eq!(Settings::parse(&data), Some(settings.clone()));
+        }
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let data = lsb_fixture();
+
+        for length in 0..data.len() {
+            assert_eq!(Settings::parse(&data[..length]), None, "length {}", length);
+        }
+
+        assert_eq!(Settings::parse(&[2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn parse_padding() {
+        // Padding bytes are skipped whatever their value, and data past the settings is ignored.
+        let mut data = lsb_fixture();
+        data[25] = 0xff;
+        data.extend_from_slice(&[0xff; 8]);
+
+        check_fixture(&Settings::parse(&data).unwrap());
+
+        // A name without its padding reads the padding from the next field.
+        let data = [
+            &[0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0][..],
+            &[TYPE_INTEGER, 0, 3, 0],
+            b"Foo",
+            &[1, 0, 0, 0, 2, 0, 0, 0],
+        ]
+        .concat();
+
+        assert_eq!(Settings::parse(&data), None);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_types() {
+        let data = [
+            &[0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0][..],
+            &[3, 0, 3, 0],
+            b"Foo\0",
+            &[1, 0, 0, 0, 2, 0, 0, 0],
+        ]
+        .concat();
+
+        assert_eq!(Settings::parse(&data), None);
+    }
+
+    #[test]
+    fn encode_rejects_long_names() {
+        let mut settings = Settings::new();
+        settings.set(&"a".repeat(65536), Value::Integer(0));
+
+        assert_eq!(settings.encode(ByteOrder::native()), None);
+    }
+
+    #[test]
+    fn serials() {
+        let mut settings = Settings::new();
+        settings.set("Net/ThemeName", Value::String("Adwaita".to_owned()));
+        settings.set("Xft/DPI", Value::Integer(96 * 1024));
+        settings.next_serial();
+
+        // Setting the same value again is not a change.
+        settings.set("Xft/DPI", Value::Integer(96 * 1024));
+        settings.set("Net/ThemeName", Value::String("HighContrast".to_owned()));
+        settings.next_serial();
+
+        assert_eq!(settings.serial(), 2);
+        assert_eq!(settings.setting("Xft/DPI").unwrap().last_change_serial, 0);
+        assert_eq!(
+            settings
+                .setting("Net/ThemeName")
+                .unwrap()
+                .last_change_serial,
+            1
+        );
+
+        settings.serial = u32::MAX;
+        settings.next_serial();
+
+        assert_eq!(settings.serial(), 0);
+    }
+
+    #[test]
+    fn changes() {
+        let previous = Settings::parse(&lsb_fixture()).unwrap();
+        let mut settings = previous.clone();
+
+        assert!(settings.changes(&previous).is_empty());
+
+        settings.set("Xft/DPI", Value::Integer(120 * 1024));
+        settings.set("Net/ThemeName", Value::String("Adwaita".to_owned()));
+        settings.set("Xft/Antialias", Value::Integer(1));
+        settings.remove("Gtk/Color");
+
+        assert_eq!(
+            settings.changes(&previous),
+            ["Xft/Antialias", "Xft/DPI", "Gtk/Color"]
+        );
+
+        // A setting changing type is a change too.
+        settings.set("Net/ThemeName", Value::Integer(0));
+
+        assert!(settings.changes(&previous).contains(&"Net/ThemeName"));
+    }
+}